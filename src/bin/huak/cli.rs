@@ -1,20 +1,29 @@
 use crate::error::{CliResult, Error};
-use clap::{Command, CommandFactory, Parser, Subcommand};
+use clap::{Args, Command, CommandFactory, Parser, Subcommand};
 use clap_complete::{self, Shell};
 use huak::{
     ops::{
         activate_python_environment, add_project_dependencies,
-        add_project_optional_dependencies, build_project, clean_project,
+        add_project_group_dependencies, add_project_optional_dependencies,
+        audit_project, build_project, clean_cache, clean_project,
+        display_cache_dir, display_cache_info, display_dependency_chains,
+        display_licenses, display_outdated_dependencies, display_project_tree,
         display_project_version, export_dependencies_to_file, format_project,
-        init_app_project, init_lib_project, install_project_dependencies,
-        lint_project, list_python, new_app_project, new_lib_project,
+        init_app_project, init_lib_project,
+        install_locked_project_dependencies, install_project_dependencies,
+        lint_project, list_python, lock_project, login_to_index,
+        logout_of_index, new_app_project, new_lib_project, prune_cache,
         publish_project, remove_project_dependencies, run_command_str,
-        test_project, update_project_dependencies, use_python, AddOptions,
-        BuildOptions, CleanOptions, ExportOptions, FormatOptions, LintOptions,
-        PublishOptions, RemoveOptions, TestOptions, UpdateOptions,
+        sync_project, test_project, update_project_dependencies, use_python,
+        vendor_dependencies, AddOptions, AuditFormat, AuditOptions,
+        BuildOptions, CleanOptions, ExportFormat, ExportOptions, FormatOptions,
+        InstallLockedOptions, LicensesFormat, LicensesOptions, LintOptions,
+        LockOptions, LoginOptions, OutdatedOptions, PublishOptions,
+        RemoveOptions, SyncOptions, TestOptions, TreeFormat, TreeOptions,
+        UpdateOptions, VendorOptions,
     },
-    Config, Error as HuakError, HuakResult, InstallOptions, TerminalOptions,
-    Verbosity, Version, WorkspaceOptions,
+    Config, Error as HuakError, HuakResult, InstallOptions, InstallerKind,
+    PinStrategy, TerminalOptions, Verbosity, Version, WorkspaceOptions,
 };
 use std::{
     fs::File,
@@ -32,6 +41,12 @@ pub struct Cli {
     command: Commands,
     #[arg(short, long, global = true)]
     quiet: bool,
+    /// The installer to install packages with [native, pip, uv].
+    #[arg(long, global = true)]
+    installer: Option<InstallerKind>,
+    /// Work without network access, installing only from --find-links directories or the cache.
+    #[arg(long, global = true)]
+    offline: bool,
 }
 
 // List of commands.
@@ -44,19 +59,55 @@ enum Commands {
     Add {
         #[arg(num_args = 1.., required = true)]
         dependencies: Vec<Dependency>,
-        /// Adds an optional dependency group.
-        #[arg(long)]
+        /// Adds to a dependency group in [dependency-groups].
+        #[arg(long, conflicts_with = "optional")]
         group: Option<String>,
+        /// Adds to an optional dependency group (an extra).
+        #[arg(long)]
+        optional: Option<String>,
+        /// How to pin dependencies added without a version [exact, compatible, lower-bound, caret, none].
+        #[arg(long)]
+        pin: Option<PinStrategy>,
+        /// Install local project directories in editable mode.
+        #[arg(long, short)]
+        editable: bool,
+        #[command(flatten)]
+        install: InstallArgs,
         /// Pass trailing arguments with `--`.
         #[arg(last = true)]
         trailing: Option<Vec<String>>,
     },
+    /// Check the project's packages against an advisory database.
+    Audit {
+        /// Directory or zip archive of OSV advisories [default: $HUAK_ADVISORY_DB].
+        #[arg(long)]
+        db: Option<PathBuf>,
+        /// Ignore an advisory by its id or alias.
+        #[arg(long, value_name = "ID")]
+        ignore: Vec<String>,
+        /// Audit the packages pinned in huak.lock instead of the installed packages.
+        #[arg(long)]
+        locked: bool,
+        /// Output format [text, json, sarif].
+        #[arg(long, default_value = "text")]
+        format: AuditFormat,
+    },
+    /// Manage the credentials of package indexes.
+    Auth {
+        #[command(subcommand)]
+        command: Auth,
+    },
     /// Build tarball and wheel for the project.
     Build {
         /// Pass trailing arguments with `--`.
         #[arg(last = true)]
         trailing: Option<Vec<String>>,
     },
+    /// Manage the cache of wheels shared by every environment.
+    Cache {
+        #[command(subcommand)]
+        command: Cache,
+    },
     /// Remove tarball and wheel from the built project.
     Clean {
         #[arg(long, required = false)]
@@ -68,15 +119,24 @@ enum Commands {
     },
     /// Export dependencies to a file.
     Export {
-        /// The output file to write the dependencies to.
-        #[arg(long, default_value = "requirements.txt")]
-        output: String,
+        /// The output file to write the dependencies to [default: requirements.txt, constraints.txt, pylock.toml, environment.yml, bom.cdx.json or bom.spdx.json].
+        #[arg(long)]
+        output: Option<String>,
+        /// Format of the file [requirements-txt, pinned-requirements-txt, constraints-txt, pylock-toml, environment-yml, cyclonedx-json, spdx-json].
+        #[arg(long, default_value = "requirements-txt")]
+        format: ExportFormat,
+        /// Export the distributions pinned in huak.lock instead of the installed ones (SBOMs). Other locked formats always read huak.lock.
+        #[arg(long)]
+        locked: bool,
         /// Include specific dependencies in the export.
         #[arg(long)]
         include: Option<String>,
         /// Exclude specific dependencies from the export.
         #[arg(long)]
         exclude: Option<String>,
+        /// Export a requirements file for each Python version or interpreter path.
+        #[arg(long, num_args = 1..)]
+        python: Option<Vec<String>>,
     },
     /// Generates a shell completion script for supported shells.
     Completion {
@@ -120,13 +180,30 @@ enum Commands {
     },
     /// Install the dependencies of an existing project.
     Install {
-        /// Install optional dependency groups
+        /// Install optional dependency groups and [dependency-groups]
         #[arg(long, num_args = 1..)]
         groups: Option<Vec<String>>,
+        /// Install exactly what's pinned in huak.lock, failing if it's out of date.
+        #[arg(long, conflicts_with = "frozen")]
+        locked: bool,
+        /// Install exactly what's pinned in huak.lock without checking if it's up to date.
+        #[arg(long, conflicts_with = "locked")]
+        frozen: bool,
+        #[command(flatten)]
+        install: InstallArgs,
         /// Pass trailing arguments with `--`.
         #[arg(last = true)]
         trailing: Option<Vec<String>>,
     },
+    /// Display the licenses of the packages installed to the project's environment.
+    Licenses {
+        /// Output format [table, csv, json].
+        #[arg(long, default_value = "table")]
+        format: LicensesFormat,
+        /// Check the licenses against the [tool.huak.licenses] policy.
+        #[arg(long)]
+        check: bool,
+    },
     /// Lint the project's Python code.
     Lint {
         /// Address any fixable lints.
@@ -139,6 +216,14 @@ enum Commands {
         #[arg(last = true)]
         trailing: Option<Vec<String>>,
     },
+    /// Lock the project's dependencies to huak.lock.
+    Lock {
+        #[command(flatten)]
+        install: InstallArgs,
+        /// Pass trailing arguments with `--`.
+        #[arg(last = true)]
+        trailing: Option<Vec<String>>,
+    },
     /// Create a new project at <path>.
     New {
         /// Use an application template.
@@ -153,6 +238,15 @@ enum Commands {
         #[arg(long)]
         no_vcs: bool,
     },
+    /// Display the project's dependencies with newer releases.
+    Outdated {
+        /// Print the report as JSON.
+        #[arg(long)]
+        json: bool,
+        /// URL or path of the package index to check [default: $PIP_INDEX_URL or PyPI].
+        #[arg(long)]
+        index_url: Option<String>,
+    },
     /// Builds and uploads current project to a registry.
     Publish {
         /// Pass trailing arguments with `--`.
@@ -168,6 +262,11 @@ enum Commands {
     Remove {
         #[arg(num_args = 1.., required = true)]
         dependencies: Vec<String>,
+        /// Display the files that would be uninstalled without changing anything.
+        #[arg(long)]
+        dry_run: bool,
+        #[command(flatten)]
+        install: InstallArgs,
         /// Pass trailing arguments with `--`.
         #[arg(last = true)]
         trailing: Option<Vec<String>>,
@@ -177,22 +276,158 @@ enum Commands {
         #[arg(trailing_var_arg = true)]
         command: Vec<String>,
     },
+    /// Sync the environment with the project's dependencies.
+    Sync {
+        /// Sync optional dependency groups and [dependency-groups]
+        #[arg(long, num_args = 1..)]
+        groups: Option<Vec<String>>,
+        /// Exclude the dev dependency group.
+        #[arg(long)]
+        no_dev: bool,
+        /// Display what would be installed and uninstalled without changing the environment.
+        #[arg(long)]
+        dry_run: bool,
+        #[command(flatten)]
+        install: InstallArgs,
+        /// Pass trailing arguments with `--`.
+        #[arg(last = true)]
+        trailing: Option<Vec<String>>,
+    },
     /// Test the project's Python code.
     Test {
         /// Pass trailing arguments with `--`.
         #[arg(last = true)]
         trailing: Option<Vec<String>>,
     },
+    /// Display the project's dependency tree.
+    Tree {
+        /// Show the packages that depend on a package instead.
+        #[arg(long, value_name = "PACKAGE")]
+        invert: Option<String>,
+        /// Maximum depth of the tree.
+        #[arg(long)]
+        depth: Option<usize>,
+        /// Only show packages required by more than one dependent.
+        #[arg(long, conflicts_with = "invert")]
+        duplicates: bool,
+        /// Output format [text, json, dot, mermaid].
+        #[arg(long, default_value = "text")]
+        format: TreeFormat,
+    },
     /// Update the project's dependencies.
     Update {
         #[arg(num_args = 0..)]
         dependencies: Option<Vec<String>>,
+        #[command(flatten)]
+        install: InstallArgs,
+        /// Pass trailing arguments with `--`.
+        #[arg(last = true)]
+        trailing: Option<Vec<String>>,
+    },
+    /// Download the project's distributions to a directory for offline installs.
+    Vendor {
+        /// The directory to download the distributions to.
+        #[arg(default_value = "wheelhouse")]
+        path: PathBuf,
+        #[command(flatten)]
+        install: InstallArgs,
         /// Pass trailing arguments with `--`.
         #[arg(last = true)]
         trailing: Option<Vec<String>>,
     },
     /// Display the version of the project.
     Version,
+    /// Display why a package is installed in the project's environment.
+    Why {
+        /// The name of the package.
+        package: String,
+    },
+}
+
+/// Options for installing packages. They're added to the project's `[tool.huak]` options.
+#[derive(Args)]
+struct InstallArgs {
+    /// The URL or path of the package index.
+    #[arg(long)]
+    index_url: Option<String>,
+    /// The URL or path of another package index.
+    #[arg(long = "extra-index-url")]
+    extra_index_urls: Vec<String>,
+    /// A directory or page listing distributions to install from.
+    #[arg(long)]
+    find_links: Vec<String>,
+    /// Don't install the dependencies of the packages installed.
+    #[arg(long)]
+    no_deps: bool,
+    /// Allow pre-release versions.
+    #[arg(long)]
+    pre: bool,
+    /// A package never installed from a wheel (:all: for every package).
+    #[arg(long)]
+    no_binary: Vec<String>,
+    /// A package only installed from a wheel (:all: for every package).
+    #[arg(long)]
+    only_binary: Vec<String>,
+    /// Don't use the package cache.
+    #[arg(long)]
+    no_cache: bool,
+    /// A host to trust even without valid HTTPS.
+    #[arg(long = "trusted-host")]
+    trusted_hosts: Vec<String>,
+}
+
+impl InstallArgs {
+    /// Get the `InstallOptions` with trailing arguments passed to the installer as they are.
+    fn options(self, values: Option<Vec<String>>) -> InstallOptions {
+        InstallOptions {
+            index_url: self.index_url,
+            extra_index_urls: self.extra_index_urls,
+            find_links: self.find_links,
+            no_deps: self.no_deps,
+            pre: self.pre,
+            no_binary: self.no_binary,
+            only_binary: self.only_binary,
+            no_cache: self.no_cache,
+            trusted_hosts: self.trusted_hosts,
+            values,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Auth {
+    /// Store the credentials of a package index.
+    Login {
+        /// The name of an index in [tool.huak.indexes] or an index URL.
+        index: String,
+        /// The username to log in with. Prompted for if it's not given.
+        #[arg(long, short)]
+        username: Option<String>,
+        /// The password to log in with. Prompted for if it's not given.
+        #[arg(long, short)]
+        password: Option<String>,
+        /// Store the password in the system keyring instead of the netrc file.
+        #[arg(long)]
+        keyring: bool,
+    },
+    /// Remove the stored credentials of a package index.
+    Logout {
+        /// The name of an index in [tool.huak.indexes] or an index URL.
+        index: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum Cache {
+    /// Display the cache directory.
+    Dir,
+    /// Display the number of wheels cached and the cache's size.
+    Info,
+    /// Remove everything from the cache.
+    Clean,
+    /// Remove unpacked wheels no environment uses.
+    Prune,
 }
 
 #[derive(Subcommand)]
@@ -219,6 +454,8 @@ impl Cli {
             workspace_root: cwd.to_path_buf(),
             cwd,
             terminal_options: TerminalOptions { verbosity },
+            installer: self.installer,
+            offline: self.offline,
         };
 
         let res = match self.command {
@@ -226,20 +463,42 @@ impl Cli {
             Commands::Add {
                 dependencies,
                 group,
+                optional,
+                pin,
+                editable,
+                install,
                 trailing,
             } => {
                 let options = AddOptions {
-                    install_options: InstallOptions { values: trailing },
+                    pin,
+                    editable,
+                    install_options: install.options(trailing),
                 };
-                add(dependencies, group, &config, &options)
+                add(dependencies, group, optional, &config, &options)
+            }
+            Commands::Audit {
+                db,
+                ignore,
+                locked,
+                format,
+            } => {
+                let options = AuditOptions {
+                    db,
+                    ignore,
+                    locked,
+                    format,
+                };
+                audit(&config, &options)
             }
             Commands::Build { trailing } => {
                 let options = BuildOptions {
                     values: trailing,
-                    install_options: InstallOptions { values: None },
+                    install_options: InstallOptions::default(),
                 };
                 build(&config, &options)
             }
+            Commands::Auth { command } => auth(command, &config),
+            Commands::Cache { command } => cache(command, &config),
             Commands::Clean {
                 include_pyc,
                 include_pycache,
@@ -252,13 +511,21 @@ impl Cli {
             }
             Commands::Export {
                 output,
+                format,
+                locked,
                 include,
                 exclude,
+                python,
             } => {
                 let options = ExportOptions {
                     include,
                     exclude,
-                    output_file: output,
+                    output_file: output.unwrap_or_else(|| {
+                        format.default_file_name().to_string()
+                    }),
+                    python,
+                    format,
+                    locked,
                 };
                 export(&config, &options)
             }
@@ -278,7 +545,7 @@ impl Cli {
                 let options = LintOptions {
                     values: trailing,
                     include_types: false,
-                    install_options: InstallOptions { values: None },
+                    install_options: InstallOptions::default(),
                 };
                 fix(&config, &options)
             }
@@ -293,7 +560,7 @@ impl Cli {
                 }
                 let options = FormatOptions {
                     values: Some(args),
-                    install_options: InstallOptions { values: None },
+                    install_options: InstallOptions::default(),
                 };
                 fmt(&config, &options)
             }
@@ -302,9 +569,27 @@ impl Cli {
                 let options = WorkspaceOptions { uses_git: !no_vcs };
                 init(app, lib, &config, &options)
             }
-            Commands::Install { groups, trailing } => {
-                let options = InstallOptions { values: trailing };
-                install(groups, &config, &options)
+            Commands::Install {
+                groups,
+                locked,
+                frozen,
+                install: install_args,
+                trailing,
+            } => {
+                let install_options = install_args.options(trailing);
+                if locked || frozen {
+                    let options = InstallLockedOptions {
+                        frozen,
+                        install_options,
+                    };
+                    install_locked(groups, &config, &options)
+                } else {
+                    install(groups, &config, &install_options)
+                }
+            }
+            Commands::Licenses { format, check } => {
+                let options = LicensesOptions { format, check };
+                licenses(&config, &options)
             }
             Commands::Lint {
                 fix,
@@ -322,10 +607,16 @@ impl Cli {
                 let options = LintOptions {
                     values: Some(args),
                     include_types: !no_types,
-                    install_options: InstallOptions { values: None },
+                    install_options: InstallOptions::default(),
                 };
                 lint(&config, &options)
             }
+            Commands::Lock { install, trailing } => {
+                let options = LockOptions {
+                    install_options: install.options(trailing),
+                };
+                lock(&config, &options)
+            }
             Commands::New {
                 path,
                 app,
@@ -336,41 +627,89 @@ impl Cli {
                 let options = WorkspaceOptions { uses_git: !no_vcs };
                 new(app, lib, &config, &options)
             }
+            Commands::Outdated { json, index_url } => {
+                let options = OutdatedOptions { json, index_url };
+                outdated(&config, &options)
+            }
             Commands::Publish { trailing } => {
                 let options = PublishOptions {
                     values: trailing,
-                    install_options: InstallOptions { values: None },
+                    install_options: InstallOptions::default(),
                 };
                 publish(&config, &options)
             }
             Commands::Python { command } => python(command, &config),
             Commands::Remove {
                 dependencies,
+                dry_run,
+                install,
                 trailing,
             } => {
                 let options = RemoveOptions {
-                    install_options: InstallOptions { values: trailing },
+                    dry_run,
+                    install_options: install.options(trailing),
                 };
                 remove(dependencies, &config, &options)
             }
             Commands::Run { command } => run(command, &config),
+            Commands::Sync {
+                groups,
+                no_dev,
+                dry_run,
+                install,
+                trailing,
+            } => {
+                let options = SyncOptions {
+                    dry_run,
+                    no_dev,
+                    install_options: install.options(trailing),
+                };
+                sync(groups, &config, &options)
+            }
             Commands::Test { trailing } => {
                 let options = TestOptions {
                     values: trailing,
-                    install_options: InstallOptions { values: None },
+                    install_options: InstallOptions::default(),
                 };
                 test(&config, &options)
             }
+            Commands::Tree {
+                invert,
+                depth,
+                duplicates,
+                format,
+            } => {
+                let options = TreeOptions {
+                    invert,
+                    depth,
+                    duplicates,
+                    format,
+                };
+                tree(&config, &options)
+            }
             Commands::Update {
                 dependencies,
+                install,
                 trailing,
             } => {
                 let options = UpdateOptions {
-                    install_options: InstallOptions { values: trailing },
+                    install_options: install.options(trailing),
                 };
                 update(dependencies, &config, &options)
             }
+            Commands::Vendor {
+                path,
+                install,
+                trailing,
+            } => {
+                let options = VendorOptions {
+                    path,
+                    install_options: install.options(trailing),
+                };
+                vendor(&config, &options)
+            }
             Commands::Version => version(&config),
+            Commands::Why { package } => why(&package, &config),
         };
 
         match res {
@@ -391,6 +730,7 @@ fn activate(config: &Config) -> HuakResult<()> {
 fn add(
     dependencies: Vec<Dependency>,
     group: Option<String>,
+    optional: Option<String>,
     config: &Config,
     options: &AddOptions,
 ) -> HuakResult<()> {
@@ -398,11 +738,46 @@ fn add(
         .iter()
         .map(|item| item.to_string())
         .collect::<Vec<String>>();
-    match group.as_ref() {
-        Some(it) => {
+    match (group.as_ref(), optional.as_ref()) {
+        (Some(it), _) => {
+            add_project_group_dependencies(&deps, it, config, options)
+        }
+        (None, Some(it)) => {
             add_project_optional_dependencies(&deps, it, config, options)
         }
-        None => add_project_dependencies(&deps, config, options),
+        (None, None) => add_project_dependencies(&deps, config, options),
+    }
+}
+
+fn audit(config: &Config, options: &AuditOptions) -> HuakResult<()> {
+    audit_project(config, options)
+}
+
+fn auth(command: Auth, config: &Config) -> HuakResult<()> {
+    match command {
+        Auth::Login {
+            index,
+            username,
+            password,
+            keyring,
+        } => {
+            let username = match username {
+                Some(it) => it,
+                None => prompt("Username", false)?,
+            };
+            let password = match password {
+                Some(it) => it,
+                None => prompt("Password", true)?,
+            };
+            let options = LoginOptions {
+                index,
+                username,
+                password,
+                keyring,
+            };
+            login_to_index(config, &options)
+        }
+        Auth::Logout { index } => logout_of_index(config, &index),
     }
 }
 
@@ -447,10 +822,26 @@ fn install(
     install_project_dependencies(groups.as_ref(), config, options)
 }
 
+fn install_locked(
+    groups: Option<Vec<String>>,
+    config: &Config,
+    options: &InstallLockedOptions,
+) -> HuakResult<()> {
+    install_locked_project_dependencies(groups.as_ref(), config, options)
+}
+
+fn licenses(config: &Config, options: &LicensesOptions) -> HuakResult<()> {
+    display_licenses(config, options)
+}
+
 fn lint(config: &Config, options: &LintOptions) -> HuakResult<()> {
     lint_project(config, options)
 }
 
+fn lock(config: &Config, options: &LockOptions) -> HuakResult<()> {
+    lock_project(config, options)
+}
+
 fn new(
     app: bool,
     _lib: bool,
@@ -464,10 +855,23 @@ fn new(
     }
 }
 
+fn outdated(config: &Config, options: &OutdatedOptions) -> HuakResult<()> {
+    display_outdated_dependencies(config, options)
+}
+
 fn publish(config: &Config, options: &PublishOptions) -> HuakResult<()> {
     publish_project(config, options)
 }
 
+fn cache(command: Cache, config: &Config) -> HuakResult<()> {
+    match command {
+        Cache::Dir => display_cache_dir(config),
+        Cache::Info => display_cache_info(config),
+        Cache::Clean => clean_cache(config),
+        Cache::Prune => prune_cache(config),
+    }
+}
+
 fn python(command: Python, config: &Config) -> HuakResult<()> {
     match command {
         Python::List => list_python(config),
@@ -487,10 +891,22 @@ fn run(command: Vec<String>, config: &Config) -> HuakResult<()> {
     run_command_str(&command.join(" "), config)
 }
 
+fn sync(
+    groups: Option<Vec<String>>,
+    config: &Config,
+    options: &SyncOptions,
+) -> HuakResult<()> {
+    sync_project(groups.as_ref(), config, options)
+}
+
 fn test(config: &Config, options: &TestOptions) -> HuakResult<()> {
     test_project(config, options)
 }
 
+fn tree(config: &Config, options: &TreeOptions) -> HuakResult<()> {
+    display_project_tree(config, options)
+}
+
 fn update(
     dependencies: Option<Vec<String>>,
     config: &Config,
@@ -499,10 +915,18 @@ fn update(
     update_project_dependencies(dependencies, config, options)
 }
 
+fn vendor(config: &Config, options: &VendorOptions) -> HuakResult<()> {
+    vendor_dependencies(config, options)
+}
+
 fn version(config: &Config) -> HuakResult<()> {
     display_project_version(config)
 }
 
+fn why(package: &str, config: &Config) -> HuakResult<()> {
+    display_dependency_chains(package, config)
+}
+
 fn completion(options: &CompletionOptions) -> HuakResult<()> {
     if (options.install || options.uninstall) && options.shell.is_none() {
         Err(HuakError::HuakConfigurationError(
@@ -524,6 +948,41 @@ struct CompletionOptions {
     uninstall: bool,
 }
 
+/// Read a line from stdin after printing a prompt to stderr. Hidden input isn't echoed
+/// when stdin is a terminal.
+fn prompt(label: &str, hidden: bool) -> HuakResult<String> {
+    eprint!("{label}: ");
+    std::io::stderr().flush()?;
+
+    // `stty` only succeeds when stdin is a terminal.
+    let stty = |arg: &str| {
+        std::process::Command::new("stty")
+            .arg(arg)
+            .stdin(std::process::Stdio::inherit())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .map_or(false, |it| it.success())
+    };
+    let echo_off = hidden && cfg!(unix) && stty("-echo");
+    let mut line = String::new();
+    let res = std::io::stdin().read_line(&mut line);
+    if echo_off {
+        stty("echo");
+        eprintln!();
+    }
+    res?;
+
+    let value = line.trim_end_matches(['\r', '\n']).to_string();
+    if value.is_empty() {
+        return Err(HuakError::HuakConfigurationError(format!(
+            "{} is required",
+            label.to_lowercase()
+        )));
+    }
+
+    Ok(value)
+}
+
 fn generate_shell_completion_script(shell: Option<Shell>) {
     let mut cmd = Cli::command();
     clap_complete::generate(
@@ -661,13 +1120,22 @@ impl FromStr for Dependency {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.replace('@', "==")))
+        // `name@version` is shorthand for `name==version`. Anything else with an `@` is a
+        // direct reference like `name @ git+https://...` and is kept as-is.
+        match s.split_once('@') {
+            Some((name, version))
+                if !name.contains(' ') && !version.contains(['/', ':']) =>
+            {
+                Ok(Self(format!("{name}=={version}")))
+            }
+            _ => Ok(Self(s.to_string())),
+        }
     }
 }
 
-impl ToString for Dependency {
-    fn to_string(&self) -> String {
-        self.0.to_owned()
+impl std::fmt::Display for Dependency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
@@ -12,7 +12,10 @@ mod error;
 
 /// Launch Huak's cli process.
 pub fn main() -> ExitCode {
-    setup_panic!();
+    #[allow(deprecated)]
+    {
+        setup_panic!();
+    }
 
     match Cli::parse().run() {
         Ok(0) => ExitCode::SUCCESS,
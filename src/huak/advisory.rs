@@ -0,0 +1,448 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use pep440_rs::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::{package::normalized_package_name, Error, HuakResult};
+
+const ADVISORY_DB_ENV_VAR: &str = "HUAK_ADVISORY_DB";
+const PYPI_ECOSYSTEM: &str = "PyPI";
+
+/// A security advisory in the OSV format (https://ossf.github.io/osv-schema/).
+///
+/// Only the fields used to match packages and report findings are read.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Advisory {
+    pub id: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawn: Option<String>,
+    #[serde(default)]
+    pub affected: Vec<Affected>,
+    #[serde(default)]
+    pub severity: Vec<Severity>,
+}
+
+/// A package an `Advisory` affects with the versions it affects.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Affected {
+    pub package: AffectedPackage,
+    #[serde(default)]
+    pub ranges: Vec<AffectedRange>,
+    /// Versions listed explicitly.
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct AffectedPackage {
+    pub ecosystem: String,
+    pub name: String,
+}
+
+/// A range of affected versions described by the versions that introduced and fixed it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct AffectedRange {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub events: Vec<RangeEvent>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RangeEvent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub introduced: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_affected: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Severity {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub score: String,
+}
+
+/// An `Advisory` that affects a version of a package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdvisoryMatch<'a> {
+    pub advisory: &'a Advisory,
+    /// The affected versions formatted as version specifiers, like `>=2.0, <2.1`.
+    pub affected: Vec<String>,
+    /// The versions that fix the advisory.
+    pub fixed: Vec<Version>,
+}
+
+/// A database of `Advisory`s for PyPI packages read from disk.
+///
+/// The database is a directory of OSV .json files (searched recursively) or a zip archive
+/// of them, like the ecosystem exports osv.dev publishes, so that it can be synced
+/// separately from the machines that use it. Files that can't be read as an `Advisory`
+/// are skipped.
+#[derive(Debug, Default)]
+pub struct AdvisoryDatabase {
+    advisories: Vec<Advisory>,
+    /// Indexes of the `advisories` by the normalized names of the packages they affect.
+    packages: HashMap<String, Vec<usize>>,
+    /// The files that couldn't be read as an `Advisory` and why.
+    invalid: Vec<(String, Error)>,
+}
+
+impl AdvisoryDatabase {
+    /// Read an `AdvisoryDatabase` from a directory or a zip archive.
+    pub fn from_path<T: AsRef<Path>>(path: T) -> HuakResult<AdvisoryDatabase> {
+        let path = path.as_ref();
+        let mut db = AdvisoryDatabase::default();
+
+        if path.is_dir() {
+            let pattern = format!(
+                "{}/**/*.json",
+                glob::Pattern::escape(&path.to_string_lossy())
+            );
+            for entry in glob::glob(&pattern)? {
+                let entry = entry?;
+                let advisory = std::fs::read_to_string(&entry)
+                    .map_err(Error::from)
+                    .and_then(|it| parse_advisory(&it));
+                db.insert_or_skip(entry.display().to_string(), advisory);
+            }
+        } else if path.exists() {
+            let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+            for i in 0..archive.len() {
+                let mut file = archive.by_index(i)?;
+                if !file.is_file() || !file.name().ends_with(".json") {
+                    continue;
+                }
+                let name = file.name().to_string();
+                let mut contents = String::new();
+                let advisory = file
+                    .read_to_string(&mut contents)
+                    .map_err(Error::from)
+                    .and_then(|_| parse_advisory(&contents));
+                db.insert_or_skip(name, advisory);
+            }
+        } else {
+            return Err(Error::AdvisoryDatabaseNotFound);
+        }
+
+        Ok(db)
+    }
+
+    /// Get the path to the advisory database from the `HUAK_ADVISORY_DB` environment variable.
+    pub fn path_from_env() -> Option<PathBuf> {
+        std::env::var_os(ADVISORY_DB_ENV_VAR).map(PathBuf::from)
+    }
+
+    /// Get the number of `Advisory`s in the database.
+    pub fn len(&self) -> usize {
+        self.advisories.len()
+    }
+
+    /// Get a warning for each file that couldn't be read as an `Advisory`.
+    pub fn warnings(&self) -> Vec<String> {
+        self.invalid
+            .iter()
+            .map(|(source, e)| format!("skipped {source} ({e})"))
+            .collect()
+    }
+
+    /// Find the `Advisory`s that affect a version of a package.
+    pub fn matches(
+        &self,
+        name: &str,
+        version: &Version,
+    ) -> Vec<AdvisoryMatch<'_>> {
+        let name = normalized_package_name(name);
+        let Some(indexes) = self.packages.get(&name) else {
+            return Vec::new();
+        };
+
+        indexes
+            .iter()
+            .map(|i| &self.advisories[*i])
+            .filter_map(|advisory| {
+                let affected = advisory
+                    .affected
+                    .iter()
+                    .filter(|it| is_pypi_package(it, &name))
+                    .collect::<Vec<_>>();
+                if !affected.iter().any(|it| affects(it, version)) {
+                    return None;
+                }
+                let mut ranges = Vec::new();
+                let mut fixed = Vec::new();
+                for it in affected {
+                    ranges.extend(specifiers(it));
+                    fixed.extend(fixed_versions(it));
+                }
+                fixed.sort();
+                fixed.dedup();
+
+                Some(AdvisoryMatch {
+                    advisory,
+                    affected: ranges,
+                    fixed,
+                })
+            })
+            .collect()
+    }
+
+    /// Add an `Advisory` read from a file, or skip the file if it couldn't be read.
+    fn insert_or_skip(
+        &mut self,
+        source: String,
+        advisory: HuakResult<Advisory>,
+    ) {
+        match advisory {
+            Ok(it) => self.insert(it),
+            Err(e) => self.invalid.push((source, e)),
+        }
+    }
+
+    /// Add an `Advisory` for each PyPI package it affects. Withdrawn advisories are ignored.
+    fn insert(&mut self, advisory: Advisory) {
+        if advisory.withdrawn.is_some() {
+            return;
+        }
+        let mut names = advisory
+            .affected
+            .iter()
+            .filter(|it| it.package.ecosystem == PYPI_ECOSYSTEM)
+            .map(|it| normalized_package_name(&it.package.name))
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            return;
+        }
+        names.sort();
+        names.dedup();
+
+        let index = self.advisories.len();
+        self.advisories.push(advisory);
+        for name in names {
+            self.packages.entry(name).or_default().push(index);
+        }
+    }
+}
+
+fn parse_advisory(contents: &str) -> HuakResult<Advisory> {
+    serde_json::from_str(contents)
+        .map_err(|e| Error::InvalidAdvisory(e.to_string()))
+}
+
+fn is_pypi_package(affected: &Affected, name: &str) -> bool {
+    affected.package.ecosystem == PYPI_ECOSYSTEM
+        && normalized_package_name(&affected.package.name) == name
+}
+
+/// Parse the version of a range event. "0" is the start of every range.
+fn event_version(value: &str) -> Option<Version> {
+    Version::from_str(value).ok()
+}
+
+/// Get the events of a range with versions (git ranges use commits) sorted by version.
+fn sorted_events(range: &AffectedRange) -> Vec<(Version, &RangeEvent)> {
+    if range.kind == "GIT" {
+        return Vec::new();
+    }
+    let mut events = range
+        .events
+        .iter()
+        .filter_map(|event| {
+            let value = event
+                .introduced
+                .as_ref()
+                .or(event.fixed.as_ref())
+                .or(event.last_affected.as_ref())?;
+            Some((event_version(value)?, event))
+        })
+        .collect::<Vec<_>>();
+    events.sort_by(|a, b| a.0.cmp(&b.0));
+
+    events
+}
+
+/// Check if a version is affected following the OSV evaluation of ranges: a version is
+/// affected if the last event at or below it introduced the advisory.
+fn affects(affected: &Affected, version: &Version) -> bool {
+    if affected
+        .versions
+        .iter()
+        .filter_map(|it| event_version(it))
+        .any(|it| &it == version)
+    {
+        return true;
+    }
+
+    affected.ranges.iter().any(|range| {
+        let mut is_affected = false;
+        for (at, event) in sorted_events(range) {
+            if event.introduced.is_some() && version >= &at {
+                is_affected = true;
+            } else if (event.fixed.is_some() && version >= &at)
+                || (event.last_affected.is_some() && version > &at)
+            {
+                is_affected = false;
+            }
+        }
+        is_affected
+    })
+}
+
+/// Format the affected versions as version specifiers.
+fn specifiers(affected: &Affected) -> Vec<String> {
+    let mut specifiers = Vec::new();
+    for range in affected.ranges.iter() {
+        let mut lower: Option<&str> = None;
+        for (_, event) in sorted_events(range) {
+            if let Some(it) = event.introduced.as_deref() {
+                lower = Some(it);
+                continue;
+            }
+            let Some(start) = lower.take() else {
+                continue;
+            };
+            let upper = match (&event.fixed, &event.last_affected) {
+                (Some(it), _) => format!("<{it}"),
+                (None, Some(it)) => format!("<={it}"),
+                (None, None) => continue,
+            };
+            specifiers.push(specifier(start, Some(upper)));
+        }
+        if let Some(start) = lower {
+            specifiers.push(specifier(start, None));
+        }
+    }
+    specifiers.extend(affected.versions.iter().map(|it| format!("=={it}")));
+
+    specifiers
+}
+
+fn specifier(introduced: &str, upper: Option<String>) -> String {
+    match (introduced, upper) {
+        ("0", Some(upper)) => upper,
+        ("0", None) => "*".to_string(),
+        (start, Some(upper)) => format!(">={start}, {upper}"),
+        (start, None) => format!(">={start}"),
+    }
+}
+
+fn fixed_versions(affected: &Affected) -> Vec<Version> {
+    affected
+        .ranges
+        .iter()
+        .flat_map(|range| range.events.iter())
+        .filter_map(|event| event.fixed.as_deref().and_then(event_version))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_resources_dir_path;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn versions(values: &[&str]) -> Vec<Version> {
+        values
+            .iter()
+            .map(|it| Version::from_str(it).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn advisory_matches() {
+        let db = AdvisoryDatabase::from_path(
+            test_resources_dir_path().join("mock-advisories"),
+        )
+        .unwrap();
+        let ids = |name: &str, version: &str| {
+            db.matches(name, &Version::from_str(version).unwrap())
+                .iter()
+                .map(|it| it.advisory.id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(db.len(), 3);
+        assert_eq!(ids("mock_utils", "1.0.0"), ["MOCK-2024-1"]);
+        assert!(ids("mock-utils", "1.5.0").is_empty());
+        assert_eq!(ids("mock-utils", "2.0.0"), ["MOCK-2024-3"]);
+        assert_eq!(ids("mock-web", "1.1"), ["MOCK-2024-2"]);
+        assert!(ids("mock-web", "1.2").is_empty());
+        assert_eq!(ids("mock-web", "2.1"), ["MOCK-2024-2"]);
+        assert!(ids("mock-web", "2.1.1").is_empty());
+
+        let web = db.matches("mock-web", &Version::from_str("2.0").unwrap());
+        assert_eq!(web[0].affected, [">=1.0, <1.2", ">=2.0, <=2.1"]);
+        assert_eq!(web[0].fixed, versions(&["1.2"]));
+    }
+
+    #[test]
+    fn advisory_database_archive() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("all.zip");
+        let mut archive = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::FileOptions::default();
+        for entry in std::fs::read_dir(
+            test_resources_dir_path()
+                .join("mock-advisories")
+                .join("PyPI"),
+        )
+        .unwrap()
+        {
+            let entry = entry.unwrap();
+            archive
+                .start_file(entry.file_name().to_string_lossy(), options)
+                .unwrap();
+            archive
+                .write_all(&std::fs::read(entry.path()).unwrap())
+                .unwrap();
+        }
+        archive.finish().unwrap();
+
+        let db = AdvisoryDatabase::from_path(&path).unwrap();
+
+        assert_eq!(db.len(), 3);
+        assert_eq!(
+            db.matches("mock-utils", &Version::from_str("0.9").unwrap())
+                .len(),
+            2
+        );
+        assert!(matches!(
+            AdvisoryDatabase::from_path(dir.path().join("missing")),
+            Err(Error::AdvisoryDatabaseNotFound)
+        ));
+    }
+
+    #[test]
+    fn advisory_database_skips_invalid_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("[advisories]");
+        let source = test_resources_dir_path().join("mock-advisories");
+        crate::fs::copy_dir(&source, &root).unwrap();
+        std::fs::write(root.join("PyPI").join("broken.json"), "{").unwrap();
+        std::fs::write(root.join("unexpected.json"), r#"{"affected": 1}"#)
+            .unwrap();
+
+        let db = AdvisoryDatabase::from_path(&root).unwrap();
+
+        assert_eq!(db.len(), 3);
+        let warnings = db.warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().any(|it| it.contains("broken.json")));
+        assert!(warnings.iter().any(|it| it.contains("unexpected.json")));
+    }
+}
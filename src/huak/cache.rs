@@ -0,0 +1,494 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::{
+    distribution::{CoreMetadata, WheelFilename},
+    index::{wheel_metadata, DistributionFile, PackageIndex},
+    installer::unpack_wheel,
+    package::normalized_package_name,
+    Error, HuakResult,
+};
+
+const CACHE_DIR_ENV_VAR: &str = "HUAK_CACHE_DIR";
+/// Used to name the temporary entries of the `Cache` made by this process.
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+/// Temporary entries older than this are assumed to be left by interrupted installs.
+const TEMP_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A user-level cache of wheels shared by every environment Huak installs to.
+///
+/// Entries are keyed by the SHA-256 digest of the wheel:
+/// - wheels/<digest>.whl is the downloaded wheel.
+/// - unpacked/<digest>/ contains the wheel's files, which are hard linked into environments.
+/// - names/<package>/<wheel filename> contains the digest, so the `Cache` can be used as a
+///   `PackageIndex` offline.
+///
+/// Entries are written to tmp/ first and renamed into place, so concurrent installs never
+/// see partial entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cache {
+    root: PathBuf,
+}
+
+/// A summary of what's in a `Cache`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheInfo {
+    /// The number of wheels cached.
+    pub wheels: usize,
+    /// The number of unpacked wheels cached.
+    pub unpacked: usize,
+    /// The size of the cache in bytes.
+    pub size: u64,
+}
+
+impl Cache {
+    /// Initialize a `Cache` at a directory. The directory is created when something is
+    /// cached.
+    pub fn new<T: Into<PathBuf>>(root: T) -> Cache {
+        Cache { root: root.into() }
+    }
+
+    /// Get the `Cache` at `HUAK_CACHE_DIR` or the platform's user cache directory.
+    pub fn from_env() -> HuakResult<Cache> {
+        if let Some(it) = std::env::var_os(CACHE_DIR_ENV_VAR) {
+            return Ok(Cache::new(it));
+        }
+
+        default_cache_dir()
+            .map(Cache::new)
+            .ok_or(Error::CacheNotFound)
+    }
+
+    /// Get the path to the `Cache`'s directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Check if a wheel is cached by its SHA-256 digest.
+    pub fn contains(&self, digest: &str) -> bool {
+        is_digest(digest)
+            && (self.wheel_path(digest).exists()
+                || self.unpacked_path(digest).exists())
+    }
+
+    /// Add a wheel to the `Cache` and get its SHA-256 digest.
+    pub fn add_wheel(
+        &self,
+        bytes: &[u8],
+        filename: &WheelFilename,
+    ) -> HuakResult<String> {
+        let digest = format!("{:x}", Sha256::digest(bytes));
+        let path = self.wheel_path(&digest);
+        if !path.exists() {
+            let temp = self.temp_path()?;
+            fs::File::create(&temp)?.write_all(bytes)?;
+            self.persist(&temp, &path)?;
+        }
+
+        let name = self
+            .root
+            .join("names")
+            .join(normalized_package_name(&filename.name))
+            .join(filename.to_string());
+        if !name.exists() {
+            let temp = self.temp_path()?;
+            fs::write(&temp, &digest)?;
+            self.persist(&temp, &name)?;
+        }
+
+        Ok(digest)
+    }
+
+    /// Get the path to a downloaded wheel by its SHA-256 digest if it's cached.
+    pub fn wheel(&self, digest: &str) -> Option<PathBuf> {
+        Some(self.wheel_path(digest))
+            .filter(|it| is_digest(digest) && it.exists())
+    }
+
+    /// Get the directory a cached wheel is unpacked to, unpacking it first if it hasn't
+    /// been.
+    pub fn unpacked(
+        &self,
+        digest: &str,
+        filename: &WheelFilename,
+    ) -> HuakResult<PathBuf> {
+        if !self.contains(digest) {
+            return Err(Error::InternalError(format!(
+                "{} {} isn't cached",
+                filename.name, filename.version
+            )));
+        }
+        let path = self.unpacked_path(digest);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let temp = self.temp_path()?;
+        let result = fs::File::open(self.wheel_path(digest))
+            .map_err(Error::from)
+            .and_then(|it| unpack_wheel(it, filename, &temp));
+        if let Err(e) = result {
+            fs::remove_dir_all(&temp).ok();
+            return Err(e);
+        }
+        self.persist(&temp, &path)?;
+
+        Ok(path)
+    }
+
+    /// Get a `CacheInfo` summarizing the `Cache`.
+    pub fn info(&self) -> HuakResult<CacheInfo> {
+        let count = |dir: &Path| -> HuakResult<usize> {
+            if !dir.exists() {
+                return Ok(0);
+            }
+            Ok(fs::read_dir(dir)?.count())
+        };
+
+        Ok(CacheInfo {
+            wheels: count(&self.root.join("wheels"))?,
+            unpacked: count(&self.root.join("unpacked"))?,
+            size: dir_size(&self.root)?,
+        })
+    }
+
+    /// Remove everything from the `Cache` and get the number of bytes freed.
+    pub fn clean(&self) -> HuakResult<u64> {
+        if !self.root.exists() {
+            return Ok(0);
+        }
+        let size = dir_size(&self.root)?;
+        fs::remove_dir_all(&self.root)?;
+
+        Ok(size)
+    }
+
+    /// Remove the unpacked wheels no environment links to and any temporary entries left
+    /// by interrupted installs, and get the number of bytes freed. Downloaded wheels are
+    /// kept.
+    ///
+    /// Temporary entries are only removed once they're older than `TEMP_MAX_AGE`, since
+    /// newer ones may still be written by a concurrent install.
+    pub fn prune(&self) -> HuakResult<u64> {
+        self.prune_older_than(TEMP_MAX_AGE)
+    }
+
+    fn prune_older_than(&self, max_age: Duration) -> HuakResult<u64> {
+        let mut paths = Vec::new();
+        let temp = self.root.join("tmp");
+        if temp.exists() {
+            let now = SystemTime::now();
+            for entry in fs::read_dir(temp)? {
+                let entry = entry?;
+                let age = now
+                    .duration_since(entry.metadata()?.modified()?)
+                    .unwrap_or_default();
+                if age >= max_age {
+                    paths.push(entry.path());
+                }
+            }
+        }
+        let unpacked = self.root.join("unpacked");
+        if unpacked.exists() {
+            for entry in fs::read_dir(unpacked)? {
+                let path = entry?.path();
+                if !is_linked(&path)? {
+                    paths.push(path);
+                }
+            }
+        }
+
+        let mut freed = 0;
+        for path in paths {
+            if path.is_dir() {
+                freed += dir_size(&path)?;
+                fs::remove_dir_all(path)?;
+            } else {
+                freed += fs::metadata(&path)?.len();
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(freed)
+    }
+
+    fn wheel_path(&self, digest: &str) -> PathBuf {
+        self.root.join("wheels").join(format!("{digest}.whl"))
+    }
+
+    fn unpacked_path(&self, digest: &str) -> PathBuf {
+        self.root.join("unpacked").join(digest)
+    }
+
+    /// Get the path to a `DistributionFile` the `Cache` listed.
+    fn file_path(&self, file: &DistributionFile) -> HuakResult<PathBuf> {
+        let digest = file.hashes.get("sha256").map(String::as_str);
+        match digest.filter(|it| is_digest(it)) {
+            Some(it) => Ok(self.wheel_path(it)),
+            None => Err(Error::InternalError(format!(
+                "{} isn't cached",
+                file.filename
+            ))),
+        }
+    }
+
+    /// Get a unique path for a temporary entry.
+    fn temp_path(&self) -> HuakResult<PathBuf> {
+        let dir = self.root.join("tmp");
+        fs::create_dir_all(&dir)?;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |it| it.subsec_nanos());
+
+        Ok(dir.join(format!(
+            "{}-{}-{nanos}",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        )))
+    }
+
+    /// Move a temporary entry into place. If another process added the entry first its
+    /// version is kept.
+    fn persist(&self, temp: &Path, path: &Path) -> HuakResult<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::rename(temp, path).is_err() {
+            if temp.is_dir() {
+                fs::remove_dir_all(temp)?;
+            } else {
+                fs::remove_file(temp)?;
+            }
+            if !path.exists() {
+                return Err(Error::InternalError(format!(
+                    "{} couldn't be added to the cache",
+                    path.display()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PackageIndex for Cache {
+    fn files(&self, package_name: &str) -> HuakResult<Vec<DistributionFile>> {
+        let dir = self
+            .root
+            .join("names")
+            .join(normalized_package_name(package_name));
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let contents = fs::read_to_string(&path)?;
+            let digest = contents.trim();
+            let filename = path
+                .file_name()
+                .and_then(|it| it.to_str())
+                .unwrap_or_default();
+            let (Ok(wheel), true) = (
+                WheelFilename::from_str(filename),
+                is_digest(digest) && self.wheel_path(digest).exists(),
+            ) else {
+                continue;
+            };
+            let url =
+                Url::from_file_path(self.wheel_path(digest)).map_err(|_| {
+                    Error::InternalError(format!("{filename} isn't cached"))
+                })?;
+
+            files.push(DistributionFile {
+                filename: filename.to_string(),
+                url,
+                version: wheel.version,
+                hashes: [("sha256".to_string(), digest.to_string())]
+                    .into_iter()
+                    .collect(),
+                requires_python: None,
+                yanked: false,
+                metadata_url: None,
+            });
+        }
+        files.sort_by(|a, b| a.filename.cmp(&b.filename));
+
+        Ok(files)
+    }
+
+    fn metadata(&self, file: &DistributionFile) -> HuakResult<CoreMetadata> {
+        wheel_metadata(fs::File::open(self.file_path(file)?)?)
+    }
+
+    fn download(&self, file: &DistributionFile) -> HuakResult<Vec<u8>> {
+        Ok(fs::read(self.file_path(file)?)?)
+    }
+}
+
+/// Get the platform's user cache directory for Huak.
+fn default_cache_dir() -> Option<PathBuf> {
+    let var = |name| std::env::var_os(name).filter(|it| !it.is_empty());
+
+    if cfg!(windows) {
+        var("LOCALAPPDATA")
+            .map(|it| PathBuf::from(it).join("huak").join("cache"))
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|it| PathBuf::from(it).join("Library/Caches/huak"))
+    } else {
+        var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|it| PathBuf::from(it).join(".cache")))
+            .map(|it| it.join("huak"))
+    }
+}
+
+/// Check if a string is a hex-encoded SHA-256 digest.
+fn is_digest(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|it| it.is_ascii_hexdigit())
+}
+
+/// Get the total size of the files in a directory.
+fn dir_size(dir: &Path) -> HuakResult<u64> {
+    let mut size = 0;
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                stack.push(entry.path());
+            } else {
+                size += metadata.len();
+            }
+        }
+    }
+
+    Ok(size)
+}
+
+/// Check if any file in a directory is hard linked somewhere else.
+#[cfg(unix)]
+fn is_linked(dir: &Path) -> HuakResult<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                stack.push(entry.path());
+            } else if metadata.nlink() > 1 {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Link counts aren't available on Windows, so everything is assumed to be linked.
+#[cfg(not(unix))]
+fn is_linked(_dir: &Path) -> HuakResult<bool> {
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        installer::{install_unpacked_wheel, FileMode, InstallScheme},
+        test_resources_dir_path,
+    };
+    use tempfile::tempdir;
+
+    #[test]
+    fn cache_unpacked_wheels() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path().join("cache"));
+        let name = "mock_utils-1.0.0-py3-none-any.whl";
+        let bytes =
+            fs::read(test_resources_dir_path().join("mock-wheels").join(name))
+                .unwrap();
+        let filename = WheelFilename::from_str(name).unwrap();
+
+        let digest = cache.add_wheel(&bytes, &filename).unwrap();
+        assert!(cache.contains(&digest));
+        let files = cache.files("Mock_Utils").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].filename, name);
+        assert_eq!(cache.download(&files[0]).unwrap(), bytes);
+        assert_eq!(cache.metadata(&files[0]).unwrap().name, "mock-utils");
+        assert!(!cache.contains("../wheels"));
+        // Digests written to names/ are validated like any other.
+        let names = cache.root().join("names").join("mock-utils");
+        fs::write(names.join("mock_utils-2.0.0-py3-none-any.whl"), "../wheels")
+            .unwrap();
+        assert_eq!(cache.files("mock-utils").unwrap().len(), 1);
+        let unpacked = cache.unpacked(&digest, &filename).unwrap();
+        assert!(unpacked.join("mock_utils/__init__.py").exists());
+        assert_eq!(cache.unpacked(&digest, &filename).unwrap(), unpacked);
+
+        let info = cache.info().unwrap();
+        assert_eq!((info.wheels, info.unpacked), (1, 1));
+        assert!(info.size > bytes.len() as u64);
+
+        // Unpacked wheels linked into an environment survive pruning.
+        let venv = dir.path().join("venv");
+        let scheme = InstallScheme {
+            site_packages: venv.join("site-packages"),
+            scripts: venv.join("bin"),
+            headers: venv.join("include"),
+            data: venv.clone(),
+            python: venv.join("bin/python"),
+        };
+        install_unpacked_wheel(
+            &unpacked,
+            &filename,
+            &scheme,
+            &Default::default(),
+            FileMode::Link,
+        )
+        .unwrap();
+        assert!(scheme.site_packages.join("mock_utils/__init__.py").exists());
+        #[cfg(unix)]
+        {
+            assert_eq!(cache.prune().unwrap(), 0);
+            fs::remove_dir_all(&venv).unwrap();
+            assert!(cache.prune().unwrap() > 0);
+            assert!(!unpacked.exists());
+            assert!(cache.contains(&digest));
+        }
+
+        assert!(cache.clean().unwrap() > 0);
+        assert!(!cache.root().exists());
+    }
+
+    #[test]
+    fn cache_prune_stale_temporary_entries() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let file = cache.temp_path().unwrap();
+        let unpacked = cache.temp_path().unwrap();
+        fs::write(&file, "file").unwrap();
+        fs::create_dir_all(&unpacked).unwrap();
+        fs::write(unpacked.join("module.py"), "module").unwrap();
+
+        // Entries could still be written by a concurrent install.
+        assert_eq!(cache.prune().unwrap(), 0);
+        assert!(file.exists() && unpacked.exists());
+        assert_eq!(cache.prune_older_than(Duration::ZERO).unwrap(), 10);
+        assert!(!file.exists() && !unpacked.exists());
+    }
+}
@@ -1,6 +1,13 @@
 use std::path::PathBuf;
 
-use crate::{sys::Terminal, workspace::Workspace, TerminalOptions};
+use crate::{
+    installer_backend::{InstallerBackend, InstallerKind},
+    metadata::HuakTable,
+    sources::apply_sources,
+    sys::Terminal,
+    workspace::Workspace,
+    Error, HuakResult, InstallOptions, TerminalOptions,
+};
 
 #[derive(Clone)]
 /// The main `Config` for Huak.
@@ -17,7 +24,9 @@ use crate::{sys::Terminal, workspace::Workspace, TerminalOptions};
 ///     cwd: PathBuf::from("."),
 ///     terminal_options: TerminalOptions {
 ///         verbosity: Verbosity::Normal,
-///     }
+///     },
+///     installer: None,
+///     offline: false,
 /// };
 ///
 /// let workspace = config.workspace();
@@ -29,6 +38,12 @@ pub struct Config {
     pub cwd: PathBuf,
     /// `Terminal` options to use.
     pub terminal_options: TerminalOptions,
+    /// The `InstallerKind` to install packages with. Defaults to the project's
+    /// `[tool.huak]` installer.
+    pub installer: Option<InstallerKind>,
+    /// Work without network access. Packages are only installed from local directories
+    /// or the cache.
+    pub offline: bool,
 }
 
 impl Config {
@@ -45,4 +60,60 @@ impl Config {
 
         terminal
     }
+
+    /// Get the `InstallerBackend` to install packages with. An explicit installer takes
+    /// precedence over the project's `[tool.huak]` installer.
+    pub fn installer_backend(&self) -> HuakResult<Box<dyn InstallerBackend>> {
+        match self.installer {
+            Some(it) => it.backend(),
+            None => self.huak_table()?.installer.unwrap_or_default().backend(),
+        }
+    }
+
+    /// Get `InstallOptions` with the project's `[tool.huak]` install options and indexes
+    /// filled in.
+    pub fn install_options(
+        &self,
+        options: &InstallOptions,
+    ) -> HuakResult<InstallOptions> {
+        let mut options =
+            options.with_defaults(&self.huak_table()?.install_options()?);
+        options.offline |= self.offline;
+
+        Ok(options)
+    }
+
+    /// Fail with an `Error::NetworkUnavailable` if the `Config` is offline.
+    pub fn require_network(&self, action: &str) -> HuakResult<()> {
+        match self.offline {
+            true => Err(Error::NetworkUnavailable(action.to_string())),
+            false => Ok(()),
+        }
+    }
+
+    /// Replace packages with a path or git source in the project's `[tool.huak.sources]`
+    /// table with direct references to their source.
+    pub fn sourced_packages(
+        &self,
+        packages: &[String],
+    ) -> HuakResult<Vec<String>> {
+        let Ok(metadata) = self.workspace().current_local_metadata() else {
+            return Ok(packages.to_vec());
+        };
+        let root = metadata.path().parent().unwrap_or(&self.workspace_root);
+
+        apply_sources(
+            packages,
+            &metadata.metadata().huak_table()?.sources,
+            root,
+        )
+    }
+
+    /// Get the current project's `[tool.huak]` table. Outside of a project it's empty.
+    fn huak_table(&self) -> HuakResult<HuakTable> {
+        match self.workspace().current_local_metadata() {
+            Ok(it) => it.metadata().huak_table(),
+            Err(_) => Ok(HuakTable::default()),
+        }
+    }
 }
@@ -0,0 +1,259 @@
+use std::fmt::Display;
+
+use pep440_rs::Version;
+use pep508_rs::{MarkerEnvironment, Requirement};
+
+use crate::{
+    dependency_graph::{DependencyGraph, Dependent},
+    index::PackageIndex,
+    resolver::{is_satisfied_by, override_requirement},
+};
+
+/// An installed package whose version a project's constraint or override decided.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintEffect {
+    /// A constraint excluded a newer version the package's requirements allow.
+    Constrained {
+        name: String,
+        version: Version,
+        constraint: Requirement,
+        excluded: Version,
+    },
+    /// An override replaced a requirement the installed version doesn't satisfy.
+    Overridden {
+        name: String,
+        version: Version,
+        requirement: Requirement,
+        required_by: String,
+    },
+}
+
+impl ConstraintEffect {
+    /// Get the title the `ConstraintEffect` is reported with.
+    pub fn title(&self) -> &str {
+        match self {
+            ConstraintEffect::Constrained { .. } => "constrained",
+            ConstraintEffect::Overridden { .. } => "overridden",
+        }
+    }
+}
+
+impl Display for ConstraintEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConstraintEffect::Constrained {
+                name,
+                version,
+                constraint,
+                excluded,
+            } => {
+                let mut constraint = constraint.clone();
+                constraint.marker = None;
+                write!(f, "{name} {version} ({constraint} excludes {excluded})")
+            }
+            ConstraintEffect::Overridden {
+                name,
+                version,
+                requirement,
+                required_by,
+            } => {
+                let mut requirement = requirement.clone();
+                requirement.marker = None;
+                write!(
+                    f,
+                    "{name} {version} (replaces {requirement} required by {required_by})"
+                )
+            }
+        }
+    }
+}
+
+/// Find the installed packages of a `DependencyGraph` that a project's constraints or
+/// overrides changed the outcome for.
+///
+/// A constraint changed the outcome if the newest version every requirement on the
+/// package allows is newer than the installed version and the constraint excludes it.
+/// Constraints are only checked if a `PackageIndex` is given. An override changed the
+/// outcome if the installed version doesn't satisfy a requirement it replaced.
+pub fn constraint_effects(
+    graph: &DependencyGraph,
+    constraints: &[Requirement],
+    overrides: &[Requirement],
+    env: &MarkerEnvironment,
+    index: Option<&dyn PackageIndex>,
+    prereleases: bool,
+) -> Vec<ConstraintEffect> {
+    let mut effects = Vec::new();
+    let overrides = overrides
+        .iter()
+        .filter(|it| it.evaluate_markers(env, Vec::new()))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for constraint in constraints
+        .iter()
+        .filter(|it| it.evaluate_markers(env, Vec::new()))
+    {
+        let (Some(index), Some(package)) =
+            (index, graph.package(&constraint.name))
+        else {
+            continue;
+        };
+        let requirements = graph
+            .dependents(&package.name)
+            .into_iter()
+            .map(|it| {
+                let requirement = match it {
+                    Dependent::Group(_, req) | Dependent::Package(_, req) => {
+                        req
+                    }
+                };
+                override_requirement(requirement, &overrides)
+                    .unwrap_or_else(|| requirement.clone())
+            })
+            .collect::<Vec<_>>();
+        let Some(newest) = newest_version(
+            index,
+            &package.name,
+            &requirements,
+            env,
+            prereleases,
+        ) else {
+            continue;
+        };
+        if newest > package.version && !is_satisfied_by(constraint, &newest) {
+            effects.push(ConstraintEffect::Constrained {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                constraint: constraint.clone(),
+                excluded: newest,
+            });
+        }
+    }
+
+    for it in overrides.iter() {
+        let Some(package) = graph.package(&it.name) else {
+            continue;
+        };
+        for dependent in graph.dependents(&package.name) {
+            let (requirement, required_by) = match dependent {
+                Dependent::Group(_, req) => (req, "the project".to_string()),
+                Dependent::Package(pkg, req) => {
+                    (req, format!("{} {}", pkg.name, pkg.version))
+                }
+            };
+            if !is_satisfied_by(requirement, &package.version) {
+                effects.push(ConstraintEffect::Overridden {
+                    name: package.name.clone(),
+                    version: package.version.clone(),
+                    requirement: requirement.clone(),
+                    required_by,
+                });
+            }
+        }
+    }
+
+    effects
+}
+
+/// Get the newest version of a package a `PackageIndex` has that satisfies every
+/// `Requirement`. Pre-releases are only used if they're allowed or are all that's left.
+fn newest_version(
+    index: &dyn PackageIndex,
+    name: &str,
+    requirements: &[Requirement],
+    env: &MarkerEnvironment,
+    prereleases: bool,
+) -> Option<Version> {
+    let python_version = &env.python_full_version.1;
+    let versions = index
+        .files(name)
+        .ok()?
+        .into_iter()
+        .filter(|file| {
+            !file.yanked
+                && file
+                    .requires_python
+                    .as_ref()
+                    .map_or(true, |it| it.contains(python_version))
+        })
+        .map(|file| file.version)
+        .filter(|version| {
+            requirements.iter().all(|it| is_satisfied_by(it, version))
+        })
+        .collect::<Vec<_>>();
+
+    let stable = versions.iter().filter(|it| !it.any_prerelease()).max();
+    match prereleases {
+        true => versions.iter().max(),
+        false => stable.or(versions.iter().max()),
+    }
+    .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        index::SimpleIndex, site_packages::installed_distributions,
+        test_marker_environment, test_resources_dir_path,
+    };
+    use indexmap::IndexMap;
+    use std::str::FromStr;
+    use url::Url;
+
+    fn requirements(reqs: &[&str]) -> Vec<Requirement> {
+        reqs.iter()
+            .map(|it| Requirement::from_str(it).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn constraint_and_override_effects() {
+        let path = test_resources_dir_path().join("mock-index");
+        let index =
+            SimpleIndex::new(Url::from_directory_path(path).unwrap().as_str())
+                .unwrap();
+        let env = test_marker_environment();
+        let dists = installed_distributions(
+            test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+        let mut groups = IndexMap::new();
+        groups.insert(
+            "required".to_string(),
+            requirements(&["mock-web", "mock-utils>=2"]),
+        );
+        let graph = DependencyGraph::new(groups, &dists, &env);
+
+        // mock-web 3.0.0 is yanked and mock-db isn't installed.
+        let constrained = constraint_effects(
+            &graph,
+            &requirements(&["mock-utils<1.5", "mock-web<3", "mock-db<2"]),
+            &[],
+            &env,
+            Some(&index),
+            false,
+        );
+        let overridden = constraint_effects(
+            &graph,
+            &[],
+            &requirements(&["mock-utils>=1"]),
+            &env,
+            Some(&index),
+            false,
+        );
+
+        assert_eq!(
+            constrained
+                .iter()
+                .map(|it| it.to_string())
+                .collect::<Vec<_>>(),
+            ["mock_utils 1.0.0 (mock-utils <1.5 excludes 2.0.0)"]
+        );
+        assert_eq!(
+            overridden.iter().map(|it| it.to_string()).collect::<Vec<_>>(),
+            ["mock_utils 1.0.0 (replaces mock-utils >=2 required by the project)"]
+        );
+    }
+}
@@ -0,0 +1,770 @@
+use std::{
+    fmt::{Debug, Display},
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use base64::Engine;
+use indexmap::IndexMap;
+use url::Url;
+
+use crate::{sources::NamedIndex, Error, HuakResult};
+
+const NETRC_ENV_VAR: &str = "NETRC";
+const KEYRING_EXECUTABLE: &str = "keyring";
+
+#[derive(Clone, PartialEq, Eq)]
+/// The username and password of a package index.
+pub struct Credentials {
+    pub username: String,
+    /// The password, if it's known. Providers that only store passwords (like a keyring)
+    /// are asked for it with the username.
+    pub password: Option<String>,
+}
+
+impl Credentials {
+    /// Get the value of an HTTP Basic `Authorization` header for the `Credentials`.
+    pub fn authorization(&self) -> String {
+        let pair = format!(
+            "{}:{}",
+            self.username,
+            self.password.as_deref().unwrap_or_default()
+        );
+
+        format!(
+            "Basic {}",
+            base64::engine::general_purpose::STANDARD.encode(pair)
+        )
+    }
+}
+
+impl Debug for Credentials {
+    // The password is never printed.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "****"))
+            .finish()
+    }
+}
+
+/// The package index (or other URL) credentials are needed for.
+pub struct IndexLocation<'a> {
+    /// The name of the index in the `[tool.huak.indexes]` table, if it has one.
+    pub name: Option<&'a str>,
+    /// The URL of the index, or of the URL requested from its host.
+    pub url: &'a Url,
+}
+
+/// A source of `Credentials` for package indexes.
+pub trait CredentialProvider {
+    /// Get the `Credentials` of an index. `username` is a username already known for the
+    /// index, which providers that only store passwords need.
+    fn credentials(
+        &self,
+        index: &IndexLocation,
+        username: Option<&str>,
+    ) -> HuakResult<Option<Credentials>>;
+}
+
+/// Credentials from `HUAK_INDEX_<NAME>_USERNAME` and `HUAK_INDEX_<NAME>_PASSWORD`, where
+/// `<NAME>` is the index's name in the `[tool.huak.indexes]` table.
+pub struct EnvCredentials;
+
+impl EnvCredentials {
+    /// Get the name of an index's environment variable (like `HUAK_INDEX_INTERNAL_PASSWORD`).
+    pub fn var_name(index_name: &str, field: &str) -> String {
+        let name = index_name
+            .chars()
+            .map(|it| match it.is_ascii_alphanumeric() {
+                true => it.to_ascii_uppercase(),
+                false => '_',
+            })
+            .collect::<String>();
+
+        format!("HUAK_INDEX_{name}_{field}")
+    }
+}
+
+impl CredentialProvider for EnvCredentials {
+    fn credentials(
+        &self,
+        index: &IndexLocation,
+        username: Option<&str>,
+    ) -> HuakResult<Option<Credentials>> {
+        let Some(name) = index.name else {
+            return Ok(None);
+        };
+        let var = |field| {
+            std::env::var(EnvCredentials::var_name(name, field))
+                .ok()
+                .filter(|it| !it.is_empty())
+        };
+        let Some(username) =
+            var("USERNAME").or_else(|| username.map(ToString::to_string))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(Credentials {
+            username,
+            password: var("PASSWORD"),
+        }))
+    }
+}
+
+/// Credentials from a netrc file, by the host of the index.
+pub struct NetrcCredentials {
+    netrc: Netrc,
+}
+
+impl NetrcCredentials {
+    /// Read the netrc file at `NETRC` or `~/.netrc`, if there is one.
+    pub fn from_env() -> HuakResult<NetrcCredentials> {
+        let netrc = match netrc_path() {
+            Some(path) if path.exists() => Netrc::from_path(path)?,
+            _ => Netrc::default(),
+        };
+
+        Ok(NetrcCredentials { netrc })
+    }
+}
+
+impl CredentialProvider for NetrcCredentials {
+    fn credentials(
+        &self,
+        index: &IndexLocation,
+        username: Option<&str>,
+    ) -> HuakResult<Option<Credentials>> {
+        let Some(entry) =
+            index.url.host_str().and_then(|it| self.netrc.get(it))
+        else {
+            return Ok(None);
+        };
+        // An entry for another user isn't used.
+        match (username, entry.login.as_deref()) {
+            (Some(a), Some(b)) if a != b => Ok(None),
+            (_, Some(login)) => Ok(Some(Credentials {
+                username: login.to_string(),
+                password: entry.password.clone(),
+            })),
+            (Some(username), None) => Ok(Some(Credentials {
+                username: username.to_string(),
+                password: entry.password.clone(),
+            })),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+/// Passwords stored in the system keyring with the `keyring` command, by index URL and
+/// username.
+pub struct KeyringCredentials;
+
+impl KeyringCredentials {
+    /// Store the password of a user for an index.
+    pub fn set(
+        service: &str,
+        username: &str,
+        password: &str,
+    ) -> HuakResult<()> {
+        let mut child = Command::new(KEYRING_EXECUTABLE)
+            .args(["set", service, username])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::KeyringError(keyring_spawn_error(e)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "{password}")?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(Error::KeyringError(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Delete the password of a user for an index. Returns `false` if the keyring didn't
+    /// have one (or there is no keyring).
+    pub fn delete(service: &str, username: &str) -> bool {
+        Command::new(KEYRING_EXECUTABLE)
+            .args(["del", service, username])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_or(false, |it| it.success())
+    }
+}
+
+impl CredentialProvider for KeyringCredentials {
+    fn credentials(
+        &self,
+        index: &IndexLocation,
+        username: Option<&str>,
+    ) -> HuakResult<Option<Credentials>> {
+        // The keyring is only asked when a username is known, so indexes without
+        // credentials never run the `keyring` command.
+        let Some(username) = username else {
+            return Ok(None);
+        };
+        let mut services = vec![index.url.to_string()];
+        if let Some(host) = index.url.host_str() {
+            services.push(host.to_string());
+        }
+        for service in services {
+            let Ok(output) = Command::new(KEYRING_EXECUTABLE)
+                .args(["get", &service, username])
+                .stdin(Stdio::null())
+                .stderr(Stdio::null())
+                .output()
+            else {
+                return Ok(None);
+            };
+            let password = String::from_utf8_lossy(&output.stdout)
+                .trim_end_matches(['\r', '\n'])
+                .to_string();
+            if output.status.success() && !password.is_empty() {
+                return Ok(Some(Credentials {
+                    username: username.to_string(),
+                    password: Some(password),
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[derive(Default)]
+/// The `CredentialProvider`s credentials for package indexes are resolved from, in
+/// order of precedence.
+///
+/// Credentials a URL already contains come first. A username found by one provider
+/// is used to ask the next ones for its password.
+pub struct CredentialStore {
+    indexes: IndexMap<String, NamedIndex>,
+    providers: Vec<Box<dyn CredentialProvider>>,
+}
+
+impl CredentialStore {
+    /// Initialize a `CredentialStore` with the environment, netrc, and keyring providers.
+    pub fn new(
+        indexes: &IndexMap<String, NamedIndex>,
+    ) -> HuakResult<CredentialStore> {
+        Ok(CredentialStore {
+            indexes: indexes.clone(),
+            providers: vec![
+                Box::new(EnvCredentials),
+                Box::new(NetrcCredentials::from_env()?),
+                Box::new(KeyringCredentials),
+            ],
+        })
+    }
+
+    /// Initialize a `CredentialStore` with specific `CredentialProvider`s.
+    pub fn with_providers(
+        indexes: &IndexMap<String, NamedIndex>,
+        providers: Vec<Box<dyn CredentialProvider>>,
+    ) -> CredentialStore {
+        CredentialStore {
+            indexes: indexes.clone(),
+            providers,
+        }
+    }
+
+    /// Get the `Credentials` for a URL. Requests to the host of a named index use its
+    /// name, so a file listed by the index gets the index's credentials.
+    pub fn credentials(&self, url: &Url) -> HuakResult<Option<Credentials>> {
+        if url.scheme() == "file" {
+            return Ok(None);
+        }
+        let mut username = Some(url.username())
+            .filter(|it| !it.is_empty())
+            .map(ToString::to_string);
+        if let (Some(username), Some(password)) = (&username, url.password()) {
+            return Ok(Some(Credentials {
+                username: username.clone(),
+                password: Some(password.to_string()),
+            }));
+        }
+
+        let named = self.named_index(url);
+        let index_url = named.as_ref().map(|(_, it)| it);
+        let location = IndexLocation {
+            name: named.as_ref().map(|(it, _)| it.as_str()),
+            url: index_url.unwrap_or(url),
+        };
+        for provider in self.providers.iter() {
+            match provider.credentials(&location, username.as_deref())? {
+                Some(it) if it.password.is_some() => return Ok(Some(it)),
+                Some(it) => username = Some(it.username),
+                None => (),
+            }
+        }
+
+        Ok(username.map(|username| Credentials {
+            username,
+            password: None,
+        }))
+    }
+
+    /// Get the name and URL of the named index a URL belongs to. The index with the
+    /// longest matching URL is used, or any index on the same host.
+    fn named_index(&self, url: &Url) -> Option<(String, Url)> {
+        let mut indexes = self
+            .indexes
+            .iter()
+            .filter_map(|(name, it)| {
+                Url::parse(&it.url).ok().map(|url| (name, url))
+            })
+            .filter(|(_, it)| it.host_str() == url.host_str())
+            .collect::<Vec<_>>();
+        indexes.sort_by_key(|(_, it)| {
+            let prefix = url.path().starts_with(it.path());
+            (!prefix, usize::MAX - it.path().len())
+        });
+
+        indexes
+            .into_iter()
+            .next()
+            .map(|(name, it)| (name.clone(), it))
+    }
+
+    /// Write a netrc file with the credentials of the hosts of remote `urls`, followed
+    /// by the user's own netrc file, for a subprocess to read from `NETRC`.
+    ///
+    /// Nothing is written if no credentials are found.
+    pub fn netrc_file<'a, I>(&self, urls: I) -> HuakResult<Option<NetrcFile>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut netrc = Netrc::default();
+        for url in urls {
+            let Ok(url) = Url::parse(url) else {
+                continue;
+            };
+            let Some(host) = url.host_str().map(ToString::to_string) else {
+                continue;
+            };
+            if netrc.get(&host).map_or(false, |it| it.machine.is_some()) {
+                continue;
+            }
+            if let Some(Credentials {
+                username,
+                password: Some(password),
+            }) = self.credentials(&url)?
+            {
+                netrc.set(&host, &username, Some(&password));
+            }
+        }
+        if netrc.entries.is_empty() {
+            return Ok(None);
+        }
+        if let Some(path) = netrc_path().filter(|it| it.exists()) {
+            for entry in Netrc::from_path(path)?.entries {
+                let known = entry.machine.as_ref().map_or(false, |it| {
+                    netrc.get(it).map_or(false, |it| it.machine.is_some())
+                });
+                if !known {
+                    netrc.entries.push(entry);
+                }
+            }
+        }
+
+        NetrcFile::new(&netrc).map(Some)
+    }
+
+    /// Set `NETRC` for a command to a `NetrcFile` with the credentials of `urls`. The
+    /// `NetrcFile` is removed when it's dropped, so it needs to be kept until the
+    /// command has run.
+    pub fn authenticate<'a, I>(
+        &self,
+        cmd: &mut Command,
+        urls: I,
+    ) -> HuakResult<Option<NetrcFile>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let file = self.netrc_file(urls)?;
+        if let Some(it) = file.as_ref() {
+            cmd.env(NETRC_ENV_VAR, it.path());
+        }
+
+        Ok(file)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// An entry in a netrc file. The `default` entry has no `machine`.
+pub struct NetrcEntry {
+    pub machine: Option<String>,
+    pub login: Option<String>,
+    pub password: Option<String>,
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A netrc file (see https://www.gnu.org/software/inetutils/manual/html_node/The-_002enetrc-file.html).
+///
+/// Macro definitions and comments aren't kept when it's written.
+pub struct Netrc {
+    entries: Vec<NetrcEntry>,
+}
+
+impl Netrc {
+    /// Read a `Netrc` from a file.
+    pub fn from_path<T: AsRef<Path>>(path: T) -> HuakResult<Netrc> {
+        Netrc::from_str(&std::fs::read_to_string(path)?)
+    }
+
+    /// Get the entry of a host, or the `default` entry.
+    pub fn get(&self, host: &str) -> Option<&NetrcEntry> {
+        self.entries
+            .iter()
+            .find(|it| it.machine.as_deref() == Some(host))
+            .or_else(|| self.entries.iter().find(|it| it.machine.is_none()))
+    }
+
+    /// Set the login and password of a host, replacing its entry if it has one.
+    pub fn set(&mut self, host: &str, login: &str, password: Option<&str>) {
+        let entry = NetrcEntry {
+            machine: Some(host.to_string()),
+            login: Some(login.to_string()),
+            password: password.map(ToString::to_string),
+            account: None,
+        };
+        match self
+            .entries
+            .iter_mut()
+            .find(|it| it.machine.as_deref() == Some(host))
+        {
+            Some(it) => *it = entry,
+            // The default entry has to come last.
+            None => {
+                let i = self
+                    .entries
+                    .iter()
+                    .position(|it| it.machine.is_none())
+                    .unwrap_or(self.entries.len());
+                self.entries.insert(i, entry);
+            }
+        }
+    }
+
+    /// Remove the entry of a host. Returns the entry removed.
+    pub fn remove(&mut self, host: &str) -> Option<NetrcEntry> {
+        let i = self
+            .entries
+            .iter()
+            .position(|it| it.machine.as_deref() == Some(host))?;
+
+        Some(self.entries.remove(i))
+    }
+
+    /// Write the `Netrc` to a file only the user can read.
+    pub fn write_file<T: AsRef<Path>>(&self, path: T) -> HuakResult<()> {
+        let mut file = private_file(path.as_ref(), false)?;
+        file.write_all(self.to_string().as_bytes())?;
+
+        Ok(())
+    }
+}
+
+impl FromStr for Netrc {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries: Vec<NetrcEntry> = Vec::new();
+        let mut in_macdef = false;
+        let mut expecting: Option<String> = None;
+        for line in s.lines() {
+            // A macro definition ends at an empty line.
+            if in_macdef {
+                in_macdef = !line.trim().is_empty();
+                continue;
+            }
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            for token in line.split_whitespace() {
+                if let Some(key) = expecting.take() {
+                    let entry = entries.last_mut().ok_or_else(|| {
+                        Error::HuakConfigurationError(format!(
+                            "netrc has {key} before any machine"
+                        ))
+                    })?;
+                    let value = Some(token.to_string());
+                    match key.as_str() {
+                        "machine" => entry.machine = value,
+                        "login" => entry.login = value,
+                        "password" => entry.password = value,
+                        "account" => entry.account = value,
+                        _ => (),
+                    }
+                    continue;
+                }
+                match token {
+                    "machine" => {
+                        entries.push(NetrcEntry::default());
+                        expecting = Some(token.to_string());
+                    }
+                    "default" => entries.push(NetrcEntry::default()),
+                    "login" | "password" | "account" => {
+                        expecting = Some(token.to_string())
+                    }
+                    "macdef" => {
+                        in_macdef = true;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(Netrc { entries })
+    }
+}
+
+impl Display for Netrc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in self.entries.iter() {
+            match entry.machine.as_ref() {
+                Some(it) => writeln!(f, "machine {it}")?,
+                None => writeln!(f, "default")?,
+            }
+            if let Some(it) = entry.login.as_ref() {
+                writeln!(f, "  login {it}")?;
+            }
+            if let Some(it) = entry.password.as_ref() {
+                writeln!(f, "  password {it}")?;
+            }
+            if let Some(it) = entry.account.as_ref() {
+                writeln!(f, "  account {it}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A temporary netrc file, removed when it's dropped.
+pub struct NetrcFile {
+    path: PathBuf,
+}
+
+impl NetrcFile {
+    fn new(netrc: &Netrc) -> HuakResult<NetrcFile> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "huak-{}-{}.netrc",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut file = private_file(&path, true)?;
+        let it = NetrcFile { path };
+        file.write_all(netrc.to_string().as_bytes())?;
+
+        Ok(it)
+    }
+
+    /// Get a reference to the path of the `NetrcFile`.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for NetrcFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+/// Check that a URL from a metadata file doesn't contain a password. Credentials are
+/// kept out of metadata files and found with a `CredentialStore` instead.
+pub fn check_url_without_password(url: &str) -> HuakResult<()> {
+    match Url::parse(url) {
+        Ok(it) if it.password().is_some() => {
+            Err(Error::HuakConfigurationError(format!(
+                "the URL of {}{} contains a password (use `huak auth login` or \
+                 HUAK_INDEX_<NAME>_PASSWORD instead)",
+                it.host_str().unwrap_or_default(),
+                it.path()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Get the path of the user's netrc file: `NETRC`, or `.netrc` (`_netrc` on Windows) in
+/// the home directory.
+pub fn netrc_path() -> Option<PathBuf> {
+    if let Some(it) =
+        std::env::var_os(NETRC_ENV_VAR).filter(|it| !it.is_empty())
+    {
+        return Some(PathBuf::from(it));
+    }
+    let (var, name) = match cfg!(windows) {
+        true => ("USERPROFILE", "_netrc"),
+        false => ("HOME", ".netrc"),
+    };
+
+    std::env::var_os(var)
+        .filter(|it| !it.is_empty())
+        .map(|it| PathBuf::from(it).join(name))
+}
+
+/// Open a file for writing that only the user can read.
+fn private_file(path: &Path, create_new: bool) -> HuakResult<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true);
+    match create_new {
+        true => options.create_new(true),
+        false => options.create(true).truncate(true),
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(file)
+}
+
+fn keyring_spawn_error(e: std::io::Error) -> String {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            format!("{KEYRING_EXECUTABLE} could not be found (install it with `pip install keyring`)")
+        }
+        _ => e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `CredentialProvider` with fixed credentials by index name.
+    struct MockProvider(IndexMap<&'static str, Credentials>);
+
+    impl CredentialProvider for MockProvider {
+        fn credentials(
+            &self,
+            index: &IndexLocation,
+            _username: Option<&str>,
+        ) -> HuakResult<Option<Credentials>> {
+            Ok(index.name.and_then(|it| self.0.get(it)).cloned())
+        }
+    }
+
+    #[test]
+    fn netrc() {
+        let mut netrc = Netrc::from_str(
+            r#"
+# comment
+machine pypi.example.com login alice password secret
+macdef init
+  cd /pub
+
+machine other.example.com
+  login bob
+default login anonymous password guest
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            netrc.get("pypi.example.com").unwrap().password.as_deref(),
+            Some("secret")
+        );
+        assert_eq!(
+            netrc.get("other.example.com").unwrap().login.as_deref(),
+            Some("bob")
+        );
+        assert_eq!(
+            netrc.get("unknown.example.com").unwrap().login.as_deref(),
+            Some("anonymous")
+        );
+
+        netrc.set("new.example.com", "carol", Some("hunter2"));
+        netrc.remove("other.example.com");
+        let netrc = Netrc::from_str(&netrc.to_string()).unwrap();
+
+        assert_eq!(
+            netrc.get("new.example.com").unwrap().login.as_deref(),
+            Some("carol")
+        );
+        assert_eq!(
+            netrc.get("other.example.com").unwrap().login.as_deref(),
+            Some("anonymous")
+        );
+        assert!(netrc.entries.last().unwrap().machine.is_none());
+    }
+
+    #[test]
+    fn credential_store() {
+        let indexes: IndexMap<String, NamedIndex> = toml::from_str(
+            r#"
+internal = { url = "https://pypi.example.com/simple" }
+"#,
+        )
+        .unwrap();
+        let credentials = Credentials {
+            username: "alice".to_string(),
+            password: Some("secret".to_string()),
+        };
+        let store = CredentialStore::with_providers(
+            &indexes,
+            vec![Box::new(MockProvider(IndexMap::from([(
+                "internal",
+                credentials.clone(),
+            )])))],
+        );
+
+        // Files served from the index's host get its credentials.
+        let url = Url::parse(
+            "https://pypi.example.com/files/ourlib-1.0-py3-none-any.whl",
+        )
+        .unwrap();
+        assert_eq!(store.credentials(&url).unwrap(), Some(credentials));
+        assert!(!format!("{:?}", store.credentials(&url)).contains("secret"));
+        let url = Url::parse("https://pypi.org/simple/").unwrap();
+        assert_eq!(store.credentials(&url).unwrap(), None);
+
+        let file = store
+            .netrc_file([
+                "https://pypi.example.com/simple",
+                "https://pypi.org/simple",
+            ])
+            .unwrap()
+            .unwrap();
+        let netrc = Netrc::from_path(file.path()).unwrap();
+        assert_eq!(
+            netrc.get("pypi.example.com").unwrap().password.as_deref(),
+            Some("secret")
+        );
+        let path = file.path().to_path_buf();
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn env_credentials_var_name() {
+        assert_eq!(
+            EnvCredentials::var_name("our-mirror", "PASSWORD"),
+            "HUAK_INDEX_OUR_MIRROR_PASSWORD"
+        );
+    }
+}
@@ -1,9 +1,18 @@
-use std::{ffi::OsStr, fmt::Display, str::FromStr};
+use std::{
+    ffi::OsStr,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use pep440_rs::VersionSpecifiers;
+use pep440_rs::{Operator, Version, VersionSpecifier, VersionSpecifiers};
 use pep508_rs::{Requirement, VersionOrUrl};
+use serde::{Deserialize, Serialize};
+use url::Url;
 
-use crate::Error;
+use crate::{
+    distribution::WheelFilename, metadata::LocalMetadata, Error, HuakResult,
+};
 
 #[derive(Clone, Debug)]
 /// The `Dependency` is an abstraction for `Package` data used as a cheap alternative
@@ -35,6 +44,65 @@ impl Dependency {
         &self.requirement().name
     }
 
+    /// Initialize a `Dependency` from the path to a local wheel or project directory.
+    ///
+    /// The `Dependency` is a direct reference to the path's `file://` URL.
+    pub fn from_path<T: AsRef<Path>>(path: T) -> HuakResult<Dependency> {
+        let path = std::fs::canonicalize(path.as_ref())
+            .map_err(|_| Error::InvalidDependencyPath(path.as_ref().into()))?;
+
+        let name = if path.is_dir() {
+            let metadata = LocalMetadata::new(path.join("pyproject.toml"))
+                .map_err(|_| Error::InvalidDependencyPath(path.clone()))?;
+            metadata.metadata().project_name().to_string()
+        } else {
+            let filename = path
+                .file_name()
+                .and_then(|it| it.to_str())
+                .unwrap_or_default();
+            WheelFilename::from_str(filename)
+                .map_err(|_| Error::InvalidDependencyPath(path.clone()))?
+                .name
+        };
+        let url = Url::from_file_path(&path)
+            .map_err(|_| Error::InvalidDependencyPath(path.clone()))?;
+
+        Ok(Dependency(Requirement {
+            name,
+            extras: None,
+            version_or_url: Some(VersionOrUrl::Url(url)),
+            marker: None,
+        }))
+    }
+
+    /// Get the `Dependency`'s URL if it's a direct reference (like `name @ git+https://...`).
+    pub fn url(&self) -> Option<&Url> {
+        match self.0.version_or_url.as_ref() {
+            Some(VersionOrUrl::Url(it)) => Some(it),
+            _ => None,
+        }
+    }
+
+    /// Get the local path of the `Dependency` if it's a direct reference to a `file://` URL.
+    pub fn local_path(&self) -> Option<PathBuf> {
+        self.url()
+            .filter(|it| it.scheme() == "file")
+            .and_then(|it| it.to_file_path().ok())
+    }
+
+    /// Pin the `Dependency` to a `Version` with a `PinStrategy`.
+    pub fn pin(
+        &mut self,
+        version: &Version,
+        strategy: PinStrategy,
+    ) -> HuakResult<()> {
+        self.0.version_or_url = strategy
+            .version_specifiers(version)?
+            .map(VersionOrUrl::VersionSpecifier);
+
+        Ok(())
+    }
+
     /// Get a reference to the `Dependency`'s `VersionSpecifiers`.
     #[allow(dead_code)]
     fn version_specifiers(&self) -> Option<&VersionSpecifiers> {
@@ -95,6 +163,98 @@ impl PartialEq for Dependency {
 
 impl Eq for Dependency {}
 
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+/// The `PinStrategy` determines the `VersionSpecifiers` a `Dependency` added without any
+/// version data is given for the `Version` installed.
+pub enum PinStrategy {
+    /// Pin to exactly the installed version (`==X.Y.Z`).
+    #[default]
+    Exact,
+    /// Allow compatible releases (`~=X.Y.Z`).
+    Compatible,
+    /// Allow the installed version or any newer version (`>=X.Y.Z`).
+    LowerBound,
+    /// Allow versions up to the next major release (`>=X.Y.Z,<X+1`). Below 1.0 the first
+    /// non-zero release segment is treated as the major release.
+    Caret,
+    /// Don't add any version specifiers.
+    None,
+}
+
+impl PinStrategy {
+    /// Get the `VersionSpecifiers` for a `Version` pinned with the `PinStrategy`.
+    pub fn version_specifiers(
+        &self,
+        version: &Version,
+    ) -> HuakResult<Option<VersionSpecifiers>> {
+        // Local version labels aren't allowed with most operators.
+        let mut version = version.clone();
+        version.local = None;
+
+        let specifiers = match self {
+            PinStrategy::Exact => vec![(Operator::Equal, version)],
+            PinStrategy::Compatible => {
+                // The compatible release operator needs at least two release segments.
+                if version.release.len() < 2 {
+                    version.release.push(0);
+                }
+                vec![(Operator::TildeEqual, version)]
+            }
+            PinStrategy::LowerBound => {
+                vec![(Operator::GreaterThanEqual, version)]
+            }
+            PinStrategy::Caret => {
+                let major = version
+                    .release
+                    .iter()
+                    .position(|it| *it != 0)
+                    .unwrap_or(version.release.len() - 1);
+                // The upper bound keeps the epoch so it's comparable with the lower bound.
+                let mut upper = version.clone();
+                upper.release.truncate(major + 1);
+                upper.release[major] += 1;
+                upper.pre = None;
+                upper.post = None;
+                upper.dev = None;
+                vec![
+                    (Operator::GreaterThanEqual, version),
+                    (Operator::LessThan, upper),
+                ]
+            }
+            PinStrategy::None => return Ok(None),
+        };
+
+        specifiers
+            .into_iter()
+            .map(|(operator, version)| {
+                VersionSpecifier::new(operator, version, false)
+                    .map_err(Error::InvalidVersionString)
+            })
+            .collect::<HuakResult<VersionSpecifiers>>()
+            .map(Some)
+    }
+}
+
+impl FromStr for PinStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(PinStrategy::Exact),
+            "compatible" => Ok(PinStrategy::Compatible),
+            "lower-bound" => Ok(PinStrategy::LowerBound),
+            "caret" => Ok(PinStrategy::Caret),
+            "none" => Ok(PinStrategy::None),
+            _ => Err(Error::HuakConfigurationError(format!(
+                "invalid pin strategy {s} (expected one of exact, compatible, lower-bound, caret, none)"
+            ))),
+        }
+    }
+}
+
 /// Construct an `Iterator` over an `IntoIterator` of `&str`s.
 ///
 /// ```
@@ -125,4 +285,95 @@ mod tests {
             pep440_rs::VersionSpecifiers::from_str("==0.0.0").unwrap()
         );
     }
+
+    #[test]
+    fn dependency_pin() {
+        let version = Version::from_str("1.2.3").unwrap();
+        let pins = [
+            (PinStrategy::Exact, "package-name ==1.2.3"),
+            (PinStrategy::Compatible, "package-name ~=1.2.3"),
+            (PinStrategy::LowerBound, "package-name >=1.2.3"),
+            (PinStrategy::Caret, "package-name >=1.2.3, <2"),
+            (PinStrategy::None, "package-name"),
+        ];
+
+        for (strategy, expected) in pins {
+            let mut dep = Dependency::from_str("package-name").unwrap();
+            dep.pin(&version, strategy).unwrap();
+            assert_eq!(dep.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn pin_strategy_caret_below_one() {
+        let specifiers = |it: &str| {
+            PinStrategy::Caret
+                .version_specifiers(&Version::from_str(it).unwrap())
+                .unwrap()
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(specifiers("0.4.1"), ">=0.4.1, <0.5");
+        assert_eq!(specifiers("0.0.3"), ">=0.0.3, <0.0.4");
+        assert_eq!(specifiers("0.0.0"), ">=0.0.0, <0.0.1");
+        assert_eq!(
+            PinStrategy::Compatible
+                .version_specifiers(&Version::from_str("5").unwrap())
+                .unwrap()
+                .unwrap()
+                .to_string(),
+            "~=5.0"
+        );
+    }
+
+    #[test]
+    fn pin_strategy_caret_epoch() {
+        let specifiers = PinStrategy::Caret
+            .version_specifiers(&Version::from_str("1!2.3rc1").unwrap())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(specifiers.to_string(), ">=1!2.3rc1, <1!3");
+        assert!(specifiers.contains(&Version::from_str("1!2.9").unwrap()));
+        assert!(!specifiers.contains(&Version::from_str("1!3.0").unwrap()));
+        assert!(!specifiers.contains(&Version::from_str("2.4").unwrap()));
+    }
+
+    #[test]
+    fn dependency_from_path() {
+        let path = crate::test_resources_dir_path()
+            .join("mock-wheels")
+            .join("mock_utils-1.0.0-py3-none-any.whl");
+        let dep = Dependency::from_path(&path).unwrap();
+
+        assert_eq!(dep.name(), "mock_utils");
+        assert_eq!(dep.local_path().unwrap(), path.canonicalize().unwrap());
+
+        let dep = Dependency::from_path(
+            crate::test_resources_dir_path().join("mock-project"),
+        )
+        .unwrap();
+
+        assert_eq!(dep.name(), "mock_project");
+        assert!(dep.url().unwrap().as_str().starts_with("file://"));
+        assert!(Dependency::from_path(
+            crate::test_resources_dir_path().join("mock-index")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn dependency_url() {
+        let dep = Dependency::from_str(
+            "mock-package @ git+https://github.com/mock/mock-package@v1.0",
+        )
+        .unwrap();
+
+        assert_eq!(
+            dep.url().unwrap().as_str(),
+            "git+https://github.com/mock/mock-package@v1.0"
+        );
+        assert!(dep.local_path().is_none());
+    }
 }
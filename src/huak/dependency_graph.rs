@@ -0,0 +1,254 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use indexmap::IndexMap;
+use pep440_rs::Version;
+use pep508_rs::{MarkerEnvironment, Requirement};
+
+use crate::{
+    package::normalized_package_name, site_packages::InstalledDistribution,
+};
+
+/// A graph of the project's declared dependency groups and the distributions installed to
+/// its environment.
+///
+/// Edges are the requirements whose environment markers apply to the environment. The
+/// requirements of installed distributions are evaluated with every extra requested of
+/// them.
+pub struct DependencyGraph {
+    /// The requirements declared for each dependency group.
+    groups: IndexMap<String, Vec<Requirement>>,
+    /// The installed packages by normalized name.
+    packages: IndexMap<String, GraphPackage>,
+}
+
+/// An installed package in a `DependencyGraph`.
+#[derive(Debug, Clone)]
+pub struct GraphPackage {
+    /// The name of the package.
+    pub name: String,
+    /// The installed `Version` of the package.
+    pub version: Version,
+    /// The requirements of the package that apply to the environment.
+    pub requires: Vec<Requirement>,
+}
+
+/// Something that depends on a package in a `DependencyGraph`.
+#[derive(Debug, Clone, Copy)]
+pub enum Dependent<'a> {
+    /// A dependency group declared by the project.
+    Group(&'a str, &'a Requirement),
+    /// An installed package.
+    Package(&'a GraphPackage, &'a Requirement),
+}
+
+impl DependencyGraph {
+    /// Build a `DependencyGraph` for the requirements of each dependency group.
+    pub fn new(
+        groups: IndexMap<String, Vec<Requirement>>,
+        distributions: &[InstalledDistribution],
+        env: &MarkerEnvironment,
+    ) -> DependencyGraph {
+        let groups = groups
+            .into_iter()
+            .map(|(group, reqs)| {
+                let reqs = reqs
+                    .into_iter()
+                    .filter(|req| req.evaluate_markers(env, Vec::new()))
+                    .collect();
+                (group, reqs)
+            })
+            .collect::<IndexMap<_, Vec<_>>>();
+        let distributions = distributions
+            .iter()
+            .map(|it| (it.normalized_name(), it))
+            .collect::<IndexMap<_, _>>();
+
+        // Walk the requirements from the dependency groups to find the extras requested of
+        // each package. A package is revisited whenever it's requested with a new extra.
+        let mut extras: HashMap<String, BTreeSet<String>> = HashMap::new();
+        let mut queue =
+            groups.values().flatten().cloned().collect::<VecDeque<_>>();
+        while let Some(req) = queue.pop_front() {
+            let name = normalized_package_name(&req.name);
+            let Some(dist) = distributions.get(&name) else {
+                continue;
+            };
+            let requested = req
+                .extras
+                .unwrap_or_default()
+                .into_iter()
+                .map(|it| normalized_package_name(&it))
+                .collect::<BTreeSet<_>>();
+            let visited = extras.contains_key(&name);
+            let known = extras.entry(name).or_default();
+            if visited && requested.is_subset(known) {
+                continue;
+            }
+            known.extend(requested);
+            let known = known.iter().cloned().collect::<Vec<_>>();
+            queue.extend(
+                dist.metadata
+                    .requires_dist
+                    .iter()
+                    .filter(|it| it.evaluate_markers(env, known.clone()))
+                    .cloned(),
+            );
+        }
+
+        let packages = distributions
+            .into_iter()
+            .map(|(name, dist)| {
+                let known = extras
+                    .get(&name)
+                    .map(|it| it.iter().cloned().collect::<Vec<_>>())
+                    .unwrap_or_default();
+                let requires = dist
+                    .metadata
+                    .requires_dist
+                    .iter()
+                    .filter(|it| it.evaluate_markers(env, known.clone()))
+                    .cloned()
+                    .collect();
+                let package = GraphPackage {
+                    name: dist.name().to_string(),
+                    version: dist.version().clone(),
+                    requires,
+                };
+                (name, package)
+            })
+            .collect();
+
+        DependencyGraph { groups, packages }
+    }
+
+    /// Get the requirements declared for each dependency group.
+    pub fn groups(&self) -> &IndexMap<String, Vec<Requirement>> {
+        &self.groups
+    }
+
+    /// Get an installed `GraphPackage` by name.
+    pub fn package(&self, name: &str) -> Option<&GraphPackage> {
+        self.packages.get(&normalized_package_name(name))
+    }
+
+    /// Get every installed `GraphPackage`.
+    pub fn packages(&self) -> impl Iterator<Item = &GraphPackage> {
+        self.packages.values()
+    }
+
+    /// Get the `Dependent`s of a package. Dependency groups come first.
+    pub fn dependents(&self, name: &str) -> Vec<Dependent<'_>> {
+        let name = normalized_package_name(name);
+        let groups = self.groups.iter().flat_map(|(group, reqs)| {
+            reqs.iter()
+                .filter(|req| normalized_package_name(&req.name) == name)
+                .map(|req| Dependent::Group(group, req))
+        });
+        let packages = self.packages.values().flat_map(|pkg| {
+            pkg.requires
+                .iter()
+                .filter(|req| normalized_package_name(&req.name) == name)
+                .map(move |req| Dependent::Package(pkg, req))
+        });
+
+        groups.chain(packages).collect()
+    }
+
+    /// Get the dependency groups that require a package directly or through other
+    /// packages, in the order they're declared.
+    pub fn requiring_groups(&self, name: &str) -> Vec<&str> {
+        let mut found = HashSet::new();
+        let mut visited = HashSet::from([normalized_package_name(name)]);
+        let mut queue = VecDeque::from([name.to_string()]);
+        while let Some(name) = queue.pop_front() {
+            for dependent in self.dependents(&name) {
+                match dependent {
+                    Dependent::Group(group, _) => {
+                        found.insert(group);
+                    }
+                    Dependent::Package(pkg, _) => {
+                        if visited.insert(normalized_package_name(&pkg.name)) {
+                            queue.push_back(pkg.name.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        self.groups
+            .keys()
+            .map(String::as_str)
+            .filter(|it| found.contains(it))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        site_packages::installed_distributions, test_marker_environment,
+        test_resources_dir_path,
+    };
+    use std::str::FromStr;
+
+    fn mock_graph() -> DependencyGraph {
+        let dists = installed_distributions(
+            test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+        let mut groups = IndexMap::new();
+        groups.insert(
+            "required".to_string(),
+            vec![Requirement::from_str("mock-web[speedups]").unwrap()],
+        );
+        groups.insert(
+            "dev".to_string(),
+            vec![
+                Requirement::from_str("mock.legacy").unwrap(),
+                Requirement::from_str("mock-missing").unwrap(),
+                Requirement::from_str("mock-windows ; sys_platform == 'win32'")
+                    .unwrap(),
+            ],
+        );
+
+        DependencyGraph::new(groups, &dists, &test_marker_environment())
+    }
+
+    #[test]
+    fn dependency_graph_requires() {
+        let graph = mock_graph();
+
+        assert_eq!(graph.groups()["dev"].len(), 2);
+        let web = graph.package("mock_web").unwrap();
+        assert_eq!(
+            web.requires
+                .iter()
+                .map(|it| it.name.as_str())
+                .collect::<Vec<_>>(),
+            ["mock-utils", "mock-speedups"]
+        );
+        assert!(graph.package("mock-missing").is_none());
+    }
+
+    #[test]
+    fn dependency_graph_dependents() {
+        let graph = mock_graph();
+        let names = graph
+            .dependents("mock-utils")
+            .iter()
+            .map(|it| match it {
+                Dependent::Group(group, _) => group.to_string(),
+                Dependent::Package(pkg, _) => pkg.name.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["Mock.Legacy", "mock-web"]);
+        assert!(matches!(
+            graph.dependents("mock-legacy")[0],
+            Dependent::Group("dev", _)
+        ));
+        assert_eq!(graph.requiring_groups("mock_utils"), ["required", "dev"]);
+        assert!(graph.requiring_groups("mock-broken").is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+use std::{fmt::Display, str::FromStr};
+
+use pep440_rs::{Version, VersionSpecifiers};
+use pep508_rs::Requirement;
+
+use crate::{package::normalized_package_name, Error};
+
+/// The core metadata of a Python distribution (see https://packaging.python.org/en/latest/specifications/core-metadata/).
+///
+/// Core metadata is read from a wheel's METADATA file, an installed distribution's
+/// METADATA or PKG-INFO file, or a PEP 658 metadata file served by an index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreMetadata {
+    /// The name of the distribution.
+    pub name: String,
+    /// The PEP 440 `Version` of the distribution.
+    pub version: Version,
+    /// The requirements of the distribution.
+    pub requires_dist: Vec<Requirement>,
+    /// The Requires-Dist values that couldn't be parsed as requirements.
+    pub invalid_requires_dist: Vec<String>,
+    /// The versions of Python the distribution supports.
+    pub requires_python: Option<VersionSpecifiers>,
+    /// The extras the distribution provides.
+    pub provides_extra: Vec<String>,
+    /// The first line of the License field.
+    pub license: Option<String>,
+    /// The SPDX license expression of the License-Expression field (see
+    /// https://peps.python.org/pep-0639/).
+    pub license_expression: Option<String>,
+    /// The trove classifiers of the distribution.
+    pub classifiers: Vec<String>,
+}
+
+impl FromStr for CoreMetadata {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name = None;
+        let mut version = None;
+        let mut requires_dist = Vec::new();
+        let mut invalid_requires_dist = Vec::new();
+        let mut requires_python = None;
+        let mut provides_extra = Vec::new();
+        let mut license = None;
+        let mut license_expression = None;
+        let mut classifiers = Vec::new();
+
+        // The metadata's fields are headers ending at the first empty line. Continuation lines
+        // start with whitespace and are skipped, so only the first line of multiline fields
+        // (like License) is read.
+        for line in s.lines() {
+            if line.is_empty() {
+                break;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            if key.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let value = value.trim();
+            match key.to_lowercase().as_str() {
+                "name" => name = Some(value.to_string()),
+                "version" => {
+                    version = Some(
+                        Version::from_str(value)
+                            .map_err(Error::InvalidVersionString)?,
+                    )
+                }
+                // A requirement that can't be parsed is kept aside instead of failing the
+                // whole distribution.
+                "requires-dist" => match Requirement::from_str(value) {
+                    Ok(it) => requires_dist.push(it),
+                    Err(_) => invalid_requires_dist.push(value.to_string()),
+                },
+                "requires-python" if !value.is_empty() => {
+                    requires_python =
+                        Some(VersionSpecifiers::from_str(value).map_err(
+                            |e| Error::InvalidVersionString(e.to_string()),
+                        )?)
+                }
+                "provides-extra" => provides_extra.push(value.to_string()),
+                "license" if !value.is_empty() => {
+                    license = Some(value.to_string())
+                }
+                "license-expression" if !value.is_empty() => {
+                    license_expression = Some(value.to_string())
+                }
+                "classifier" => classifiers.push(value.to_string()),
+                _ => (),
+            }
+        }
+
+        let (Some(name), Some(version)) = (name, version) else {
+            return Err(Error::InvalidCoreMetadata(
+                "missing name or version".to_string(),
+            ));
+        };
+
+        Ok(CoreMetadata {
+            name,
+            version,
+            requires_dist,
+            invalid_requires_dist,
+            requires_python,
+            provides_extra,
+            license,
+            license_expression,
+            classifiers,
+        })
+    }
+}
+
+/// The parts of a wheel's filename (see https://packaging.python.org/en/latest/specifications/binary-distribution-format/).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WheelFilename {
+    /// The name of the distribution.
+    pub name: String,
+    /// The PEP 440 `Version` of the distribution.
+    pub version: Version,
+    /// The optional build tag.
+    pub build_tag: Option<String>,
+    /// The Python tags the wheel is compatible with.
+    pub python_tags: Vec<String>,
+    /// The ABI tags the wheel is compatible with.
+    pub abi_tags: Vec<String>,
+    /// The platform tags the wheel is compatible with.
+    pub platform_tags: Vec<String>,
+}
+
+impl FromStr for WheelFilename {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(stem) = s.strip_suffix(".whl") else {
+            return Err(Error::InvalidDistributionFilename(s.to_string()));
+        };
+
+        let parts = stem.split('-').collect::<Vec<_>>();
+        let (name, version, build_tag, tags) = match parts.as_slice() {
+            [name, version, python, abi, platform] => {
+                (name, version, None, [python, abi, platform])
+            }
+            [name, version, build, python, abi, platform] => {
+                (name, version, Some(build), [python, abi, platform])
+            }
+            _ => return Err(Error::InvalidDistributionFilename(s.to_string())),
+        };
+        let [python, abi, platform] = tags.map(|tag| {
+            tag.split('.').map(ToString::to_string).collect::<Vec<_>>()
+        });
+
+        Ok(WheelFilename {
+            name: name.to_string(),
+            version: Version::from_str(version).map_err(|_| {
+                Error::InvalidDistributionFilename(s.to_string())
+            })?,
+            build_tag: build_tag.map(ToString::to_string),
+            python_tags: python,
+            abi_tags: abi,
+            platform_tags: platform,
+        })
+    }
+}
+
+impl Display for WheelFilename {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.name, self.version)?;
+        if let Some(it) = self.build_tag.as_ref() {
+            write!(f, "-{it}")?;
+        }
+        write!(
+            f,
+            "-{}-{}-{}.whl",
+            self.python_tags.join("."),
+            self.abi_tags.join("."),
+            self.platform_tags.join(".")
+        )
+    }
+}
+
+/// Get the `Version` of a source distribution from its filename if it belongs to the
+/// package named.
+pub fn sdist_version(filename: &str, package_name: &str) -> Option<Version> {
+    let stem = filename
+        .strip_suffix(".tar.gz")
+        .or_else(|| filename.strip_suffix(".zip"))?;
+    let (name, version) = stem.rsplit_once('-')?;
+
+    if normalized_package_name(name) != normalized_package_name(package_name) {
+        return None;
+    }
+
+    Version::from_str(version).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn core_metadata_from_str() {
+        let metadata = CoreMetadata::from_str(
+            r#"Metadata-Version: 2.1
+Name: black
+Version: 22.8.0
+Summary: The uncompromising code formatter.
+Requires-Python: >=3.6.2
+Requires-Dist: click (>=8.0.0)
+Requires-Dist: uvloop (>=0.15.2) ; extra == 'uvloop'
+Requires-Dist: colorama ; platform_system = 'Windows'
+Provides-Extra: uvloop
+License: MIT License
+        Copyright (c) 2018 Łukasz Langa
+Classifier: License :: OSI Approved :: MIT License
+Classifier: Programming Language :: Python
+Description-Content-Type: text/markdown
+
+Name: not-black
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(metadata.name, "black");
+        assert_eq!(metadata.version, Version::from_str("22.8.0").unwrap());
+        assert_eq!(metadata.requires_dist.len(), 2);
+        assert_eq!(metadata.requires_dist[0].name, "click");
+        assert_eq!(
+            metadata.invalid_requires_dist,
+            ["colorama ; platform_system = 'Windows'"]
+        );
+        assert_eq!(
+            metadata.requires_python,
+            Some(VersionSpecifiers::from_str(">=3.6.2").unwrap())
+        );
+        assert_eq!(metadata.provides_extra, ["uvloop"]);
+        assert_eq!(metadata.license.as_deref(), Some("MIT License"));
+        assert_eq!(metadata.license_expression, None);
+        assert_eq!(metadata.classifiers.len(), 2);
+    }
+
+    #[test]
+    fn wheel_filename_from_str() {
+        let name = "charset_normalizer-3.1.0-1-cp311-cp311-manylinux_2_17_x86_64.manylinux2014_x86_64.whl";
+        let filename = WheelFilename::from_str(name).unwrap();
+
+        assert_eq!(filename.name, "charset_normalizer");
+        assert_eq!(filename.version, Version::from_str("3.1.0").unwrap());
+        assert_eq!(filename.build_tag.as_deref(), Some("1"));
+        assert_eq!(filename.python_tags, ["cp311"]);
+        assert_eq!(
+            filename.platform_tags,
+            ["manylinux_2_17_x86_64", "manylinux2014_x86_64"]
+        );
+        assert_eq!(filename.to_string(), name);
+        assert!(WheelFilename::from_str("click-8.1.3.tar.gz").is_err());
+    }
+
+    #[test]
+    fn sdist_filename_version() {
+        assert_eq!(
+            sdist_version("zope.interface-6.0.tar.gz", "zope-interface"),
+            Some(Version::from_str("6.0").unwrap())
+        );
+        assert_eq!(sdist_version("click-8.1.3.tar.gz", "black"), None);
+    }
+}
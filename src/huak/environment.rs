@@ -77,6 +77,19 @@ pub fn env_path_values() -> Option<Vec<PathBuf>> {
     None
 }
 
+/// Find an executable by name in the directories of the `PATH` environment variable.
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    let file_name = match cfg!(windows) {
+        true => format!("{name}.exe"),
+        false => name.to_string(),
+    };
+
+    env_path_values()?
+        .into_iter()
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
 /// Get the OsString value of the enrionment variable `PATH`.
 pub fn env_path_string() -> Option<OsString> {
     std::env::var_os("PATH")
@@ -6,12 +6,24 @@ pub type HuakResult<T> = Result<T, Error>;
 
 #[derive(ThisError, Debug)]
 pub enum Error {
+    #[error("an advisory database could not be found (use --db or set HUAK_ADVISORY_DB)")]
+    AdvisoryDatabaseNotFound,
+    #[error("a cache directory could not be found (set HUAK_CACHE_DIR)")]
+    CacheNotFound,
     #[error("a problem with argument parsing occurred: {0}")]
     ClapError(#[from] clap::Error),
+    #[error(
+        "only local project directories can be installed in editable mode: {0}"
+    )]
+    DependencyNotEditable(String),
+    #[error("dependencies could not be resolved:\n{0}")]
+    DependencyResolutionFailure(String),
     #[error("dependency group(s): {0}, cannot be both included and excluded")]
     DependencyGroupConflict(String),
     #[error("dependency group(s) not found: {0}")]
     DependencyGroupNotFound(String),
+    #[error("dependency groups include each other: {0}")]
+    DependencyGroupCycle(String),
     #[error("a directory already exists: {0}")]
     DirectoryExists(PathBuf),
     #[error("a problem with the environment occurred: {0}")]
@@ -22,12 +34,28 @@ pub enum Error {
     GlobError(#[from] glob::GlobError),
     #[error("a problem occurred with a glob pattern: {0}")]
     GlobPatternError(#[from] glob::PatternError),
+    #[error("a problem with an http request occurred: {0}")]
+    HTTPError(Box<ureq::Error>),
     #[error("a problem with huak configuration occurred: {0}")]
     HuakConfigurationError(String),
+    #[error("an installer could not be found: {0}")]
+    InstallerNotFound(String),
     #[error("a problem with huak's internals occurred: {0}")]
     InternalError(String),
+    #[error("an advisory could not be parsed: {0}")]
+    InvalidAdvisory(String),
+    #[error("a problem occurred with core metadata parsing: {0}")]
+    InvalidCoreMetadata(String),
+    #[error("a dependency path must be a wheel or a project directory: {0}")]
+    InvalidDependencyPath(PathBuf),
+    #[error("a distribution filename could not be parsed: {0}")]
+    InvalidDistributionFilename(String),
     #[error("a version number could not be parsed: {0}")]
     InvalidVersionString(String),
+    #[error("a wheel could not be installed: {0}")]
+    InvalidWheel(String),
+    #[error("a problem with the keyring occurred: {0}")]
+    KeyringError(String),
     #[error("a problem occurred with json deserialization: {0}")]
     JSONSerdeError(#[from] serde_json::Error),
     #[error("a problem with io occurred: {0}")]
@@ -38,10 +66,18 @@ pub enum Error {
     PEP440Error(#[from] pep440_rs::Pep440Error),
     #[error("a problem occurred with PEP508 parsing: {0}")]
     PEP508Error(#[from] pep508_rs::Pep508Error),
+    #[error("licenses were rejected by the license policy: {0}")]
+    LicensesRejected(String),
+    #[error("a lockfile could not be found")]
+    LockfileNotFound,
+    #[error("the lockfile needs to be updated: {0}")]
+    LockfileOutOfDate(String),
     #[error("a metadata file already exists")]
     MetadataFileFound,
     #[error("a metadata file could not be found")]
     MetadataFileNotFound,
+    #[error("network access is needed while offline: {0}")]
+    NetworkUnavailable(String),
     #[error("a package version could not be found")]
     PackageVersionNotFound,
     #[error("a project already exists")]
@@ -64,8 +100,14 @@ pub enum Error {
     TOMLEditDeserializationError(#[from] toml_edit::de::Error),
     #[error("a problem with toml serialization occurred {0}")]
     TOMLEditSerializationError(#[from] toml_edit::ser::Error),
+    #[error("a problem occurred with url parsing: {0}")]
+    URLParseError(#[from] url::ParseError),
     #[error("a feature is unimplemented: {0}")]
     Unimplemented(String),
     #[error("a problem with utf-8 parsing occurred: {0}")]
     Utf8Error(#[from] std::str::Utf8Error),
+    #[error("vulnerable packages were found: {0}")]
+    VulnerabilitiesFound(String),
+    #[error("a problem occurred with a zip archive: {0}")]
+    ZipError(#[from] zip::result::ZipError),
 }
@@ -2,6 +2,7 @@ use crate::error::{Error, HuakResult};
 use std::{
     fs,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 #[allow(dead_code)]
@@ -40,6 +41,54 @@ pub fn copy_dir<T: AsRef<Path>>(from: T, to: T) -> HuakResult<()> {
     Ok(())
 }
 
+/// A temporary file, removed when it's dropped.
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Write a `TempFile` to the system's temporary directory. `name` ends its file name.
+    pub fn new(name: &str, contents: &str) -> HuakResult<TempFile> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "huak-{}-{}-{name}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&path, contents)?;
+
+        Ok(TempFile { path })
+    }
+
+    /// Get a reference to the path of the `TempFile`.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+/// Format a number of bytes for display (e.g. "1.5 MiB").
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
 /// Get an iterator over all paths found in each directory.
 pub fn flatten_directories(
     directories: impl IntoIterator<Item = PathBuf>,
@@ -100,7 +149,7 @@ pub fn last_path_component<T: AsRef<Path>>(path: T) -> HuakResult<String> {
     let path = path.as_ref();
     let path = path
         .components()
-        .last()
+        .next_back()
         .ok_or(Error::InternalError(format!(
             "failed to parse path {}",
             path.display()
@@ -131,6 +180,13 @@ mod tests {
         assert!(to.join("mock-project").join("pyproject.toml").exists());
     }
 
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+
     #[test]
     fn test_find_root_file_bottom_up() {
         let tmp = tempdir().unwrap().into_path();
@@ -0,0 +1,774 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read, Seek},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use indexmap::IndexMap;
+use pep440_rs::{Version, VersionSpecifiers};
+use regex::Regex;
+use serde::Deserialize;
+use url::Url;
+
+use crate::{
+    credentials::{CredentialStore, Credentials},
+    distribution::{sdist_version, CoreMetadata, WheelFilename},
+    package::normalized_package_name,
+    Error, HuakResult,
+};
+
+const SIMPLE_JSON_CONTENT_TYPE: &str = "application/vnd.pypi.simple.v1+json";
+/// The URL of the package index used when no other index is configured.
+pub const DEFAULT_INDEX_URL: &str = "https://pypi.org/simple/";
+/// The environment variable pip reads its index URL from.
+const PIP_INDEX_URL_ENV_VAR: &str = "PIP_INDEX_URL";
+
+/// A source of Python distributions Huak can resolve packages from.
+pub trait PackageIndex {
+    /// Get the `DistributionFile`s available for a package.
+    fn files(&self, package_name: &str) -> HuakResult<Vec<DistributionFile>>;
+
+    /// Get the `CoreMetadata` of a `DistributionFile`.
+    fn metadata(&self, file: &DistributionFile) -> HuakResult<CoreMetadata>;
+
+    /// Download a `DistributionFile`.
+    fn download(&self, file: &DistributionFile) -> HuakResult<Vec<u8>>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A distribution file listed by a `PackageIndex`.
+pub struct DistributionFile {
+    /// The name of the file.
+    pub filename: String,
+    /// Where the file can be downloaded from.
+    pub url: Url,
+    /// The PEP 440 `Version` parsed from the filename.
+    pub version: Version,
+    /// Hashes of the file keyed by their algorithm.
+    pub hashes: IndexMap<String, String>,
+    /// The versions of Python the distribution supports.
+    pub requires_python: Option<VersionSpecifiers>,
+    /// Whether the file has been yanked (see https://peps.python.org/pep-0592/).
+    pub yanked: bool,
+    /// The URL of the file's core metadata if the index serves it separately (see https://peps.python.org/pep-0658/).
+    pub metadata_url: Option<Url>,
+}
+
+impl DistributionFile {
+    /// Initialize a `DistributionFile` if the filename is a distribution of the package named.
+    fn new(filename: &str, url: Url, package_name: &str) -> Option<Self> {
+        let version = if filename.ends_with(".whl") {
+            let wheel = WheelFilename::from_str(filename).ok()?;
+            if normalized_package_name(&wheel.name)
+                != normalized_package_name(package_name)
+            {
+                return None;
+            }
+            wheel.version
+        } else {
+            sdist_version(filename, package_name)?
+        };
+
+        Some(DistributionFile {
+            filename: filename.to_string(),
+            url,
+            version,
+            hashes: IndexMap::new(),
+            requires_python: None,
+            yanked: false,
+            metadata_url: None,
+        })
+    }
+
+    /// Check if the `DistributionFile` is a wheel.
+    pub fn is_wheel(&self) -> bool {
+        self.filename.ends_with(".whl")
+    }
+}
+
+/// A PEP 503 "simple" repository (e.g. https://pypi.org/simple/).
+///
+/// Project pages are requested as PEP 691 JSON and fall back to HTML. A file:// URL can be
+/// used to serve a simple repository from a directory where each project page is
+/// <project>/index.json or <project>/index.html.
+pub struct SimpleIndex {
+    url: Url,
+    agent: ureq::Agent,
+    /// The `Credentials` sent with requests to the index's host.
+    credentials: Option<Credentials>,
+}
+
+impl SimpleIndex {
+    /// Initialize a `SimpleIndex` from the URL of its root.
+    pub fn new(url: &str) -> HuakResult<SimpleIndex> {
+        let mut url = Url::parse(url)?;
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+
+        Ok(SimpleIndex {
+            url,
+            agent: ureq::Agent::new(),
+            credentials: None,
+        })
+    }
+
+    /// Authenticate requests to the `SimpleIndex`'s host with `Credentials`.
+    pub fn with_credentials(
+        mut self,
+        credentials: Option<Credentials>,
+    ) -> SimpleIndex {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Make a GET request, with the `Credentials` if it's to the index's host.
+    fn get(&self, url: &Url) -> ureq::Request {
+        let request = self.agent.request_url("GET", url);
+        match self.credentials.as_ref() {
+            Some(it) if url.host_str() == self.url.host_str() => {
+                request.set("Authorization", &it.authorization())
+            }
+            _ => request,
+        }
+    }
+
+    /// Get the project page of a package and whether it's JSON.
+    fn project_page(
+        &self,
+        package_name: &str,
+    ) -> HuakResult<(Url, String, bool)> {
+        let url = self
+            .url
+            .join(&format!("{}/", normalized_package_name(package_name)))?;
+
+        if url.scheme() == "file" {
+            let dir = file_url_path(&url)?;
+            let json = dir.join("index.json");
+            if json.exists() {
+                return Ok((url, std::fs::read_to_string(json)?, true));
+            }
+            let html = dir.join("index.html");
+            if html.exists() {
+                return Ok((url, std::fs::read_to_string(html)?, false));
+            }
+            return Ok((url, String::new(), false));
+        }
+
+        let response = match self
+            .get(&url)
+            .set(
+                "Accept",
+                &format!("{SIMPLE_JSON_CONTENT_TYPE}, text/html;q=0.1"),
+            )
+            .call()
+        {
+            Ok(it) => it,
+            // A project the index doesn't have has no files.
+            Err(ureq::Error::Status(404, _)) => {
+                return Ok((url, String::new(), false))
+            }
+            Err(e) => return Err(Error::HTTPError(Box::new(e))),
+        };
+        let is_json = response.content_type().ends_with("json");
+        let url = Url::parse(response.get_url())?;
+
+        Ok((url, response.into_string()?, is_json))
+    }
+
+    /// Read the bytes at a URL.
+    fn read_url(&self, url: &Url) -> HuakResult<Vec<u8>> {
+        if url.scheme() == "file" {
+            return Ok(std::fs::read(file_url_path(url)?)?);
+        }
+
+        let response = self
+            .get(url)
+            .call()
+            .map_err(|e| Error::HTTPError(Box::new(e)))?;
+        let mut bytes = Vec::new();
+        response.into_reader().read_to_end(&mut bytes)?;
+
+        Ok(bytes)
+    }
+}
+
+impl PackageIndex for SimpleIndex {
+    fn files(&self, package_name: &str) -> HuakResult<Vec<DistributionFile>> {
+        let (url, page, is_json) = self.project_page(package_name)?;
+
+        if is_json {
+            parse_json_project_page(&page, &url, package_name)
+        } else {
+            parse_html_project_page(&page, &url, package_name)
+        }
+    }
+
+    fn metadata(&self, file: &DistributionFile) -> HuakResult<CoreMetadata> {
+        if let Some(url) = file.metadata_url.as_ref() {
+            let bytes = self.read_url(url)?;
+            return CoreMetadata::from_str(std::str::from_utf8(&bytes)?);
+        }
+
+        if !file.is_wheel() {
+            return Err(Error::Unimplemented(format!(
+                "reading metadata from source distribution {}",
+                file.filename
+            )));
+        }
+
+        wheel_metadata(Cursor::new(self.read_url(&file.url)?))
+    }
+
+    fn download(&self, file: &DistributionFile) -> HuakResult<Vec<u8>> {
+        self.read_url(&file.url)
+    }
+}
+
+/// A directory of distribution files (like pip's --find-links).
+pub struct LocalIndex {
+    path: PathBuf,
+}
+
+impl LocalIndex {
+    /// Initialize a `LocalIndex` from the path to its directory.
+    pub fn new<T: AsRef<Path>>(path: T) -> HuakResult<LocalIndex> {
+        Ok(LocalIndex {
+            path: std::fs::canonicalize(path)?,
+        })
+    }
+}
+
+impl PackageIndex for LocalIndex {
+    fn files(&self, package_name: &str) -> HuakResult<Vec<DistributionFile>> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            let Some(filename) = path.file_name().and_then(|it| it.to_str())
+            else {
+                continue;
+            };
+            let Ok(url) = Url::from_file_path(&path) else {
+                continue;
+            };
+            if let Some(file) =
+                DistributionFile::new(filename, url, package_name)
+            {
+                files.push(file);
+            }
+        }
+        files.sort_by(|a, b| a.filename.cmp(&b.filename));
+
+        Ok(files)
+    }
+
+    fn metadata(&self, file: &DistributionFile) -> HuakResult<CoreMetadata> {
+        if !file.is_wheel() {
+            return Err(Error::Unimplemented(format!(
+                "reading metadata from source distribution {}",
+                file.filename
+            )));
+        }
+
+        wheel_metadata(std::fs::File::open(file_url_path(&file.url)?)?)
+    }
+
+    fn download(&self, file: &DistributionFile) -> HuakResult<Vec<u8>> {
+        Ok(std::fs::read(file_url_path(&file.url)?)?)
+    }
+}
+
+/// Several `PackageIndex`es searched together (like pip's --extra-index-url and
+/// --find-links). Files are listed from every index in order.
+pub struct MultiIndex {
+    indexes: Vec<Box<dyn PackageIndex>>,
+}
+
+impl MultiIndex {
+    /// Initialize a `MultiIndex` from the `PackageIndex`es to search.
+    pub fn new(indexes: Vec<Box<dyn PackageIndex>>) -> MultiIndex {
+        MultiIndex { indexes }
+    }
+
+    /// Use the first index a `DistributionFile` can be read from.
+    fn first_ok<T>(
+        &self,
+        f: impl Fn(&dyn PackageIndex) -> HuakResult<T>,
+    ) -> HuakResult<T> {
+        let mut result = Err(Error::InternalError(
+            "no package index is configured".to_string(),
+        ));
+        for index in self.indexes.iter() {
+            result = f(index.as_ref());
+            if result.is_ok() {
+                break;
+            }
+        }
+
+        result
+    }
+}
+
+impl PackageIndex for MultiIndex {
+    fn files(&self, package_name: &str) -> HuakResult<Vec<DistributionFile>> {
+        let mut files = Vec::new();
+        for index in self.indexes.iter() {
+            files.extend(index.files(package_name)?);
+        }
+
+        Ok(files)
+    }
+
+    fn metadata(&self, file: &DistributionFile) -> HuakResult<CoreMetadata> {
+        self.first_ok(|index| index.metadata(file))
+    }
+
+    fn download(&self, file: &DistributionFile) -> HuakResult<Vec<u8>> {
+        self.first_ok(|index| index.download(file))
+    }
+}
+
+/// A `PackageIndex` listing specific packages from only their own index (see the
+/// `[tool.huak.sources]` table) and every other package from a default index.
+pub struct SourcedIndex {
+    default: Box<dyn PackageIndex>,
+    indexes: Vec<Box<dyn PackageIndex>>,
+    /// The position of each package's index in `indexes`, by normalized package name.
+    sources: HashMap<String, usize>,
+}
+
+impl SourcedIndex {
+    /// Initialize a `SourcedIndex` from the default `PackageIndex` and the URL or path of
+    /// the index each package is listed from.
+    pub fn new(
+        default: Box<dyn PackageIndex>,
+        locations: &IndexMap<String, String>,
+        credentials: &CredentialStore,
+    ) -> HuakResult<SourcedIndex> {
+        let mut urls: Vec<&String> = Vec::new();
+        let mut indexes = Vec::new();
+        let mut sources = HashMap::new();
+        for (name, location) in locations {
+            let i = match urls.iter().position(|it| *it == location) {
+                Some(i) => i,
+                None => {
+                    urls.push(location);
+                    indexes.push(package_index(location, credentials)?);
+                    indexes.len() - 1
+                }
+            };
+            sources.insert(normalized_package_name(name), i);
+        }
+
+        Ok(SourcedIndex {
+            default,
+            indexes,
+            sources,
+        })
+    }
+
+    /// Get the `PackageIndex` a package is listed from.
+    fn index(&self, package_name: &str) -> &dyn PackageIndex {
+        match self.sources.get(&normalized_package_name(package_name)) {
+            Some(i) => self.indexes[*i].as_ref(),
+            None => self.default.as_ref(),
+        }
+    }
+
+    /// Get the `PackageIndex` a `DistributionFile` was listed from.
+    fn file_index(&self, file: &DistributionFile) -> &dyn PackageIndex {
+        self.sources
+            .iter()
+            .find(|(name, _)| {
+                DistributionFile::new(&file.filename, file.url.clone(), name)
+                    .is_some()
+            })
+            .map_or(self.default.as_ref(), |(_, i)| self.indexes[*i].as_ref())
+    }
+}
+
+impl PackageIndex for SourcedIndex {
+    fn files(&self, package_name: &str) -> HuakResult<Vec<DistributionFile>> {
+        self.index(package_name).files(package_name)
+    }
+
+    fn metadata(&self, file: &DistributionFile) -> HuakResult<CoreMetadata> {
+        self.file_index(file).metadata(file)
+    }
+
+    fn download(&self, file: &DistributionFile) -> HuakResult<Vec<u8>> {
+        self.file_index(file).download(file)
+    }
+}
+
+/// Get the URL of the index to use when none is given: pip's `PIP_INDEX_URL` or PyPI.
+pub fn default_index_url() -> String {
+    std::env::var(PIP_INDEX_URL_ENV_VAR)
+        .unwrap_or(DEFAULT_INDEX_URL.to_string())
+}
+
+/// Get the `PackageIndex` at a URL or path.
+///
+/// Directories (or file:// URLs) containing distribution files are used as a `LocalIndex`.
+/// Anything else is used as a `SimpleIndex`, authenticated with the `CredentialStore`.
+pub fn package_index(
+    location: &str,
+    credentials: &CredentialStore,
+) -> HuakResult<Box<dyn PackageIndex>> {
+    let path = match Url::parse(location) {
+        Ok(url) if url.scheme() == "file" => file_url_path(&url)?,
+        Ok(url) => {
+            return Ok(Box::new(
+                SimpleIndex::new(location)?
+                    .with_credentials(credentials.credentials(&url)?),
+            ))
+        }
+        Err(_) => PathBuf::from(location),
+    };
+
+    if !path.is_dir() {
+        return Err(Error::HuakConfigurationError(format!(
+            "package index {location} could not be found"
+        )));
+    }
+
+    let contains_distributions = std::fs::read_dir(&path)?.any(|entry| {
+        entry.ok().map_or(false, |it| {
+            let name = it.file_name().to_string_lossy().to_string();
+            name.ends_with(".whl")
+                || name.ends_with(".tar.gz")
+                || name.ends_with(".zip")
+        })
+    });
+    if contains_distributions {
+        return Ok(Box::new(LocalIndex::new(&path)?));
+    }
+
+    let url = Url::from_directory_path(std::fs::canonicalize(&path)?).map_err(
+        |_| {
+            Error::HuakConfigurationError(format!(
+                "package index {location} could not be found"
+            ))
+        },
+    )?;
+
+    Ok(Box::new(SimpleIndex::new(url.as_str())?))
+}
+
+/// Read the `CoreMetadata` from a wheel's .dist-info/METADATA file.
+pub fn wheel_metadata<R: Read + Seek>(reader: R) -> HuakResult<CoreMetadata> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let Some(name) = archive
+        .file_names()
+        .find(|name| {
+            let mut parts = name.split('/');
+            matches!(
+                (parts.next(), parts.next(), parts.next()),
+                (Some(dir), Some("METADATA"), None) if dir.ends_with(".dist-info")
+            )
+        })
+        .map(ToString::to_string)
+    else {
+        return Err(Error::InvalidCoreMetadata(
+            "the wheel has no .dist-info/METADATA file".to_string(),
+        ));
+    };
+
+    let mut contents = String::new();
+    archive.by_name(&name)?.read_to_string(&mut contents)?;
+
+    CoreMetadata::from_str(&contents)
+}
+
+fn file_url_path(url: &Url) -> HuakResult<PathBuf> {
+    url.to_file_path()
+        .map_err(|_| Error::InternalError(format!("{url} is not a file path")))
+}
+
+#[derive(Deserialize)]
+struct JsonProjectPage {
+    #[serde(default)]
+    files: Vec<JsonProjectFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct JsonProjectFile {
+    filename: String,
+    url: String,
+    #[serde(default)]
+    hashes: IndexMap<String, String>,
+    requires_python: Option<String>,
+    #[serde(default)]
+    yanked: serde_json::Value,
+    #[serde(default)]
+    core_metadata: serde_json::Value,
+    #[serde(default)]
+    dist_info_metadata: serde_json::Value,
+}
+
+/// Parse the `DistributionFile`s listed on a PEP 691 JSON project page.
+fn parse_json_project_page(
+    page: &str,
+    url: &Url,
+    package_name: &str,
+) -> HuakResult<Vec<DistributionFile>> {
+    let page: JsonProjectPage = serde_json::from_str(page)?;
+
+    let mut files = Vec::new();
+    for item in page.files {
+        let file_url = url.join(&item.url)?;
+        let Some(mut file) =
+            DistributionFile::new(&item.filename, file_url, package_name)
+        else {
+            continue;
+        };
+
+        file.hashes = item.hashes;
+        file.requires_python = item
+            .requires_python
+            .as_deref()
+            .and_then(|it| VersionSpecifiers::from_str(it).ok());
+        file.yanked = is_truthy(&item.yanked);
+        if is_truthy(&item.core_metadata) || is_truthy(&item.dist_info_metadata)
+        {
+            file.metadata_url = Some(metadata_url(&file.url)?);
+        }
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+/// Parse the `DistributionFile`s listed on a PEP 503 HTML project page.
+fn parse_html_project_page(
+    page: &str,
+    url: &Url,
+    package_name: &str,
+) -> HuakResult<Vec<DistributionFile>> {
+    let anchor_re = Regex::new(r"(?is)<a\s([^>]*)>(.*?)</a>")?;
+    let attribute_re =
+        Regex::new(r#"(?s)([\w-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#)?;
+    let base_re =
+        Regex::new(r#"(?is)<base\s[^>]*href\s*=\s*["']([^"']*)["']"#)?;
+
+    let base = match base_re.captures(page) {
+        Some(captures) => url.join(&unescape_html(&captures[1]))?,
+        None => url.clone(),
+    };
+
+    let mut files = Vec::new();
+    for anchor in anchor_re.captures_iter(page) {
+        let attributes = attribute_re
+            .captures_iter(&anchor[1])
+            .map(|it| {
+                let value =
+                    it.get(2).or_else(|| it.get(3)).map_or("", |m| m.as_str());
+                (it[1].to_lowercase(), unescape_html(value))
+            })
+            .collect::<IndexMap<_, _>>();
+        let Some(href) = attributes.get("href") else {
+            continue;
+        };
+
+        let mut file_url = base.join(href)?;
+        let filename = unescape_html(anchor[2].trim());
+        let filename = match filename.is_empty() {
+            true => file_url
+                .path_segments()
+                .and_then(|mut it| it.next_back())
+                .unwrap_or_default()
+                .to_string(),
+            false => filename,
+        };
+
+        // The hash of the file is given as a URL fragment formatted as <algorithm>=<digest>.
+        let mut hashes = IndexMap::new();
+        if let Some((algorithm, digest)) =
+            file_url.fragment().and_then(|it| it.split_once('='))
+        {
+            hashes.insert(algorithm.to_string(), digest.to_string());
+        }
+        file_url.set_fragment(None);
+
+        let Some(mut file) =
+            DistributionFile::new(&filename, file_url, package_name)
+        else {
+            continue;
+        };
+
+        file.hashes = hashes;
+        file.requires_python = attributes
+            .get("data-requires-python")
+            .and_then(|it| VersionSpecifiers::from_str(it).ok());
+        file.yanked = attributes.contains_key("data-yanked");
+        if ["data-core-metadata", "data-dist-info-metadata"]
+            .iter()
+            .any(|key| attributes.get(*key).map_or(false, |it| it != "false"))
+        {
+            file.metadata_url = Some(metadata_url(&file.url)?);
+        }
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+/// Get the URL of a distribution file's PEP 658 metadata.
+fn metadata_url(url: &Url) -> HuakResult<Url> {
+    let mut url = url.clone();
+    url.set_path(&format!("{}.metadata", url.path()));
+
+    Ok(url)
+}
+
+/// Check if a PEP 691 value that may be a boolean, a string, or a mapping is set.
+fn is_truthy(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => false,
+        serde_json::Value::Bool(it) => *it,
+        _ => true,
+    }
+}
+
+fn unescape_html(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_resources_dir_path;
+
+    fn mock_index() -> SimpleIndex {
+        let path = test_resources_dir_path().join("mock-index");
+        SimpleIndex::new(Url::from_directory_path(path).unwrap().as_str())
+            .unwrap()
+    }
+
+    #[test]
+    fn simple_index_html_files() {
+        let index = mock_index();
+        let files = index.files("Mock_Web").unwrap();
+
+        assert_eq!(files.len(), 4);
+        assert_eq!(files[0].filename, "mock_web-1.0.0-py3-none-any.whl");
+        assert_eq!(files[0].version, Version::from_str("1.0.0").unwrap());
+        assert_eq!(
+            files[0].hashes.get("sha256").map(String::as_str),
+            Some("0000000000000000000000000000000000000000000000000000000000000001")
+        );
+        assert!(files[0].metadata_url.is_some());
+        assert!(files.iter().any(|file| file.yanked));
+        assert!(files.iter().any(|file| !file.is_wheel()));
+    }
+
+    #[test]
+    fn simple_index_json_files() {
+        let index = mock_index();
+        let files = index.files("mock-db").unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(
+            files[1].requires_python,
+            Some(VersionSpecifiers::from_str(">=4").unwrap())
+        );
+    }
+
+    #[test]
+    fn simple_index_metadata() {
+        let index = mock_index();
+        let files = index.files("mock-web").unwrap();
+        let metadata = index.metadata(&files[0]).unwrap();
+
+        assert_eq!(metadata.name, "mock-web");
+        assert_eq!(metadata.requires_dist[0].name, "mock-utils");
+        assert!(index.files("missing-package").unwrap().is_empty());
+    }
+
+    #[test]
+    fn local_index_files_and_metadata() {
+        let index =
+            LocalIndex::new(test_resources_dir_path().join("mock-wheels"))
+                .unwrap();
+        let files = index.files("mock-utils").unwrap();
+        let metadata = index.metadata(&files[0]).unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(metadata.version, Version::from_str("1.0.0").unwrap());
+        assert_eq!(metadata.requires_dist[0].name, "mock-speedups");
+    }
+
+    #[test]
+    fn package_index_location() {
+        let dir = test_resources_dir_path().join("mock-wheels");
+        let index =
+            package_index(dir.to_str().unwrap(), &CredentialStore::default())
+                .unwrap();
+
+        assert_eq!(index.files("mock-utils").unwrap().len(), 1);
+
+        let url = Url::from_directory_path(
+            test_resources_dir_path().join("mock-index"),
+        )
+        .unwrap();
+        let index =
+            package_index(url.as_str(), &CredentialStore::default()).unwrap();
+
+        assert_eq!(index.files("mock-web").unwrap().len(), 4);
+        assert!(package_index("/missing/index", &CredentialStore::default())
+            .is_err());
+    }
+
+    #[test]
+    fn multi_index_files() {
+        let index = MultiIndex::new(vec![
+            Box::new(mock_index()),
+            Box::new(
+                LocalIndex::new(test_resources_dir_path().join("mock-wheels"))
+                    .unwrap(),
+            ),
+        ]);
+        let files = index.files("mock-utils").unwrap();
+
+        assert_eq!(files.len(), 5);
+        assert_eq!(
+            index.download(files.last().unwrap()).unwrap(),
+            std::fs::read(
+                test_resources_dir_path()
+                    .join("mock-wheels")
+                    .join("mock_utils-1.0.0-py3-none-any.whl")
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn sourced_index_files() {
+        let mut locations = IndexMap::new();
+        locations.insert(
+            "Mock_Utils".to_string(),
+            test_resources_dir_path()
+                .join("mock-wheels")
+                .display()
+                .to_string(),
+        );
+        let index = SourcedIndex::new(
+            Box::new(mock_index()),
+            &locations,
+            &CredentialStore::default(),
+        )
+        .unwrap();
+        let files = index.files("mock-utils").unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(index.download(&files[0]).is_ok());
+        assert_eq!(index.files("mock-web").unwrap().len(), 4);
+    }
+}
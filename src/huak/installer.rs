@@ -0,0 +1,2063 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    io::{Cursor, Read, Seek, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use indexmap::IndexMap;
+use pep440_rs::Version;
+use pep508_rs::{MarkerEnvironment, Requirement, VersionOrUrl};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use url::Url;
+
+use crate::{
+    cache::Cache,
+    credentials::CredentialStore,
+    distribution::WheelFilename,
+    index::{
+        default_index_url, package_index, wheel_metadata, DistributionFile,
+        MultiIndex, PackageIndex, SourcedIndex,
+    },
+    lockfile::LockedPackage,
+    package::normalized_package_name,
+    python_environment::{ArchiveInfo, DirectUrl, InstallOptions},
+    resolver::{
+        is_satisfied_by, override_requirement, Resolver, ResolverOptions,
+    },
+    site_packages::{
+        installed_distributions, normalize_path, InstalledDistribution,
+        RecordEntry,
+    },
+    sources::NamedIndex,
+    sys, Error, HuakResult,
+};
+
+/// The name Huak records in the INSTALLER file of the distributions it installs.
+const INSTALLER_NAME: &str = "huak";
+/// A Python script byte-compiling the files listed on stdin and printing the path of each
+/// compiled file.
+const COMPILE_SCRIPT: &str = r#"
+import py_compile, sys
+for path in sys.stdin.read().splitlines():
+    try:
+        print(py_compile.compile(path, doraise=True))
+    except Exception:
+        pass
+"#;
+
+/// The directories the files of a wheel are installed to (see
+/// https://docs.python.org/3/library/sysconfig.html#installation-paths).
+#[derive(Debug, Clone)]
+pub struct InstallScheme {
+    /// The directory for pure and platform-specific modules (purelib and platlib).
+    pub site_packages: PathBuf,
+    /// The directory for scripts.
+    pub scripts: PathBuf,
+    /// The directory containing the C headers of each distribution.
+    pub headers: PathBuf,
+    /// The directory for data files.
+    pub data: PathBuf,
+    /// The Python interpreter scripts are run with.
+    pub python: PathBuf,
+}
+
+/// Options for the native `Installer` parsed from pip's install arguments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NativeInstallOptions {
+    /// The URL or path of the package index (pip's --index-url).
+    pub index_url: Option<String>,
+    /// The URLs or paths of other package indexes (pip's --extra-index-url).
+    pub extra_index_urls: Vec<String>,
+    /// Directories of distributions to install from (pip's --find-links).
+    pub find_links: Vec<String>,
+    /// Ignore the package indexes and only use `find_links`.
+    pub no_index: bool,
+    /// Don't install the requirements of the packages installed.
+    pub no_deps: bool,
+    /// Allow pre-release versions.
+    pub pre: bool,
+    /// Byte-compile the installed modules.
+    pub compile: bool,
+    /// Don't use the `Cache` (pip's --no-cache-dir).
+    pub no_cache: bool,
+    /// The URL or path of the only index specific packages are installed from, by
+    /// normalized package name.
+    pub package_index_urls: IndexMap<String, String>,
+    /// The named indexes credentials are found for.
+    pub indexes: IndexMap<String, NamedIndex>,
+    /// Requirements limiting the versions of packages that are installed.
+    pub constraints: Vec<Requirement>,
+    /// Requirements replacing every requirement on their packages.
+    pub overrides: Vec<Requirement>,
+    /// Install without network access, only from `find_links` directories or the `Cache`.
+    pub offline: bool,
+}
+
+impl NativeInstallOptions {
+    /// Get `NativeInstallOptions` from `InstallOptions`, parsing their raw values as pip's
+    /// install arguments. Options the native `Installer` doesn't support are an
+    /// `Error::Unimplemented`.
+    pub fn new(options: &InstallOptions) -> HuakResult<NativeInstallOptions> {
+        // Wheels are the only thing installed natively, so only-binary always holds.
+        if !options.no_binary.is_empty() {
+            return Err(Error::Unimplemented(
+                "installing with --no-binary".to_string(),
+            ));
+        }
+        if !options.trusted_hosts.is_empty() {
+            return Err(Error::Unimplemented(
+                "installing with --trusted-host".to_string(),
+            ));
+        }
+        let mut it = NativeInstallOptions {
+            index_url: options.index_url.clone(),
+            extra_index_urls: options.extra_index_urls.clone(),
+            find_links: options.find_links.clone(),
+            no_deps: options.no_deps,
+            pre: options.pre,
+            no_cache: options.no_cache,
+            package_index_urls: options.package_index_urls.clone(),
+            indexes: options.indexes.clone(),
+            constraints: parse_requirements(&options.constraints)?,
+            overrides: parse_requirements(&options.overrides)?,
+            no_index: options.offline,
+            offline: options.offline,
+            ..Default::default()
+        };
+        let mut values = options.values.iter().flatten();
+
+        while let Some(value) = values.next() {
+            let (flag, inline) = match value.split_once('=') {
+                Some((flag, arg)) if flag.starts_with("--") => {
+                    (flag, Some(arg.to_string()))
+                }
+                _ => (value.as_str(), None),
+            };
+            let mut arg = || {
+                inline.clone().or_else(|| values.next().cloned()).ok_or(
+                    Error::HuakConfigurationError(format!(
+                        "{flag} requires a value"
+                    )),
+                )
+            };
+            match flag {
+                "-i" | "--index-url" => it.index_url = Some(arg()?),
+                "--extra-index-url" => it.extra_index_urls.push(arg()?),
+                "-f" | "--find-links" => it.find_links.push(arg()?),
+                "--no-index" => it.no_index = true,
+                "--no-deps" => it.no_deps = true,
+                "--pre" => it.pre = true,
+                "--compile" => it.compile = true,
+                "--no-compile" => it.compile = false,
+                "--no-cache-dir" => it.no_cache = true,
+                "--only-binary" => {
+                    arg()?;
+                }
+                "-q"
+                | "--quiet"
+                | "-v"
+                | "--verbose"
+                | "--disable-pip-version-check" => (),
+                _ => {
+                    return Err(Error::Unimplemented(format!(
+                        "installing with {value}"
+                    )))
+                }
+            }
+        }
+
+        Ok(it)
+    }
+
+    /// Get the `PackageIndex` to install packages from. Offline the `Cache` is searched
+    /// too.
+    pub fn package_index(
+        &self,
+        cache: Option<&Cache>,
+    ) -> HuakResult<Box<dyn PackageIndex>> {
+        let credentials = self.credential_store()?;
+        let mut indexes = Vec::new();
+        if !self.no_index {
+            let url = self.index_url.clone().unwrap_or_else(default_index_url);
+            indexes.push(package_index(&url, &credentials)?);
+            for url in self.extra_index_urls.iter() {
+                indexes.push(package_index(url, &credentials)?);
+            }
+        }
+        for location in self.find_links.iter() {
+            indexes.push(package_index(location, &credentials)?);
+        }
+        if let Some(it) = cache.filter(|_| self.offline) {
+            indexes.push(Box::new(it.clone()));
+        }
+        let index = Box::new(MultiIndex::new(indexes));
+        if self.no_index || self.package_index_urls.is_empty() {
+            return Ok(index);
+        }
+
+        Ok(Box::new(SourcedIndex::new(
+            index,
+            &self.package_index_urls,
+            &credentials,
+        )?))
+    }
+
+    /// Get the `CredentialStore` for the package indexes and URLs installed from.
+    /// Credentials aren't needed offline.
+    pub fn credential_store(&self) -> HuakResult<CredentialStore> {
+        match self.offline {
+            true => Ok(CredentialStore::default()),
+            false => CredentialStore::new(&self.indexes),
+        }
+    }
+}
+
+/// A package installed by an `Installer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Installation {
+    /// The name of the package.
+    pub name: String,
+    /// The `Version` installed.
+    pub version: Version,
+    /// The `Version` that was replaced.
+    pub replaced: Option<Version>,
+}
+
+/// Installs packages from wheels without pip.
+///
+/// Packages are resolved with the `Resolver`, trying the versions already installed first.
+/// Everything to install is planned before anything is installed, so requirements the
+/// `Installer` can't handle (like source distributions or vcs URLs) are an
+/// `Error::Unimplemented` that leaves the environment untouched.
+///
+/// With a `Cache`, wheels are only downloaded and unpacked once and their files are hard
+/// linked into the environment.
+pub struct Installer<'a> {
+    scheme: &'a InstallScheme,
+    index: &'a dyn PackageIndex,
+    env: &'a MarkerEnvironment,
+    tags: &'a [String],
+    options: &'a NativeInstallOptions,
+    cache: Option<&'a Cache>,
+}
+
+/// A wheel an `Installer` is going to install.
+struct PlannedWheel {
+    name: String,
+    version: Version,
+    filename: WheelFilename,
+    source: WheelSource,
+}
+
+enum WheelSource {
+    /// A file listed by the `PackageIndex`.
+    Index(DistributionFile),
+    /// A wheel required by URL. It's read while planning to resolve its requirements.
+    Direct(Vec<u8>, DirectUrl),
+    /// A wheel locked to a URL with its hashes formatted as `<algorithm>:<digest>`.
+    Locked(Url, Vec<String>),
+}
+
+/// A wheel fetched by an `Installer`.
+enum FetchedWheel {
+    /// The wheel's contents.
+    Downloaded(Vec<u8>),
+    /// The directory the wheel is unpacked to in the `Cache`.
+    Cached(PathBuf),
+}
+
+impl<'a> Installer<'a> {
+    /// Initialize an `Installer` for an `InstallScheme`. Wheels are chosen by the tags the
+    /// interpreter supports, most preferred first.
+    pub fn new(
+        scheme: &'a InstallScheme,
+        index: &'a dyn PackageIndex,
+        env: &'a MarkerEnvironment,
+        tags: &'a [String],
+        options: &'a NativeInstallOptions,
+        cache: Option<&'a Cache>,
+    ) -> Installer<'a> {
+        Installer {
+            scheme,
+            index,
+            env,
+            tags,
+            options,
+            cache,
+        }
+    }
+
+    /// Install the packages needed to satisfy the `Requirement`s. Installed packages are
+    /// kept if they can be, unless `upgrade` is true and they're required directly.
+    pub fn install(
+        &self,
+        requirements: &[Requirement],
+        upgrade: bool,
+    ) -> HuakResult<Vec<Installation>> {
+        let installed = self.installed()?;
+        let requirements = requirements
+            .iter()
+            .filter(|it| it.evaluate_markers(self.env, Vec::new()))
+            .collect::<Vec<_>>();
+        let requested = requirements
+            .iter()
+            .map(|it| normalized_package_name(&it.name))
+            .collect::<HashSet<_>>();
+
+        // Wheels required by URL are read first so their requirements can be resolved.
+        let mut roots = Vec::new();
+        let mut direct = HashSet::new();
+        let mut planned = Vec::new();
+        for requirement in requirements {
+            let Some(VersionOrUrl::Url(url)) =
+                requirement.version_or_url.as_ref()
+            else {
+                roots.push(requirement.clone());
+                continue;
+            };
+            let filename = url_wheel_filename(url)?;
+            let bytes = read_url(url, &self.options.credential_store()?)?;
+            let metadata = wheel_metadata(Cursor::new(&bytes))?;
+            if !self.options.no_deps {
+                let extras = requirement.extras.clone().unwrap_or_default();
+                roots.extend(
+                    metadata
+                        .requires_dist
+                        .iter()
+                        .filter(|it| {
+                            it.evaluate_markers(self.env, extras.clone())
+                        })
+                        .cloned(),
+                );
+            }
+
+            let name = normalized_package_name(&metadata.name);
+            let direct_url = archive_direct_url(url, &bytes);
+            direct.insert(name.clone());
+            if installed
+                .get(&name)
+                .and_then(|it| it.direct_url.as_ref())
+                .map_or(false, |it| it.url == direct_url.url)
+            {
+                continue;
+            }
+            planned.push(PlannedWheel {
+                name: metadata.name,
+                version: metadata.version,
+                filename,
+                source: WheelSource::Direct(bytes, direct_url),
+            });
+        }
+
+        if roots.is_empty()
+            || (!upgrade
+                && planned.is_empty()
+                && self.satisfied(&roots, &installed))
+        {
+            return self.install_planned(planned, &installed, &requested);
+        }
+
+        let preferences = installed
+            .iter()
+            .filter(|(name, _)| !(upgrade && requested.contains(*name)))
+            .map(|(name, it)| (name.clone(), it.version().clone()))
+            .collect();
+        let options = ResolverOptions {
+            prereleases: self.options.pre,
+            preferences,
+            no_deps: self.options.no_deps,
+            constraints: self.options.constraints.clone(),
+            overrides: self.options.overrides.clone(),
+        };
+        let resolution =
+            Resolver::new(self.index, self.env, options).resolve(&roots)?;
+
+        for package in resolution.packages() {
+            let name = normalized_package_name(&package.name);
+            if direct.contains(&name)
+                || installed
+                    .get(&name)
+                    .map_or(false, |it| it.version() == &package.version)
+            {
+                continue;
+            }
+            let Some((file, filename)) = self.best_wheel(&package.files) else {
+                return Err(Error::Unimplemented(format!(
+                    "installing {} {} without a compatible wheel",
+                    package.name, package.version
+                )));
+            };
+            planned.push(PlannedWheel {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                filename,
+                source: WheelSource::Index(file.clone()),
+            });
+        }
+
+        self.install_planned(planned, &installed, &requested)
+    }
+
+    /// Install `LockedPackage`s exactly as they're pinned without their requirements.
+    /// Packages already installed at their locked version are kept.
+    pub fn install_locked(
+        &self,
+        packages: &[&LockedPackage],
+    ) -> HuakResult<Vec<Installation>> {
+        let installed = self.installed()?;
+
+        let mut planned = Vec::new();
+        for package in packages {
+            let name = normalized_package_name(&package.name);
+            if installed
+                .get(&name)
+                .map_or(false, |it| it.version() == &package.version)
+            {
+                continue;
+            }
+            let Some(mut url) =
+                package.source.as_ref().and_then(|it| Url::parse(it).ok())
+            else {
+                return Err(Error::Unimplemented(format!(
+                    "installing {} without a source",
+                    package.name
+                )));
+            };
+            let filename = url_wheel_filename(&url)?;
+            if self.options.offline && url.scheme() != "file" {
+                url = self.offline_url(package, &filename)?;
+            }
+            planned.push(PlannedWheel {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                filename,
+                source: WheelSource::Locked(url, package.hashes.clone()),
+            });
+        }
+
+        self.install_planned(planned, &installed, &HashSet::new())
+    }
+
+    /// Get where a `LockedPackage` with a remote source can be installed from offline: the
+    /// same wheel in a find-links directory, or its source if the wheel is cached.
+    fn offline_url(
+        &self,
+        package: &LockedPackage,
+        filename: &WheelFilename,
+    ) -> HuakResult<Url> {
+        let name = filename.to_string();
+        let found = self
+            .options
+            .find_links
+            .iter()
+            .map(|it| match Url::parse(it) {
+                Ok(url) if url.scheme() == "file" => {
+                    url.to_file_path().unwrap_or_default()
+                }
+                _ => PathBuf::from(it),
+            })
+            .map(|it| it.join(&name))
+            .find(|it| it.is_file());
+        if let Some(url) = found.and_then(|it| {
+            Url::from_file_path(std::fs::canonicalize(it).ok()?).ok()
+        }) {
+            return Ok(url);
+        }
+
+        let cached = package.hashes.iter().any(|it| {
+            it.strip_prefix("sha256:").map_or(false, |digest| {
+                self.cache.map_or(false, |cache| cache.contains(digest))
+            })
+        });
+        match (cached, package.source.as_ref()) {
+            (true, Some(source)) => Ok(Url::parse(source)?),
+            _ => Err(Error::NetworkUnavailable(format!(
+                "{name} isn't in the cache or a --find-links directory"
+            ))),
+        }
+    }
+
+    /// Install the `PlannedWheel`s, replacing the versions already installed.
+    fn install_planned(
+        &self,
+        planned: Vec<PlannedWheel>,
+        installed: &HashMap<String, InstalledDistribution>,
+        requested: &HashSet<String>,
+    ) -> HuakResult<Vec<Installation>> {
+        for wheel in planned.iter() {
+            let replaced = installed.get(&normalized_package_name(&wheel.name));
+            if replaced.map_or(false, |it| it.record().is_err()) {
+                return Err(Error::Unimplemented(format!(
+                    "replacing {} without a RECORD",
+                    wheel.name
+                )));
+            }
+        }
+
+        let mut others = installed.values().cloned().collect::<Vec<_>>();
+        let mut installations = Vec::new();
+        for wheel in planned {
+            let (fetched, direct_url) =
+                self.fetch(wheel.source, &wheel.filename)?;
+
+            let name = normalized_package_name(&wheel.name);
+            let replaced = installed.get(&name);
+            // The replaced distribution's files are moved aside until the wheel is installed
+            // so a failed install can be rolled back.
+            let stash = match replaced {
+                Some(it) => {
+                    Some(StashedDistribution::new(it, &others, self.scheme)?)
+                }
+                None => None,
+            };
+            let options = WheelInstallOptions {
+                requested: requested.contains(&name)
+                    || replaced.map_or(false, |it| it.requested),
+                direct_url,
+                compile: self.options.compile,
+            };
+            let result = match fetched {
+                FetchedWheel::Downloaded(bytes) => install_wheel(
+                    Cursor::new(bytes),
+                    &wheel.filename,
+                    self.scheme,
+                    &options,
+                ),
+                FetchedWheel::Cached(dir) => install_unpacked_wheel(
+                    &dir,
+                    &wheel.filename,
+                    self.scheme,
+                    &options,
+                    FileMode::Link,
+                ),
+            };
+            let dist_info = match result {
+                Ok(it) => it,
+                Err(e) => {
+                    if let Some(it) = stash {
+                        it.restore()?;
+                    }
+                    remove_incomplete_dist_info(&wheel.filename, self.scheme)?;
+                    return Err(e);
+                }
+            };
+            if let Some(it) = stash {
+                it.remove(self.scheme)?;
+            }
+
+            // Files of wheels installed earlier are shared with the ones installed later.
+            if let Some(it) = replaced {
+                others.retain(|other| other.path != it.path);
+            }
+            others.push(InstalledDistribution::new(&dist_info)?);
+
+            installations.push(Installation {
+                name: wheel.name,
+                version: wheel.version,
+                replaced: replaced.map(|it| it.version().clone()),
+            });
+        }
+
+        Ok(installations)
+    }
+
+    /// Get a wheel, downloading it unless it's already in the `Cache`. Wheels with hashes
+    /// are verified before they're used.
+    fn fetch(
+        &self,
+        source: WheelSource,
+        filename: &WheelFilename,
+    ) -> HuakResult<(FetchedWheel, Option<DirectUrl>)> {
+        let (hashes, direct_url) = match &source {
+            WheelSource::Index(file) => (
+                file.hashes
+                    .iter()
+                    .map(|(algorithm, digest)| format!("{algorithm}:{digest}"))
+                    .collect::<Vec<_>>(),
+                None,
+            ),
+            WheelSource::Direct(_, direct_url) => {
+                (Vec::new(), Some(direct_url.clone()))
+            }
+            WheelSource::Locked(_, hashes) => (hashes.clone(), None),
+        };
+
+        if let Some(cache) = self.cache {
+            let digest =
+                hashes.iter().find_map(|it| it.strip_prefix("sha256:"));
+            if let Some(digest) = digest.filter(|it| cache.contains(it)) {
+                let dir = cache.unpacked(digest, filename)?;
+                return Ok((FetchedWheel::Cached(dir), direct_url));
+            }
+        }
+
+        let bytes = match source {
+            WheelSource::Index(file) => {
+                let bytes = self.index.download(&file)?;
+                verify_hashes(&file.filename, &bytes, &hashes)?;
+                bytes
+            }
+            WheelSource::Direct(bytes, _) => bytes,
+            WheelSource::Locked(url, _) => {
+                let bytes = read_url(&url, &self.options.credential_store()?)?;
+                verify_hashes(url.as_str(), &bytes, &hashes)?;
+                bytes
+            }
+        };
+
+        match self.cache {
+            Some(cache) => {
+                let digest = cache.add_wheel(&bytes, filename)?;
+                let dir = cache.unpacked(&digest, filename)?;
+                Ok((FetchedWheel::Cached(dir), direct_url))
+            }
+            None => Ok((FetchedWheel::Downloaded(bytes), direct_url)),
+        }
+    }
+
+    /// Get the distributions installed to the `InstallScheme` by normalized name.
+    fn installed(&self) -> HuakResult<HashMap<String, InstalledDistribution>> {
+        if !self.scheme.site_packages.exists() {
+            return Ok(HashMap::new());
+        }
+
+        Ok(installed_distributions(&self.scheme.site_packages)?
+            .into_iter()
+            .map(|it| (it.normalized_name(), it))
+            .collect())
+    }
+
+    /// Check if the installed distributions satisfy the `Requirement`s and everything they
+    /// require.
+    fn satisfied(
+        &self,
+        requirements: &[Requirement],
+        installed: &HashMap<String, InstalledDistribution>,
+    ) -> bool {
+        let mut queue = requirements.iter().cloned().collect::<VecDeque<_>>();
+        let mut seen = HashSet::new();
+
+        while let Some(requirement) = queue.pop_front() {
+            let requirement =
+                override_requirement(&requirement, &self.options.overrides)
+                    .unwrap_or(requirement);
+            let name = normalized_package_name(&requirement.name);
+            let Some(dist) = installed.get(&name) else {
+                return false;
+            };
+            let constrained = self.options.constraints.iter().any(|it| {
+                normalized_package_name(&it.name) == name
+                    && it.evaluate_markers(self.env, Vec::new())
+                    && !is_satisfied_by(it, dist.version())
+            });
+            if constrained {
+                return false;
+            }
+            let satisfied = match requirement.version_or_url.as_ref() {
+                Some(VersionOrUrl::VersionSpecifier(it)) => {
+                    it.contains(dist.version())
+                }
+                Some(VersionOrUrl::Url(url)) => dist
+                    .direct_url
+                    .as_ref()
+                    .map_or(false, |it| it.url == url.as_str()),
+                None => true,
+            };
+            if !satisfied {
+                return false;
+            }
+
+            let mut extras = requirement.extras.unwrap_or_default();
+            extras.sort();
+            if self.options.no_deps || !seen.insert((name, extras.join(","))) {
+                continue;
+            }
+            queue.extend(
+                dist.metadata
+                    .requires_dist
+                    .iter()
+                    .filter(|it| it.evaluate_markers(self.env, extras.clone()))
+                    .cloned(),
+            );
+        }
+
+        true
+    }
+
+    /// Get the wheel the interpreter prefers most from `DistributionFile`s.
+    fn best_wheel<'b>(
+        &self,
+        files: &'b [DistributionFile],
+    ) -> Option<(&'b DistributionFile, WheelFilename)> {
+        let python_version = &self.env.python_full_version.1;
+
+        files
+            .iter()
+            .filter(|file| {
+                !file.yanked
+                    && file.is_wheel()
+                    && file
+                        .requires_python
+                        .as_ref()
+                        .map_or(true, |it| it.contains(python_version))
+            })
+            .filter_map(|file| {
+                let filename = WheelFilename::from_str(&file.filename).ok()?;
+                let priority = wheel_priority(&filename, self.tags)?;
+                Some((priority, file, filename))
+            })
+            .min_by_key(|(priority, ..)| *priority)
+            .map(|(_, file, filename)| (file, filename))
+    }
+}
+
+/// Get the position of a wheel's most preferred tag in the tags an interpreter supports, or
+/// `None` if the wheel isn't compatible.
+pub fn wheel_priority(
+    filename: &WheelFilename,
+    tags: &[String],
+) -> Option<usize> {
+    let mut priority = None;
+    for python in filename.python_tags.iter() {
+        for abi in filename.abi_tags.iter() {
+            for platform in filename.platform_tags.iter() {
+                let tag = format!("{python}-{abi}-{platform}");
+                if let Some(i) = tags.iter().position(|it| it == &tag) {
+                    priority = Some(priority.map_or(i, |it: usize| it.min(i)));
+                }
+            }
+        }
+    }
+
+    priority
+}
+
+/// Options for installing a single wheel.
+#[derive(Debug, Clone, Default)]
+pub struct WheelInstallOptions {
+    /// Mark the distribution as requested rather than installed as a dependency.
+    pub requested: bool,
+    /// Where the wheel came from if it was required by URL (see
+    /// https://peps.python.org/pep-0610/).
+    pub direct_url: Option<DirectUrl>,
+    /// Byte-compile the installed modules.
+    pub compile: bool,
+}
+
+/// Install a wheel to an `InstallScheme` (see
+/// https://packaging.python.org/en/latest/specifications/binary-distribution-format/).
+///
+/// The wheel is unpacked next to the site-packages directory and its files are moved into
+/// place (see `install_unpacked_wheel`). Returns the path to the installed .dist-info
+/// directory.
+pub fn install_wheel<R: Read + Seek>(
+    reader: R,
+    filename: &WheelFilename,
+    scheme: &InstallScheme,
+    options: &WheelInstallOptions,
+) -> HuakResult<PathBuf> {
+    let dir = scheme
+        .site_packages
+        .join(format!(".{}-{}.unpacked", filename.name, filename.version));
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+
+    let result = unpack_wheel(reader, filename, &dir).and_then(|_| {
+        install_unpacked_wheel(&dir, filename, scheme, options, FileMode::Move)
+    });
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+
+    result
+}
+
+/// Extract the files of a wheel to a directory. The files are checked against the wheel's
+/// RECORD.
+pub fn unpack_wheel<R: Read + Seek>(
+    reader: R,
+    filename: &WheelFilename,
+    dir: &Path,
+) -> HuakResult<()> {
+    let wheel = format!("{} {}", filename.name, filename.version);
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let dist_info = archive
+        .file_names()
+        .filter_map(|it| it.split('/').next())
+        .find(|it| is_dist_info_of(it, filename))
+        .map(ToString::to_string)
+        .ok_or(Error::InvalidWheel(format!(
+            "{wheel} has no .dist-info directory"
+        )))?;
+
+    let wheel_version =
+        read_archive_file(&mut archive, &format!("{dist_info}/WHEEL"))?
+            .lines()
+            .find_map(|it| it.strip_prefix("Wheel-Version:"))
+            .map(|it| it.trim().to_string())
+            .unwrap_or_default();
+    if wheel_version.split('.').next() != Some("1") {
+        return Err(Error::Unimplemented(format!(
+            "installing wheels of version {wheel_version}"
+        )));
+    }
+    let hashes =
+        read_archive_file(&mut archive, &format!("{dist_info}/RECORD"))?
+            .lines()
+            .filter_map(RecordEntry::from_line)
+            .filter_map(|it| Some((it.path, it.hash?)))
+            .collect::<HashMap<_, _>>();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let Some(path) = file.enclosed_name().map(Path::to_path_buf) else {
+            return Err(Error::InvalidWheel(format!(
+                "{wheel} contains the unsafe path {}",
+                file.name()
+            )));
+        };
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        if let Some(expected) = hashes.get(&path) {
+            if !record_hash_matches(&bytes, expected) {
+                return Err(Error::InvalidWheel(format!(
+                    "{} doesn't match its hash in the RECORD of {wheel}",
+                    path.display()
+                )));
+            }
+        }
+
+        let dest = dir.join(path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&dest, bytes)?;
+        if file.unix_mode().map_or(false, |it| it & 0o111 != 0) {
+            set_executable(&dest)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// How an unpacked wheel's files are put in place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileMode {
+    /// Move the files, leaving the unpacked wheel incomplete.
+    Move,
+    /// Hard link the files so the unpacked wheel can be installed again. Files are copied if
+    /// they can't be linked (like when they're on another file system).
+    Link,
+}
+
+/// Install a wheel unpacked with `unpack_wheel` to an `InstallScheme`.
+///
+/// Scripts get their shebang rewritten, console scripts are generated from the wheel's
+/// entry_points.txt, and a new RECORD listing every installed file is written. Returns the
+/// path to the installed .dist-info directory.
+pub fn install_unpacked_wheel(
+    dir: &Path,
+    filename: &WheelFilename,
+    scheme: &InstallScheme,
+    options: &WheelInstallOptions,
+    mode: FileMode,
+) -> HuakResult<PathBuf> {
+    let wheel = format!("{} {}", filename.name, filename.version);
+    let dist_info = fs::read_dir(dir)?
+        .filter_map(|it| it.ok())
+        .map(|it| it.file_name().to_string_lossy().to_string())
+        .find(|it| is_dist_info_of(it, filename))
+        .ok_or(Error::InvalidWheel(format!(
+            "{wheel} has no .dist-info directory"
+        )))?;
+    let data_dir = PathBuf::from(format!(
+        "{}.data",
+        dist_info.trim_end_matches(".dist-info")
+    ));
+    let entry_points =
+        fs::read_to_string(dir.join(&dist_info).join("entry_points.txt"))
+            .unwrap_or_default();
+    let scripts = entry_point_scripts(&entry_points);
+    if let Some(it) = scripts.iter().find(|it| !is_script_name(&it.name)) {
+        return Err(Error::InvalidWheel(format!(
+            "{wheel} has an entry point with the invalid script name {}",
+            it.name
+        )));
+    }
+    let entries = fs::read_to_string(dir.join(&dist_info).join("RECORD"))?
+        .lines()
+        .filter_map(RecordEntry::from_line)
+        .map(|it| (it.path.clone(), it))
+        .collect::<HashMap<_, _>>();
+
+    let mut record = Vec::new();
+    let mut modules = Vec::new();
+    let mut files = files_in(dir)?;
+    files.sort();
+    for file in files {
+        let path = relative_path(&file, dir);
+        // The RECORD is rewritten, which invalidates its signatures.
+        if path.starts_with(&dist_info)
+            && ["RECORD", "RECORD.jws", "RECORD.p7s"]
+                .iter()
+                .any(|it| path.ends_with(it))
+        {
+            continue;
+        }
+
+        let (dest, is_script) = match path.strip_prefix(&data_dir) {
+            Ok(rest) => {
+                let mut components = rest.components();
+                let key = components
+                    .next()
+                    .map(|it| it.as_os_str().to_string_lossy().to_string());
+                let rest = components.as_path();
+                match key.as_deref() {
+                    Some("purelib" | "platlib") => {
+                        (scheme.site_packages.join(rest), false)
+                    }
+                    Some("scripts") => (scheme.scripts.join(rest), true),
+                    Some("headers") => {
+                        (scheme.headers.join(&filename.name).join(rest), false)
+                    }
+                    Some("data") => (scheme.data.join(rest), false),
+                    _ => {
+                        return Err(Error::InvalidWheel(format!(
+                            "{wheel} contains {} in an unknown scheme",
+                            path.display()
+                        )))
+                    }
+                }
+            }
+            Err(_) => (scheme.site_packages.join(&path), false),
+        };
+
+        if is_script {
+            let bytes = rewrite_shebang(fs::read(&file)?, &scheme.python);
+            record.push(write_installed_file(
+                &dest,
+                &bytes,
+                true,
+                &scheme.site_packages,
+            )?);
+        } else {
+            place_file(&file, &dest, mode)?;
+            let hash = match entries.get(&path).and_then(|it| it.hash.clone()) {
+                Some(it) => it,
+                None => record_hash(&fs::read(&dest)?),
+            };
+            record.push(RecordEntry {
+                path: relative_path(&dest, &scheme.site_packages),
+                hash: Some(hash),
+                size: Some(fs::metadata(&dest)?.len()),
+            });
+        }
+        if dest.starts_with(&scheme.site_packages)
+            && dest.extension().map_or(false, |it| it == "py")
+        {
+            modules.push(dest);
+        }
+    }
+
+    let dist_info = scheme.site_packages.join(dist_info);
+    for script in scripts {
+        record.push(write_installed_file(
+            &scheme.scripts.join(&script.name),
+            script.launcher(&scheme.python).as_bytes(),
+            true,
+            &scheme.site_packages,
+        )?);
+    }
+
+    let mut metadata = vec![("INSTALLER", format!("{INSTALLER_NAME}\n"))];
+    if options.requested {
+        metadata.push(("REQUESTED", String::new()));
+    }
+    if let Some(it) = options.direct_url.as_ref() {
+        metadata.push(("direct_url.json", serde_json::to_string(it)?));
+    }
+    for (name, contents) in metadata {
+        record.push(write_installed_file(
+            &dist_info.join(name),
+            contents.as_bytes(),
+            false,
+            &scheme.site_packages,
+        )?);
+    }
+
+    if options.compile {
+        record.extend(compile_modules(
+            &scheme.python,
+            &modules,
+            &scheme.site_packages,
+        )?);
+    }
+
+    record.push(RecordEntry {
+        path: relative_path(&dist_info.join("RECORD"), &scheme.site_packages),
+        hash: None,
+        size: None,
+    });
+    let contents = record
+        .iter()
+        .map(|it| format!("{}\n", it.to_line()))
+        .collect::<String>();
+    fs::write(dist_info.join("RECORD"), contents)?;
+
+    Ok(dist_info)
+}
+
+/// Check if a directory name is the .dist-info directory of a wheel.
+fn is_dist_info_of(dir: &str, filename: &WheelFilename) -> bool {
+    dir.ends_with(".dist-info")
+        && dir.split('-').next().map_or(false, |it| {
+            normalized_package_name(it)
+                == normalized_package_name(&filename.name)
+        })
+}
+
+/// Put a file from an unpacked wheel in place.
+fn place_file(src: &Path, dest: &Path, mode: FileMode) -> HuakResult<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if dest.exists() {
+        fs::remove_file(dest)?;
+    }
+    let placed = match mode {
+        FileMode::Move => fs::rename(src, dest),
+        FileMode::Link => fs::hard_link(src, dest),
+    };
+    if placed.is_err() {
+        fs::copy(src, dest)?;
+    }
+
+    Ok(())
+}
+
+/// The files removed by uninstalling a distribution (or that would be).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uninstallation {
+    /// The name of the distribution.
+    pub name: String,
+    /// The `Version` of the distribution.
+    pub version: Version,
+    /// The files removed.
+    pub removed: Vec<PathBuf>,
+    /// Files in the distribution's RECORD that are kept because another installed
+    /// distribution owns them too, with the name of that distribution.
+    pub shared: Vec<(PathBuf, String)>,
+    /// Files listed for the distribution that are outside the `InstallScheme`. They're never
+    /// removed.
+    pub outside: Vec<PathBuf>,
+    /// The number of bytes freed.
+    pub freed: u64,
+}
+
+/// Uninstall an `InstalledDistribution` from an `InstallScheme` using its RECORD.
+///
+/// Files that are also in the RECORD of one of the `others` are kept. Byte-compiled
+/// modules and console scripts missing from the RECORD are removed too, and directories
+/// left empty are cleaned up. Files outside the site-packages, scripts, and data
+/// directories (like absolute paths in a RECORD) are never removed. Nothing is removed if
+/// `dry_run` is true.
+pub fn uninstall_distribution(
+    dist: &InstalledDistribution,
+    others: &[InstalledDistribution],
+    scheme: &InstallScheme,
+    dry_run: bool,
+) -> HuakResult<Uninstallation> {
+    let site_packages = &scheme.site_packages;
+    let owners = others
+        .iter()
+        .filter(|it| it.path != dist.path)
+        .filter_map(|it| Some((it.name(), it.record().ok()?)))
+        .flat_map(|(name, record)| {
+            record.into_iter().map(move |entry| {
+                (
+                    normalize_path(&site_packages.join(entry.path)),
+                    name.to_string(),
+                )
+            })
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut paths = Vec::new();
+    for entry in dist.record()? {
+        let path = normalize_path(&site_packages.join(entry.path));
+        if path.extension().map_or(false, |it| it == "py") {
+            paths.extend(compiled_paths(&path));
+        }
+        paths.push(path);
+    }
+    let entry_points = fs::read_to_string(dist.path.join("entry_points.txt"))
+        .unwrap_or_default();
+    for script in entry_point_scripts(&entry_points) {
+        paths.push(normalize_path(&scheme.scripts.join(script.name)));
+    }
+    if dist.path.is_dir() {
+        paths.extend(files_in(&dist.path)?);
+    } else {
+        paths.push(dist.path.clone());
+    }
+
+    let roots = [&scheme.site_packages, &scheme.scripts, &scheme.data]
+        .map(|it| normalize_path(it));
+    let mut seen = HashSet::new();
+    let mut removed = Vec::new();
+    let mut shared = Vec::new();
+    let mut outside = Vec::new();
+    let mut freed = 0;
+    for path in paths {
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        if !roots.iter().any(|it| path.starts_with(it)) {
+            outside.push(path);
+            continue;
+        }
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            continue;
+        }
+        if let Some(owner) = owners.get(&path) {
+            shared.push((path, owner.clone()));
+            continue;
+        }
+        freed += metadata.len();
+        removed.push(path);
+    }
+
+    if !dry_run {
+        for path in removed.iter() {
+            fs::remove_file(path)?;
+        }
+        remove_empty_dirs(&removed, scheme)?;
+    }
+
+    Ok(Uninstallation {
+        name: dist.name().to_string(),
+        version: dist.version().clone(),
+        removed,
+        shared,
+        outside,
+        freed,
+    })
+}
+
+/// The files of a distribution being replaced, moved aside until the new distribution is
+/// installed.
+struct StashedDistribution {
+    /// The directory the files are moved to.
+    dir: PathBuf,
+    /// The original path of each file and where it was moved.
+    files: Vec<(PathBuf, PathBuf)>,
+}
+
+impl StashedDistribution {
+    /// Move the files uninstalling an `InstalledDistribution` would remove to a hidden
+    /// directory in the site-packages directory. Files the `others` own too are left in
+    /// place.
+    fn new(
+        dist: &InstalledDistribution,
+        others: &[InstalledDistribution],
+        scheme: &InstallScheme,
+    ) -> HuakResult<StashedDistribution> {
+        let uninstallation =
+            uninstall_distribution(dist, others, scheme, true)?;
+        let dir = scheme.site_packages.join(format!(
+            ".{}-{}.replaced",
+            dist.normalized_name(),
+            dist.version()
+        ));
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+
+        let mut stash = StashedDistribution {
+            dir,
+            files: Vec::new(),
+        };
+        for (i, path) in uninstallation.removed.into_iter().enumerate() {
+            let stashed = stash.dir.join(i.to_string());
+            let moved =
+                place_file(&path, &stashed, FileMode::Move).and_then(|_| {
+                    match path.exists() {
+                        true => Ok(fs::remove_file(&path)?),
+                        false => Ok(()),
+                    }
+                });
+            if let Err(e) = moved {
+                stash.restore()?;
+                return Err(e);
+            }
+            stash.files.push((path, stashed));
+        }
+
+        Ok(stash)
+    }
+
+    /// Move the files back to where they were.
+    fn restore(self) -> HuakResult<()> {
+        for (path, stashed) in self.files.iter() {
+            place_file(stashed, path, FileMode::Move)?;
+        }
+        fs::remove_dir_all(&self.dir)?;
+
+        Ok(())
+    }
+
+    /// Remove the files, cleaning up the directories they leave empty.
+    fn remove(self, scheme: &InstallScheme) -> HuakResult<()> {
+        fs::remove_dir_all(&self.dir)?;
+        let paths = self
+            .files
+            .into_iter()
+            .map(|(path, _)| path)
+            .filter(|it| !it.exists())
+            .collect::<Vec<_>>();
+
+        remove_empty_dirs(&paths, scheme)
+    }
+}
+
+/// Remove the .dist-info directories of a wheel that are missing a RECORD, which is only
+/// written once every other file is installed.
+fn remove_incomplete_dist_info(
+    filename: &WheelFilename,
+    scheme: &InstallScheme,
+) -> HuakResult<()> {
+    let Ok(entries) = fs::read_dir(&scheme.site_packages) else {
+        return Ok(());
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir()
+            && is_dist_info_of(&entry.file_name().to_string_lossy(), filename)
+            && !path.join("RECORD").exists()
+        {
+            fs::remove_dir_all(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the byte-compiled files of a module.
+fn compiled_paths(path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) =
+        (path.parent(), path.file_stem().and_then(|it| it.to_str()))
+    else {
+        return Vec::new();
+    };
+    let pattern = format!(
+        "{}/{}.*.pyc",
+        glob::Pattern::escape(&dir.join("__pycache__").to_string_lossy()),
+        glob::Pattern::escape(stem)
+    );
+
+    glob::glob(&pattern)
+        .map(|it| it.filter_map(Result::ok).collect())
+        .unwrap_or_default()
+}
+
+/// Get every file in a directory and its subdirectories.
+fn files_in(dir: &Path) -> HuakResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Remove the directories left empty by removing files, stopping at the directories of the
+/// `InstallScheme`.
+fn remove_empty_dirs(
+    removed: &[PathBuf],
+    scheme: &InstallScheme,
+) -> HuakResult<()> {
+    let roots = [
+        &scheme.site_packages,
+        &scheme.scripts,
+        &scheme.headers,
+        &scheme.data,
+    ];
+    let dirs = removed
+        .iter()
+        .filter_map(|it| it.parent())
+        .collect::<HashSet<_>>();
+
+    for dir in dirs {
+        let mut dir = Some(dir);
+        while let Some(it) = dir {
+            let is_empty =
+                fs::read_dir(it).map_or(false, |mut it| it.next().is_none());
+            if roots.iter().any(|root| root.as_path() == it)
+                || !(it.starts_with(&scheme.data)
+                    || it.starts_with(&scheme.site_packages))
+                || !is_empty
+            {
+                break;
+            }
+            fs::remove_dir(it)?;
+            dir = it.parent();
+        }
+    }
+
+    Ok(())
+}
+
+/// A console or GUI script declared in an entry_points.txt file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EntryPointScript {
+    name: String,
+    module: String,
+    attr: String,
+}
+
+impl EntryPointScript {
+    /// Get the contents of a script calling the entry point with the interpreter.
+    fn launcher(&self, python: &Path) -> String {
+        let import = self.attr.split('.').next().unwrap_or_default();
+
+        format!(
+            r#"{}
+# -*- coding: utf-8 -*-
+import re
+import sys
+from {} import {import}
+if __name__ == "__main__":
+    sys.argv[0] = re.sub(r"(-script\.pyw|\.exe)?$", "", sys.argv[0])
+    sys.exit({}())
+"#,
+            shebang(python),
+            self.module,
+            self.attr
+        )
+    }
+}
+
+/// Parse the console and GUI scripts of an entry_points.txt file.
+fn entry_point_scripts(contents: &str) -> Vec<EntryPointScript> {
+    let mut scripts = Vec::new();
+    let mut section = "";
+
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(it) =
+            line.strip_prefix('[').and_then(|it| it.strip_suffix(']'))
+        {
+            section = it.trim();
+            continue;
+        }
+        if section != "console_scripts" && section != "gui_scripts" {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        // Extras (`module:attr [extra]`) don't change the script.
+        let value = value.split('[').next().unwrap_or_default();
+        let Some((module, attr)) = value.split_once(':') else {
+            continue;
+        };
+        scripts.push(EntryPointScript {
+            name: name.trim().to_string(),
+            module: module.trim().to_string(),
+            attr: attr.trim().to_string(),
+        });
+    }
+
+    scripts
+}
+
+/// Check if the name of an entry point's script is a file name, so the script can't be
+/// written outside the scripts directory.
+fn is_script_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && !Path::new(name).is_absolute()
+}
+
+/// Get a shebang running a script with the interpreter. Paths containing spaces can't be
+/// used in a shebang, so the script is run through /bin/sh instead.
+fn shebang(python: &Path) -> String {
+    let python = python.display().to_string();
+    if python.contains(' ') {
+        format!("#!/bin/sh\n'''exec' \"{python}\" \"$0\" \"$@\"\n' '''")
+    } else {
+        format!("#!{python}")
+    }
+}
+
+/// Replace the `#!python` shebang of a script from a wheel with one for the interpreter.
+fn rewrite_shebang(bytes: Vec<u8>, python: &Path) -> Vec<u8> {
+    let Some(rest) = bytes.strip_prefix(b"#!python") else {
+        return bytes;
+    };
+    let rest = rest.strip_prefix(b"w").unwrap_or(rest);
+
+    let mut script = shebang(python).into_bytes();
+    script.extend_from_slice(rest);
+    script
+}
+
+/// Write an installed file and get its `RecordEntry`.
+fn write_installed_file(
+    path: &Path,
+    bytes: &[u8],
+    executable: bool,
+    site_packages: &Path,
+) -> HuakResult<RecordEntry> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Replace rather than write through existing files in case they're hard links.
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    fs::write(path, bytes)?;
+    if executable {
+        set_executable(path)?;
+    }
+
+    Ok(RecordEntry {
+        path: relative_path(path, site_packages),
+        hash: Some(record_hash(bytes)),
+        size: Some(bytes.len() as u64),
+    })
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> HuakResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn set_executable(_path: &Path) -> HuakResult<()> {
+    Ok(())
+}
+
+/// Byte-compile Python modules with the interpreter and get the `RecordEntry` of each
+/// compiled file.
+fn compile_modules(
+    python: &Path,
+    modules: &[PathBuf],
+    site_packages: &Path,
+) -> HuakResult<Vec<RecordEntry>> {
+    if modules.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut child = Command::new(python)
+        .args(["-c", COMPILE_SCRIPT])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let paths = modules
+            .iter()
+            .map(|it| it.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        stdin.write_all(paths.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::SubprocessFailure(sys::SubprocessError::new(
+            output.status,
+        )));
+    }
+
+    let mut record = Vec::new();
+    for line in std::str::from_utf8(&output.stdout)?.lines() {
+        let path = PathBuf::from(line.trim());
+        let bytes = fs::read(&path)?;
+        record.push(RecordEntry {
+            path: relative_path(&path, site_packages),
+            hash: Some(record_hash(&bytes)),
+            size: Some(bytes.len() as u64),
+        });
+    }
+
+    Ok(record)
+}
+
+/// Get the hash of a file formatted for a RECORD (`sha256=<urlsafe-base64-digest>`).
+fn record_hash(bytes: &[u8]) -> String {
+    format!("sha256={}", URL_SAFE_NO_PAD.encode(Sha256::digest(bytes)))
+}
+
+/// Check a file against its hash in a RECORD. Hashes with unknown algorithms can't be
+/// checked.
+fn record_hash_matches(bytes: &[u8], expected: &str) -> bool {
+    let Some((algorithm, digest)) = expected.split_once('=') else {
+        return true;
+    };
+    let actual = match algorithm {
+        "sha256" => URL_SAFE_NO_PAD.encode(Sha256::digest(bytes)),
+        "sha384" => URL_SAFE_NO_PAD.encode(Sha384::digest(bytes)),
+        "sha512" => URL_SAFE_NO_PAD.encode(Sha512::digest(bytes)),
+        _ => return true,
+    };
+
+    actual == digest.trim_end_matches('=')
+}
+
+/// Check a downloaded file against hashes formatted as `<algorithm>:<digest>`. Files
+/// without hashes aren't checked.
+pub fn verify_hashes(
+    name: &str,
+    bytes: &[u8],
+    hashes: &[String],
+) -> HuakResult<()> {
+    if hashes.is_empty() {
+        return Ok(());
+    }
+    let digests = hashes
+        .iter()
+        .filter_map(|it| it.strip_prefix("sha256:"))
+        .collect::<Vec<_>>();
+    if digests.is_empty() {
+        return Err(Error::Unimplemented(format!(
+            "verifying {name} without a sha256 hash"
+        )));
+    }
+
+    let digest = format!("{:x}", Sha256::digest(bytes));
+    match digests.contains(&digest.as_str()) {
+        true => Ok(()),
+        false => Err(Error::InvalidWheel(format!(
+            "{name} doesn't match its hashes"
+        ))),
+    }
+}
+
+/// Parse PEP 508 requirement strings.
+pub fn parse_requirements(values: &[String]) -> HuakResult<Vec<Requirement>> {
+    values
+        .iter()
+        .map(|it| Requirement::from_str(it).map_err(Error::from))
+        .collect()
+}
+
+/// Get the `WheelFilename` of a URL if it's a wheel the native `Installer` can read.
+fn url_wheel_filename(url: &Url) -> HuakResult<WheelFilename> {
+    let filename = url
+        .path_segments()
+        .and_then(|mut it| it.next_back())
+        .unwrap_or_default();
+    if !matches!(url.scheme(), "file" | "http" | "https")
+        || !filename.ends_with(".whl")
+    {
+        return Err(Error::Unimplemented(format!("installing {url}")));
+    }
+
+    WheelFilename::from_str(filename)
+        .map_err(|_| Error::Unimplemented(format!("installing {url}")))
+}
+
+/// Read a file from a file:// or http(s):// URL, with credentials from the `CredentialStore`.
+pub fn read_url(
+    url: &Url,
+    credentials: &CredentialStore,
+) -> HuakResult<Vec<u8>> {
+    if url.scheme() == "file" {
+        let path = url.to_file_path().map_err(|_| {
+            Error::InternalError(format!("{url} is not a file path"))
+        })?;
+        return Ok(fs::read(path)?);
+    }
+
+    let mut request = ureq::get(url.as_str());
+    if let Some(it) = credentials.credentials(url)? {
+        request = request.set("Authorization", &it.authorization());
+    }
+    let response = request.call().map_err(|e| Error::HTTPError(Box::new(e)))?;
+    let mut bytes = Vec::new();
+    response.into_reader().read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+/// Get the `DirectUrl` of an archive read from a URL.
+fn archive_direct_url(url: &Url, bytes: &[u8]) -> DirectUrl {
+    let digest = format!("{:x}", Sha256::digest(bytes));
+
+    DirectUrl {
+        url: url.to_string(),
+        archive_info: Some(ArchiveInfo {
+            hash: Some(format!("sha256={digest}")),
+            hashes: IndexMap::from([("sha256".to_string(), digest)]),
+        }),
+        vcs_info: None,
+        dir_info: None,
+    }
+}
+
+/// Read a file from a zip archive as a string.
+fn read_archive_file<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> HuakResult<String> {
+    let mut contents = String::new();
+    archive.by_name(name)?.read_to_string(&mut contents)?;
+
+    Ok(contents)
+}
+
+/// Get a path relative to a base directory, using `..` where the path isn't in it.
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path = path.components().collect::<Vec<_>>();
+    let base = base.components().collect::<Vec<_>>();
+    let common = path
+        .iter()
+        .zip(base.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for it in &path[common..] {
+        relative.push(it);
+    }
+
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        index::LocalIndex, site_packages::InstalledDistribution,
+        test_marker_environment, test_resources_dir_path,
+    };
+    use tempfile::tempdir;
+
+    fn mock_scheme(root: &Path) -> InstallScheme {
+        InstallScheme {
+            site_packages: root.join("lib").join("site-packages"),
+            scripts: root.join("bin"),
+            headers: root.join("include"),
+            data: root.to_path_buf(),
+            python: root.join("bin").join("python"),
+        }
+    }
+
+    fn mock_wheel(name: &str) -> (PathBuf, WheelFilename) {
+        let path = test_resources_dir_path().join("mock-wheels").join(name);
+        (path, WheelFilename::from_str(name).unwrap())
+    }
+
+    #[test]
+    fn install_wheel_files() {
+        let dir = tempdir().unwrap();
+        let scheme = mock_scheme(dir.path());
+        let (path, filename) = mock_wheel("mock_cli-1.0.0-py3-none-any.whl");
+        let options = WheelInstallOptions {
+            requested: true,
+            ..Default::default()
+        };
+
+        let dist_info = install_wheel(
+            fs::File::open(path).unwrap(),
+            &filename,
+            &scheme,
+            &options,
+        )
+        .unwrap();
+
+        assert!(scheme.site_packages.join("mock_cli/__init__.py").exists());
+        assert!(dir.path().join("share/mock-cli/readme.txt").exists());
+        let legacy =
+            fs::read_to_string(scheme.scripts.join("mock-cli-legacy")).unwrap();
+        assert!(legacy.starts_with(&format!("#!{}\n", scheme.python.display())));
+        let launcher =
+            fs::read_to_string(scheme.scripts.join("mock-cli")).unwrap();
+        assert!(launcher.contains("from mock_cli import main"));
+        assert!(scheme.scripts.join("mock-cli-gui").exists());
+
+        let dist = InstalledDistribution::new(dist_info).unwrap();
+        assert_eq!(dist.installer.as_deref(), Some(INSTALLER_NAME));
+        assert!(dist.requested);
+        let paths = dist
+            .record()
+            .unwrap()
+            .into_iter()
+            .map(|it| it.path.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        assert!(paths.contains(&"../../bin/mock-cli".to_string()));
+        assert!(paths.contains(&"../../share/mock-cli/readme.txt".to_string()));
+        assert_eq!(paths.last().unwrap(), "mock_cli-1.0.0.dist-info/RECORD");
+    }
+
+    #[test]
+    fn installer_install_requirements() {
+        let dir = tempdir().unwrap();
+        let scheme = mock_scheme(dir.path());
+        let index =
+            LocalIndex::new(test_resources_dir_path().join("mock-wheels"))
+                .unwrap();
+        let env = test_marker_environment();
+        let tags = ["py311-none-any", "py3-none-any"].map(ToString::to_string);
+        let options = NativeInstallOptions::default();
+        let installer =
+            Installer::new(&scheme, &index, &env, &tags, &options, None);
+
+        let installations = installer
+            .install(&[Requirement::from_str("mock-cli").unwrap()], false)
+            .unwrap();
+        assert_eq!(
+            installations
+                .iter()
+                .map(|it| it.name.as_str())
+                .collect::<Vec<_>>(),
+            ["mock-cli", "mock-speedups", "mock-utils"]
+        );
+
+        // Installed packages that satisfy the requirements are kept.
+        assert!(installer
+            .install(&[Requirement::from_str("mock-cli").unwrap()], false)
+            .unwrap()
+            .is_empty());
+
+        let (path, _) = mock_wheel("mock_utils-1.0.0-py3-none-any.whl");
+        let url = Url::from_file_path(path).unwrap();
+        let requirement =
+            Requirement::from_str(&format!("mock-utils @ {url}")).unwrap();
+        let installations = installer.install(&[requirement], false).unwrap();
+        assert_eq!(
+            installations[0].replaced,
+            Some(Version::from_str("1.0.0").unwrap())
+        );
+        let dist = InstalledDistribution::new(
+            scheme.site_packages.join("mock_utils-1.0.0.dist-info"),
+        )
+        .unwrap();
+        assert_eq!(dist.direct_url.unwrap().url, url.to_string());
+    }
+
+    #[test]
+    fn installer_rolls_back_failed_replacement() {
+        let dir = tempdir().unwrap();
+        let scheme = mock_scheme(dir.path());
+        let index =
+            LocalIndex::new(test_resources_dir_path().join("mock-wheels"))
+                .unwrap();
+        let env = test_marker_environment();
+        let tags = ["py3-none-any".to_string()];
+        let options = NativeInstallOptions::default();
+        let installer =
+            Installer::new(&scheme, &index, &env, &tags, &options, None);
+        installer
+            .install(&[Requirement::from_str("mock-utils").unwrap()], false)
+            .unwrap();
+        let module = scheme.site_packages.join("mock_utils/__init__.py");
+        let contents = fs::read_to_string(&module).unwrap();
+
+        // A copy of the wheel with a file in a scheme that doesn't exist.
+        let (path, filename) = mock_wheel("mock_utils-1.0.0-py3-none-any.whl");
+        let mut archive =
+            zip::ZipArchive::new(fs::File::open(path).unwrap()).unwrap();
+        let broken = dir.path().join(filename.to_string());
+        let mut writer =
+            zip::ZipWriter::new(fs::File::create(&broken).unwrap());
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes).unwrap();
+            writer
+                .start_file(file.name(), zip::write::FileOptions::default())
+                .unwrap();
+            std::io::Write::write_all(&mut writer, &bytes).unwrap();
+        }
+        writer
+            .start_file(
+                "mock_utils-1.0.0.data/unknown/file.txt",
+                zip::write::FileOptions::default(),
+            )
+            .unwrap();
+        writer.finish().unwrap();
+        let url = Url::from_file_path(&broken).unwrap();
+        let requirement =
+            Requirement::from_str(&format!("mock-utils @ {url}")).unwrap();
+
+        let result = installer.install(&[requirement], false);
+
+        assert!(matches!(result, Err(Error::InvalidWheel(_))));
+        assert_eq!(fs::read_to_string(&module).unwrap(), contents);
+        let dists = installed_distributions(&scheme.site_packages).unwrap();
+        assert_eq!(
+            dists.iter().map(|it| it.name()).collect::<Vec<_>>(),
+            ["mock-speedups", "mock-utils"]
+        );
+        assert!(dists[1].record().is_ok());
+        assert!(fs::read_dir(&scheme.site_packages).unwrap().all(|it| !it
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .ends_with(".replaced")));
+    }
+
+    #[test]
+    fn uninstall_distribution_files() {
+        let dir = tempdir().unwrap();
+        let scheme = mock_scheme(dir.path());
+        for name in [
+            "mock_cli-1.0.0-py3-none-any.whl",
+            "mock_speedups-0.1.0-py3-none-any.whl",
+        ] {
+            let (path, filename) = mock_wheel(name);
+            install_wheel(
+                fs::File::open(path).unwrap(),
+                &filename,
+                &scheme,
+                &WheelInstallOptions::default(),
+            )
+            .unwrap();
+        }
+        let package = scheme.site_packages.join("mock_cli");
+        fs::create_dir(package.join("__pycache__")).unwrap();
+        fs::write(package.join("__pycache__/__init__.cpython-311.pyc"), "")
+            .unwrap();
+        // Pretend mock-speedups owns one of mock-cli's files too.
+        let record = scheme
+            .site_packages
+            .join("mock_speedups-0.1.0.dist-info")
+            .join("RECORD");
+        let mut contents = fs::read_to_string(&record).unwrap();
+        contents.push_str("../../share/mock-cli/readme.txt,,\n");
+        fs::write(&record, contents).unwrap();
+
+        let dists = installed_distributions(&scheme.site_packages).unwrap();
+        let dry_run =
+            uninstall_distribution(&dists[0], &dists, &scheme, true).unwrap();
+        assert!(dry_run.removed.contains(&scheme.scripts.join("mock-cli")));
+        assert!(dry_run
+            .removed
+            .contains(&package.join("__pycache__/__init__.cpython-311.pyc")));
+        assert!(scheme.scripts.join("mock-cli").exists());
+
+        let uninstallation =
+            uninstall_distribution(&dists[0], &dists, &scheme, false).unwrap();
+        assert_eq!(uninstallation, dry_run);
+        assert_eq!(
+            uninstallation.shared,
+            [(
+                dir.path().join("share/mock-cli/readme.txt"),
+                "mock-speedups".to_string()
+            )]
+        );
+        assert!(uninstallation.freed > 0);
+        assert!(!package.exists());
+        assert!(!scheme
+            .site_packages
+            .join("mock_cli-1.0.0.dist-info")
+            .exists());
+        assert!(!scheme.scripts.join("mock-cli").exists());
+        assert!(dir.path().join("share/mock-cli/readme.txt").exists());
+        assert!(scheme.site_packages.join("mock_speedups").exists());
+    }
+
+    #[test]
+    fn uninstall_distribution_outside_scheme() {
+        let dir = tempdir().unwrap();
+        let scheme = mock_scheme(&dir.path().join("venv"));
+        let (path, filename) = mock_wheel("mock_cli-1.0.0-py3-none-any.whl");
+        let dist_info = install_wheel(
+            fs::File::open(path).unwrap(),
+            &filename,
+            &scheme,
+            &WheelInstallOptions::default(),
+        )
+        .unwrap();
+        let outside =
+            [dir.path().join("outside.txt"), dir.path().join("script")];
+        for it in outside.iter() {
+            fs::write(it, "").unwrap();
+        }
+        // A RECORD and an entry point that walk out of the environment.
+        let mut record = fs::read_to_string(dist_info.join("RECORD")).unwrap();
+        record.push_str(&format!(
+            "../../../outside.txt,,\n{},,\n",
+            outside[0].display()
+        ));
+        fs::write(dist_info.join("RECORD"), record).unwrap();
+        let mut entry_points =
+            fs::read_to_string(dist_info.join("entry_points.txt")).unwrap();
+        entry_points
+            .push_str("\n[console_scripts]\n../../script = mock_cli:main\n");
+        fs::write(dist_info.join("entry_points.txt"), entry_points).unwrap();
+
+        let dist = InstalledDistribution::new(&dist_info).unwrap();
+        let uninstallation =
+            uninstall_distribution(&dist, &[], &scheme, false).unwrap();
+
+        assert_eq!(uninstallation.outside, outside);
+        assert!(outside.iter().all(|it| it.exists()));
+        assert!(!dist_info.exists());
+    }
+
+    #[test]
+    fn install_wheel_scripts_outside_scheme() {
+        let dir = tempdir().unwrap();
+        let scheme = mock_scheme(&dir.path().join("venv"));
+        let (path, filename) = mock_wheel("mock_cli-1.0.0-py3-none-any.whl");
+        let unpacked = dir.path().join("unpacked");
+        unpack_wheel(fs::File::open(path).unwrap(), &filename, &unpacked)
+            .unwrap();
+        let entry_points = unpacked
+            .join("mock_cli-1.0.0.dist-info")
+            .join("entry_points.txt");
+        let contents = fs::read_to_string(&entry_points).unwrap();
+
+        // Entry points that walk out of the environment or replace the scripts directory.
+        for name in [
+            "../../script",
+            &dir.path().join("script").display().to_string(),
+            "..",
+        ] {
+            fs::write(
+                &entry_points,
+                format!(
+                    "{contents}\n[console_scripts]\n{name} = mock_cli:main\n"
+                ),
+            )
+            .unwrap();
+            let result = install_unpacked_wheel(
+                &unpacked,
+                &filename,
+                &scheme,
+                &WheelInstallOptions::default(),
+                FileMode::Link,
+            );
+
+            assert!(matches!(result, Err(Error::InvalidWheel(_))));
+            assert!(!dir.path().join("script").exists());
+            assert!(!scheme.site_packages.exists());
+        }
+    }
+
+    #[test]
+    fn installer_unsupported_requirements() {
+        let dir = tempdir().unwrap();
+        let scheme = mock_scheme(dir.path());
+        let index =
+            LocalIndex::new(test_resources_dir_path().join("mock-wheels"))
+                .unwrap();
+        let env = test_marker_environment();
+        let tags = ["cp311-cp311-manylinux_2_17_x86_64".to_string()];
+        let options = NativeInstallOptions::default();
+        let installer =
+            Installer::new(&scheme, &index, &env, &tags, &options, None);
+
+        for requirement in [
+            "mock-utils",
+            "mock-utils @ git+https://github.com/mock/mock-utils",
+        ] {
+            let result = installer
+                .install(&[Requirement::from_str(requirement).unwrap()], false);
+            assert!(matches!(result, Err(Error::Unimplemented(_))));
+        }
+        assert!(!scheme.site_packages.exists());
+    }
+
+    #[test]
+    fn native_install_options() {
+        let options = InstallOptions {
+            values: Some(
+                [
+                    "--no-index",
+                    "--find-links",
+                    "wheels",
+                    "--index-url=https://mock",
+                    "--compile",
+                ]
+                .map(ToString::to_string)
+                .to_vec(),
+            ),
+            find_links: vec!["more-wheels".to_string()],
+            pre: true,
+            only_binary: vec![":all:".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            NativeInstallOptions::new(&options).unwrap(),
+            NativeInstallOptions {
+                index_url: Some("https://mock".to_string()),
+                find_links: vec![
+                    "more-wheels".to_string(),
+                    "wheels".to_string()
+                ],
+                no_index: true,
+                pre: true,
+                compile: true,
+                ..Default::default()
+            }
+        );
+
+        for options in [
+            InstallOptions {
+                values: Some(vec!["--user".to_string()]),
+                ..Default::default()
+            },
+            InstallOptions {
+                no_binary: vec![":all:".to_string()],
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                NativeInstallOptions::new(&options),
+                Err(Error::Unimplemented(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn wheel_tag_priority() {
+        let tags = [
+            "cp311-cp311-linux_x86_64",
+            "cp311-abi3-linux_x86_64",
+            "py3-none-any",
+        ]
+        .map(ToString::to_string);
+        let priority = |name: &str| {
+            wheel_priority(&WheelFilename::from_str(name).unwrap(), &tags)
+        };
+
+        assert_eq!(priority("mock-1.0-py2.py3-none-any.whl"), Some(2));
+        assert_eq!(priority("mock-1.0-cp311-abi3-linux_x86_64.whl"), Some(1));
+        assert_eq!(priority("mock-1.0-cp310-cp310-win_amd64.whl"), None);
+    }
+}
@@ -0,0 +1,868 @@
+use std::{fmt::Display, path::PathBuf, process::Command, str::FromStr};
+
+use indexmap::IndexMap;
+use pep508_rs::{MarkerEnvironment, Requirement};
+use serde::{Deserialize, Serialize};
+use termcolor::Color;
+
+use crate::{
+    cache::Cache,
+    environment::find_executable,
+    fs::{self, TempFile},
+    installer::{
+        uninstall_distribution, InstallScheme, Installation, Installer,
+        NativeInstallOptions, Uninstallation,
+    },
+    lockfile::LockedPackage,
+    package::normalized_package_name,
+    python_environment::PythonEnvironment,
+    Config, Error, HuakResult, InstallOptions,
+};
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+/// The `InstallerKind` determines which `InstallerBackend` installs packages to a
+/// `PythonEnvironment`.
+pub enum InstallerKind {
+    /// Huak's native installer. pip is used for anything it doesn't support.
+    #[default]
+    Native,
+    /// pip, run with the environment's interpreter.
+    Pip,
+    /// uv's pip interface (see https://github.com/astral-sh/uv). uv must be on the `PATH`.
+    Uv,
+}
+
+impl InstallerKind {
+    /// Get the `InstallerBackend` for the `InstallerKind`.
+    pub fn backend(&self) -> HuakResult<Box<dyn InstallerBackend>> {
+        match self {
+            InstallerKind::Native => Ok(Box::new(NativeBackend)),
+            InstallerKind::Pip => Ok(Box::new(PipBackend)),
+            InstallerKind::Uv => {
+                let path = find_executable("uv")
+                    .ok_or(Error::InstallerNotFound("uv".to_string()))?;
+                Ok(Box::new(UvBackend { path }))
+            }
+        }
+    }
+}
+
+impl FromStr for InstallerKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "native" => Ok(InstallerKind::Native),
+            "pip" => Ok(InstallerKind::Pip),
+            "uv" => Ok(InstallerKind::Uv),
+            _ => Err(Error::HuakConfigurationError(format!(
+                "invalid installer {s} (expected one of native, pip, uv)"
+            ))),
+        }
+    }
+}
+
+impl Display for InstallerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            InstallerKind::Native => "native",
+            InstallerKind::Pip => "pip",
+            InstallerKind::Uv => "uv",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// An `InstallerBackend` installs and uninstalls packages for a `PythonEnvironment`.
+///
+/// Packages are requirement strings. Backends running pip's command line interface get the
+/// `InstallOptions` as pip arguments.
+pub trait InstallerBackend {
+    /// Install packages. Packages that are already installed are kept if they satisfy
+    /// the requirements.
+    fn install_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()>;
+
+    /// Install packages, upgrading the ones already installed.
+    fn update_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()>;
+
+    /// Install local project directories in editable mode.
+    fn install_editable_packages(
+        &self,
+        env: &PythonEnvironment,
+        paths: &[PathBuf],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()>;
+
+    /// Install `LockedPackage`s exactly as they're pinned. Hashes are required for every
+    /// `LockedPackage` that has them and dependencies are never resolved.
+    fn install_locked_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[&LockedPackage],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()>;
+
+    /// Uninstall packages by name.
+    fn uninstall_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()>;
+}
+
+/// An `InstallerBackend` running pip with the `PythonEnvironment`'s interpreter.
+pub struct PipBackend;
+
+impl PipBackend {
+    fn command(&self, env: &PythonEnvironment, subcommand: &str) -> Command {
+        let mut cmd = Command::new(env.python_path());
+        cmd.args(["-m", "pip", subcommand]);
+        cmd
+    }
+
+    fn install(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        upgrade: bool,
+        config: &Config,
+    ) -> HuakResult<()> {
+        let mut cmd = self.command(env, "install");
+        if upgrade {
+            cmd.arg("--upgrade");
+        }
+        cmd.args(packages);
+        let _files = options.constrain(&mut cmd, None)?;
+        run_with_options(cmd, options, config)
+    }
+}
+
+impl InstallerBackend for PipBackend {
+    fn install_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        install_from_package_indexes(
+            packages,
+            options,
+            false,
+            |packages, options, upgrade| {
+                self.install(env, packages, options, upgrade, config)
+            },
+        )
+    }
+
+    fn update_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        install_from_package_indexes(
+            packages,
+            options,
+            true,
+            |packages, options, upgrade| {
+                self.install(env, packages, options, upgrade, config)
+            },
+        )
+    }
+
+    fn install_editable_packages(
+        &self,
+        env: &PythonEnvironment,
+        paths: &[PathBuf],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        let mut cmd = self.command(env, "install");
+        for path in paths {
+            cmd.arg("--editable").arg(path);
+        }
+        let _files = options.constrain(&mut cmd, None)?;
+        run_with_options(cmd, options, config)
+    }
+
+    fn install_locked_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[&LockedPackage],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        install_locked_with(
+            || self.command(env, "install"),
+            packages,
+            options,
+            config,
+        )
+    }
+
+    fn uninstall_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        // Only the raw values apply to uninstalling.
+        let mut cmd = self.command(env, "uninstall");
+        cmd.args(packages)
+            .arg("-y")
+            .args(options.values.iter().flatten());
+        config.terminal().run_command(&mut cmd)
+    }
+}
+
+/// An `InstallerBackend` running uv's pip interface against the `PythonEnvironment`'s
+/// interpreter.
+pub struct UvBackend {
+    /// The path to the uv executable.
+    path: PathBuf,
+}
+
+impl UvBackend {
+    fn command(&self, env: &PythonEnvironment, subcommand: &str) -> Command {
+        let mut cmd = Command::new(&self.path);
+        cmd.args(["pip", subcommand])
+            .arg("--python")
+            .arg(env.python_path());
+        cmd
+    }
+
+    fn install(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        upgrade: bool,
+        config: &Config,
+    ) -> HuakResult<()> {
+        let mut cmd = self.command(env, "install");
+        // Only the packages provided are upgraded, like with pip's --upgrade.
+        if upgrade {
+            for package in packages {
+                let name = Requirement::from_str(package)
+                    .map_or(package.clone(), |it| it.name);
+                cmd.arg("--upgrade-package").arg(name);
+            }
+        }
+        cmd.args(packages);
+        let _files = options.constrain(&mut cmd, Some("--override"))?;
+        run_with_options(cmd, options, config)
+    }
+}
+
+impl InstallerBackend for UvBackend {
+    fn install_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        install_from_package_indexes(
+            packages,
+            options,
+            false,
+            |packages, options, upgrade| {
+                self.install(env, packages, options, upgrade, config)
+            },
+        )
+    }
+
+    fn update_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        install_from_package_indexes(
+            packages,
+            options,
+            true,
+            |packages, options, upgrade| {
+                self.install(env, packages, options, upgrade, config)
+            },
+        )
+    }
+
+    fn install_editable_packages(
+        &self,
+        env: &PythonEnvironment,
+        paths: &[PathBuf],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        let mut cmd = self.command(env, "install");
+        for path in paths {
+            cmd.arg("--editable").arg(path);
+        }
+        let _files = options.constrain(&mut cmd, Some("--override"))?;
+        run_with_options(cmd, options, config)
+    }
+
+    fn install_locked_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[&LockedPackage],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        install_locked_with(
+            || self.command(env, "install"),
+            packages,
+            options,
+            config,
+        )
+    }
+
+    fn uninstall_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        // Only the raw values apply to uninstalling, and uv never asks for confirmation.
+        let mut cmd = self.command(env, "uninstall");
+        cmd.args(packages).args(options.values.iter().flatten());
+        config.terminal().run_command(&mut cmd)
+    }
+}
+
+/// An `InstallerBackend` using Huak's native `Installer`.
+///
+/// Wheels are installed natively and distributions are uninstalled using their RECORD.
+/// pip is used for anything the native `Installer` doesn't support (like source
+/// distributions).
+pub struct NativeBackend;
+
+impl NativeBackend {
+    /// Install requirements with the native `Installer`.
+    fn install(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        upgrade: bool,
+        config: &Config,
+    ) -> HuakResult<()> {
+        let requirements = packages
+            .iter()
+            .map(|it| {
+                Requirement::from_str(it).map_err(|_| {
+                    Error::Unimplemented(format!("installing {it}"))
+                })
+            })
+            .collect::<HuakResult<Vec<_>>>()?;
+        let options = NativeInstallOptions::new(options)?;
+        let (marker_env, tags, scheme) = native_installer_context(env)?;
+        let cache = native_cache(&options);
+        let index = options.package_index(cache.as_ref())?;
+
+        let installations = Installer::new(
+            &scheme,
+            index.as_ref(),
+            &marker_env,
+            &tags,
+            &options,
+            cache.as_ref(),
+        )
+        .install(&requirements, upgrade)?;
+
+        print_installations(&installations, config)
+    }
+}
+
+impl InstallerBackend for NativeBackend {
+    fn install_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        with_pip_fallback(
+            || self.install(env, packages, options, false, config),
+            || PipBackend.install_packages(env, packages, options, config),
+            config,
+        )
+    }
+
+    fn update_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        with_pip_fallback(
+            || self.install(env, packages, options, true, config),
+            || PipBackend.update_packages(env, packages, options, config),
+            config,
+        )
+    }
+
+    fn install_editable_packages(
+        &self,
+        env: &PythonEnvironment,
+        paths: &[PathBuf],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        // Editable installs need the project's build backend.
+        PipBackend.install_editable_packages(env, paths, options, config)
+    }
+
+    fn install_locked_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[&LockedPackage],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        with_pip_fallback(
+            || {
+                let native_options = NativeInstallOptions::new(options)?;
+                let (marker_env, tags, scheme) = native_installer_context(env)?;
+                let cache = native_cache(&native_options);
+                let index = native_options.package_index(cache.as_ref())?;
+
+                let installations = Installer::new(
+                    &scheme,
+                    index.as_ref(),
+                    &marker_env,
+                    &tags,
+                    &native_options,
+                    cache.as_ref(),
+                )
+                .install_locked(packages)?;
+
+                print_installations(&installations, config)
+            },
+            || {
+                PipBackend
+                    .install_locked_packages(env, packages, options, config)
+            },
+            config,
+        )
+    }
+
+    fn uninstall_packages(
+        &self,
+        env: &PythonEnvironment,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        with_pip_fallback(
+            || {
+                if let Some(v) = options.values.as_ref() {
+                    return Err(Error::Unimplemented(format!(
+                        "uninstalling with {}",
+                        v.join(" ")
+                    )));
+                }
+                let uninstallations =
+                    uninstall_natively(env, packages, false, config)?;
+                print_uninstallations(&uninstallations, config)
+            },
+            || PipBackend.uninstall_packages(env, packages, options, config),
+            config,
+        )
+    }
+}
+
+/// Uninstall distributions from a `PythonEnvironment` using their RECORD. Packages that
+/// aren't installed are skipped with a warning. Nothing is removed if `dry_run` is true.
+pub fn uninstall_natively(
+    env: &PythonEnvironment,
+    packages: &[String],
+    dry_run: bool,
+    config: &Config,
+) -> HuakResult<Vec<Uninstallation>> {
+    let site_packages = env.site_packages()?;
+    let mut terminal = config.terminal();
+    for it in site_packages.warnings() {
+        terminal.print_warning(it)?;
+    }
+    let (targets, others): (Vec<_>, Vec<_>) =
+        site_packages.distributions.into_iter().partition(|it| {
+            packages.iter().any(|package| {
+                normalized_package_name(package) == it.normalized_name()
+            })
+        });
+
+    for package in packages {
+        let name = normalized_package_name(package);
+        if !targets.iter().any(|it| it.normalized_name() == name) {
+            terminal.print_warning(format!("{package} is not installed"))?;
+        }
+    }
+    if let Some(it) = targets.iter().find(|it| it.record().is_err()) {
+        return Err(Error::Unimplemented(format!(
+            "uninstalling {} without a RECORD",
+            it.name()
+        )));
+    }
+
+    let scheme = env.install_scheme();
+    targets
+        .iter()
+        .map(|it| uninstall_distribution(it, &others, &scheme, dry_run))
+        .collect()
+}
+
+/// Run a pip-compatible command with the `InstallOptions`.
+fn run_with_options(
+    mut cmd: Command,
+    options: &InstallOptions,
+    config: &Config,
+) -> HuakResult<()> {
+    cmd.args(options.args());
+    let _netrc = options.authenticate(&mut cmd)?;
+
+    config.terminal().run_command(&mut cmd)
+}
+
+/// Install `LockedPackage`s with a pip-compatible install command.
+fn install_locked_with<F>(
+    install: F,
+    packages: &[&LockedPackage],
+    options: &InstallOptions,
+    config: &Config,
+) -> HuakResult<()>
+where
+    F: Fn() -> Command,
+{
+    let mut batches: IndexMap<Option<&String>, Vec<&LockedPackage>> =
+        IndexMap::new();
+    for package in packages {
+        let url = options.package_index_url(&package.name);
+        batches.entry(url).or_default().push(package);
+    }
+
+    for (url, packages) in batches {
+        let options = match url {
+            Some(it) => options.for_package_index(it),
+            None => options.clone(),
+        };
+        install_locked_batch(&install, &packages, &options, config)?;
+    }
+
+    Ok(())
+}
+
+/// Install `LockedPackage`s from the same package indexes.
+fn install_locked_batch<F>(
+    install: F,
+    packages: &[&LockedPackage],
+    options: &InstallOptions,
+    config: &Config,
+) -> HuakResult<()>
+where
+    F: Fn() -> Command,
+{
+    let (hashed, unhashed): (Vec<&LockedPackage>, Vec<&LockedPackage>) =
+        packages.iter().partition(|pkg| !pkg.hashes.is_empty());
+
+    if !hashed.is_empty() {
+        // Hashes can only be passed with a requirements file.
+        let contents = hashed
+            .iter()
+            .map(|pkg| pkg.to_requirement_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let file = TempFile::new("requirements.txt", &contents)?;
+
+        let mut cmd = install();
+        cmd.args(["--no-deps", "--require-hashes"])
+            .arg("-r")
+            .arg(file.path());
+
+        run_with_options(cmd, options, config)?;
+    }
+
+    if !unhashed.is_empty() {
+        let mut cmd = install();
+        cmd.arg("--no-deps")
+            .args(unhashed.iter().map(|pkg| pkg.to_requirement_string()));
+
+        run_with_options(cmd, options, config)?;
+    }
+
+    Ok(())
+}
+
+/// Install packages with pip's or uv's command line interface, which can't limit a package
+/// to its own package index.
+///
+/// Packages with a package index are installed from only that index first, without their
+/// dependencies. Installing them again afterwards only installs their dependencies, since
+/// they're already satisfied.
+fn install_from_package_indexes<F>(
+    packages: &[String],
+    options: &InstallOptions,
+    upgrade: bool,
+    install: F,
+) -> HuakResult<()>
+where
+    F: Fn(&[String], &InstallOptions, bool) -> HuakResult<()>,
+{
+    let (indexed, rest) = options.split_by_package_index(packages);
+    if indexed.is_empty() {
+        return install(packages, options, upgrade);
+    }
+
+    for (url, packages) in indexed.iter() {
+        install(packages, &options.for_package_index(url), upgrade)?;
+    }
+    if upgrade && !rest.is_empty() {
+        install(&rest, options, true)?;
+    }
+    if upgrade {
+        let packages = indexed.into_values().flatten().collect::<Vec<_>>();
+        install(&packages, options, false)
+    } else {
+        install(packages, options, false)
+    }
+}
+
+/// Get what the native `Installer` needs to know about a `PythonEnvironment`.
+fn native_installer_context(
+    env: &PythonEnvironment,
+) -> HuakResult<(MarkerEnvironment, Vec<String>, InstallScheme)> {
+    // Console scripts need .exe launchers on Windows.
+    #[cfg(windows)]
+    return Err(Error::Unimplemented(
+        "installing wheels natively on Windows".to_string(),
+    ));
+
+    #[cfg(not(windows))]
+    Ok((
+        env.marker_environment()?,
+        env.supported_tags()?,
+        env.install_scheme(),
+    ))
+}
+
+/// Run a native installation, using pip instead if the native `Installer` doesn't support
+/// it (like when a package only has source distributions pip can build). Resolution
+/// failures are returned so their explanation isn't lost.
+fn with_pip_fallback<N, P>(native: N, pip: P, config: &Config) -> HuakResult<()>
+where
+    N: FnOnce() -> HuakResult<()>,
+    P: FnOnce() -> HuakResult<()>,
+{
+    let reason = match native() {
+        Err(Error::Unimplemented(it)) => it,
+        result => return result,
+    };
+    config.terminal().print_custom(
+        "using pip",
+        format!("{reason} isn't supported by the native installer"),
+        Color::Yellow,
+        false,
+    )?;
+
+    pip()
+}
+
+/// Get the `Cache` the native `Installer` should use. Installs go without one if caching is
+/// disabled or there's nowhere to put it.
+fn native_cache(options: &NativeInstallOptions) -> Option<Cache> {
+    if options.no_cache {
+        return None;
+    }
+
+    Cache::from_env().ok()
+}
+
+/// Print the `Installation`s made by the native `Installer`.
+fn print_installations(
+    installations: &[Installation],
+    config: &Config,
+) -> HuakResult<()> {
+    let mut terminal = config.terminal();
+    for it in installations {
+        let message = match it.replaced.as_ref() {
+            Some(replaced) => {
+                format!("{} {} (was {replaced})", it.name, it.version)
+            }
+            None => format!("{} {}", it.name, it.version),
+        };
+        terminal.print_custom("installed", message, Color::Green, false)?;
+    }
+
+    Ok(())
+}
+
+/// Print the `Uninstallation`s made natively.
+fn print_uninstallations(
+    uninstallations: &[Uninstallation],
+    config: &Config,
+) -> HuakResult<()> {
+    let mut terminal = config.terminal();
+    for it in uninstallations {
+        for path in it.outside.iter() {
+            terminal.print_warning(format!(
+                "kept {} since it's outside the environment",
+                path.display()
+            ))?;
+        }
+        for (path, owner) in it.shared.iter() {
+            terminal.print_custom(
+                "kept",
+                format!("{} (also installed by {owner})", path.display()),
+                Color::Yellow,
+                false,
+            )?;
+        }
+        terminal.print_custom(
+            "uninstalled",
+            format!(
+                "{} {} ({})",
+                it.name,
+                it.version,
+                fs::format_size(it.freed)
+            ),
+            Color::Green,
+            false,
+        )?;
+    }
+    if uninstallations.len() > 1 {
+        let freed = uninstallations.iter().map(|it| it.freed).sum();
+        terminal.print_custom(
+            "freed",
+            fs::format_size(freed),
+            Color::Green,
+            false,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installer_kind_from_str() {
+        for kind in
+            [InstallerKind::Native, InstallerKind::Pip, InstallerKind::Uv]
+        {
+            assert_eq!(
+                InstallerKind::from_str(&kind.to_string()).unwrap(),
+                kind
+            );
+        }
+        assert!(InstallerKind::from_str("poetry").is_err());
+    }
+
+    #[test]
+    fn uv_backend_command() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pyvenv.cfg"),
+            "home = /usr/bin\nversion = 3.11.7\n",
+        )
+        .unwrap();
+        let env = PythonEnvironment::new(dir.path()).unwrap();
+        let backend = UvBackend {
+            path: PathBuf::from("uv"),
+        };
+
+        let cmd = backend.command(&env, "install");
+        let args = cmd
+            .get_args()
+            .map(|it| it.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(cmd.get_program(), "uv");
+        assert_eq!(
+            args,
+            [
+                "pip".to_string(),
+                "install".to_string(),
+                "--python".to_string(),
+                env.python_path().display().to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn install_from_package_indexes_in_passes() {
+        let mut options = InstallOptions {
+            extra_index_urls: vec!["https://extra".to_string()],
+            ..Default::default()
+        };
+        options
+            .package_index_urls
+            .insert("our-lib".to_string(), "https://internal".to_string());
+        let packages = ["Our_Lib>=1", "click"].map(ToString::to_string);
+
+        for upgrade in [false, true] {
+            let calls = std::cell::RefCell::new(Vec::new());
+            install_from_package_indexes(
+                &packages,
+                &options,
+                upgrade,
+                |packages, options, upgrade| {
+                    calls.borrow_mut().push(format!(
+                        "{} {upgrade} {}",
+                        packages.join(","),
+                        options.args().join(" ")
+                    ));
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+            let first = format!(
+                "Our_Lib>=1 {upgrade} --index-url https://internal --no-deps"
+            );
+            let expected = if upgrade {
+                vec![
+                    first,
+                    "click true --extra-index-url https://extra".to_string(),
+                    "Our_Lib>=1 false --extra-index-url https://extra"
+                        .to_string(),
+                ]
+            } else {
+                vec![
+                    first,
+                    "Our_Lib>=1,click false --extra-index-url https://extra"
+                        .to_string(),
+                ]
+            };
+            assert_eq!(calls.into_inner(), expected);
+        }
+    }
+}
@@ -1,67 +1,95 @@
-///! # Huak
-///!
-///! A Python package manager written in Rust inspired by Cargo.
-///!
-///! ## About
-///!
-///! Huak is considered a package manager but focuses on supporting development workflows
-///! useful for building both Python packages and projects in general.
-///!
-///! Workflows supported consist of the following life-cycle:
-///! 1. Initialization and setup
-///! 2. Making some change to the project
-///! 3. Running tests
-///! 4. Distributing the project
-///!
-///!```zsh
-///! ❯ huak help
-///!
-///! A Python package manager written in Rust inspired by Cargo.
-///!
-///! Usage: huak [OPTIONS] <COMMAND>
-///!
-///! Commands:
-///!   activate    Activate the virtual environment
-///!   add         Add dependencies to the project
-///!   build       Build tarball and wheel for the project
-///!   completion  Generates a shell completion script for supported shells
-///!   clean       Remove tarball and wheel from the built project
-///!   fix         Auto-fix fixable lint conflicts
-///!   fmt         Format the project's Python code
-///!   init        Initialize the existing project
-///!   install     Install the dependencies of an existing project
-///!   lint        Lint the project's Python code
-///!   new         Create a new project at <path>
-///!   lish        Builds and uploads current project to a registry
-///!   python      Manage Python installations
-///!   remove      Remove dependencies from the project
-///!   run         Run a command within the project's environment context
-///!   test        Test the project's Python code
-///!   update      Update the project's dependencies
-///!   version     Display the version of the project
-///!   help        Print this message or the help of the given subcommand(s)
-///!
-///!  Options:
-///!    -q, --quiet    
-///!    -h, --help     Print help
-///!    -V, --version  Print version
-///!```
+//! # Huak
+//!
+//! A Python package manager written in Rust inspired by Cargo.
+//!
+//! ## About
+//!
+//! Huak is considered a package manager but focuses on supporting development workflows
+//! useful for building both Python packages and projects in general.
+//!
+//! Workflows supported consist of the following life-cycle:
+//! 1. Initialization and setup
+//! 2. Making some change to the project
+//! 3. Running tests
+//! 4. Distributing the project
+//!
+//!```zsh
+//! ❯ huak help
+//!
+//! A Python package manager written in Rust inspired by Cargo.
+//!
+//! Usage: huak [OPTIONS] <COMMAND>
+//!
+//! Commands:
+//!   activate    Activate the virtual environment
+//!   add         Add dependencies to the project
+//!   audit       Check the project's packages against an advisory database
+//!   auth        Manage the credentials of package indexes
+//!   build       Build tarball and wheel for the project
+//!   cache       Manage the cache of wheels shared by every environment
+//!   completion  Generates a shell completion script for supported shells
+//!   clean       Remove tarball and wheel from the built project
+//!   fix         Auto-fix fixable lint conflicts
+//!   fmt         Format the project's Python code
+//!   init        Initialize the existing project
+//!   install     Install the dependencies of an existing project
+//!   licenses    Display the licenses of the packages installed to the project's environment
+//!   lint        Lint the project's Python code
+//!   lock        Lock the project's dependencies to huak.lock
+//!   new         Create a new project at <path>
+//!   lish        Builds and uploads current project to a registry
+//!   python      Manage Python installations
+//!   remove      Remove dependencies from the project
+//!   run         Run a command within the project's environment context
+//!   sync        Sync the environment with the project's dependencies
+//!   test        Test the project's Python code
+//!   tree        Display the project's dependency tree
+//!   update      Update the project's dependencies
+//!   vendor      Download the project's distributions to a directory for offline installs
+//!   version     Display the version of the project
+//!   why         Display why a package is installed in the project's environment
+//!   help        Print this message or the help of the given subcommand(s)
+//!
+//!  Options:
+//!    -q, --quiet                  
+//!        --installer <INSTALLER>  The installer to install packages with [native, pip, uv]
+//!        --offline                Work without network access, installing only from --find-links directories or the cache
+//!    -h, --help                   Print help
+//!    -V, --version                Print version
+//!```
+mod advisory;
+mod cache;
 mod config;
+mod constraints;
+mod credentials;
 mod dependency;
+mod dependency_graph;
+mod distribution;
 mod environment;
 mod error;
 mod fs;
 mod git;
+mod index;
+mod installer;
+mod installer_backend;
+mod licenses;
+mod lockfile;
 mod metadata;
 pub mod ops;
 mod package;
 mod python_environment;
+mod resolver;
+mod sbom;
+mod site_packages;
+mod sources;
 mod sys;
 mod version;
 mod workspace;
 
 pub use config::Config;
+pub use dependency::PinStrategy;
 pub use error::{Error, HuakResult};
+pub use installer_backend::InstallerKind;
 pub use python_environment::InstallOptions;
 use python_environment::PythonEnvironment;
 #[allow(unused_imports)]
@@ -75,3 +103,26 @@ pub use workspace::WorkspaceOptions;
 pub(crate) fn test_resources_dir_path() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("dev-resources")
 }
+
+#[cfg(test)]
+/// A `MarkerEnvironment` for CPython 3.11 on Linux used for testing purposes.
+pub(crate) fn test_marker_environment() -> pep508_rs::MarkerEnvironment {
+    let values = [
+        ("implementation_name", "cpython"),
+        ("implementation_version", "3.11.7"),
+        ("os_name", "posix"),
+        ("platform_machine", "x86_64"),
+        ("platform_python_implementation", "CPython"),
+        ("platform_release", "6.0.0"),
+        ("platform_system", "Linux"),
+        ("platform_version", "1 SMP"),
+        ("python_full_version", "3.11.7"),
+        ("python_version", "3.11"),
+        ("sys_platform", "linux"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+
+    python_environment::marker_environment_from_values(&values).unwrap()
+}
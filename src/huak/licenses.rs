@@ -0,0 +1,625 @@
+use std::fmt::Display;
+
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+
+use crate::{distribution::CoreMetadata, HuakResult};
+
+const LICENSE_CLASSIFIER_PREFIX: &str = "License :: ";
+
+/// SPDX identifiers of the common license classifiers (by the classifier's last part).
+const CLASSIFIER_IDENTIFIERS: [(&str, &str); 24] = [
+    ("Academic Free License (AFL)", "AFL-3.0"),
+    ("Apache Software License", "Apache-2.0"),
+    ("Artistic License", "Artistic-2.0"),
+    ("Boost Software License 1.0 (BSL-1.0)", "BSL-1.0"),
+    ("BSD License", "BSD"),
+    ("Eclipse Public License 2.0 (EPL-2.0)", "EPL-2.0"),
+    ("European Union Public Licence 1.2 (EUPL 1.2)", "EUPL-1.2"),
+    ("GNU Affero General Public License v3", "AGPL-3.0-only"),
+    (
+        "GNU Affero General Public License v3 or later (AGPLv3+)",
+        "AGPL-3.0-or-later",
+    ),
+    ("GNU General Public License (GPL)", "GPL"),
+    ("GNU General Public License v2 (GPLv2)", "GPL-2.0-only"),
+    (
+        "GNU General Public License v2 or later (GPLv2+)",
+        "GPL-2.0-or-later",
+    ),
+    ("GNU General Public License v3 (GPLv3)", "GPL-3.0-only"),
+    (
+        "GNU General Public License v3 or later (GPLv3+)",
+        "GPL-3.0-or-later",
+    ),
+    (
+        "GNU Lesser General Public License v2 (LGPLv2)",
+        "LGPL-2.0-only",
+    ),
+    (
+        "GNU Lesser General Public License v2 or later (LGPLv2+)",
+        "LGPL-2.0-or-later",
+    ),
+    (
+        "GNU Lesser General Public License v3 (LGPLv3)",
+        "LGPL-3.0-only",
+    ),
+    (
+        "GNU Lesser General Public License v3 or later (LGPLv3+)",
+        "LGPL-3.0-or-later",
+    ),
+    (
+        "GNU Library or Lesser General Public License (LGPL)",
+        "LGPL",
+    ),
+    ("ISC License (ISCL)", "ISC"),
+    ("MIT License", "MIT"),
+    ("Mozilla Public License 2.0 (MPL 2.0)", "MPL-2.0"),
+    ("Python Software Foundation License", "PSF-2.0"),
+    ("The Unlicense (Unlicense)", "Unlicense"),
+];
+
+/// SPDX identifiers the License field is commonly set to, besides those of classifiers.
+const SPDX_IDENTIFIERS: [&str; 9] = [
+    "0BSD",
+    "Apache-1.1",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "CC0-1.0",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "MPL-1.1",
+    "Zlib",
+];
+
+/// SPDX identifiers of common License field values that aren't classifier names or SPDX
+/// identifiers. Names are compared with `license_key`.
+const NAME_IDENTIFIERS: [(&str, &str); 25] = [
+    ("Apache 2", "Apache-2.0"),
+    ("Apache Software License 2.0", "Apache-2.0"),
+    ("ASL 2.0", "Apache-2.0"),
+    ("2-Clause BSD", "BSD-2-Clause"),
+    ("Simplified BSD", "BSD-2-Clause"),
+    ("3-Clause BSD", "BSD-3-Clause"),
+    ("Modified BSD", "BSD-3-Clause"),
+    ("New BSD", "BSD-3-Clause"),
+    ("CC0", "CC0-1.0"),
+    ("GNU GPL", "GPL"),
+    ("GPLv2", "GPL-2.0-only"),
+    ("GPL-2.0", "GPL-2.0-only"),
+    ("GPLv2+", "GPL-2.0-or-later"),
+    ("GPL-2.0+", "GPL-2.0-or-later"),
+    ("GPLv3", "GPL-3.0-only"),
+    ("GPL-3.0", "GPL-3.0-only"),
+    ("GPLv3+", "GPL-3.0-or-later"),
+    ("GPL-3.0+", "GPL-3.0-or-later"),
+    ("AGPLv3", "AGPL-3.0-only"),
+    ("AGPL-3.0", "AGPL-3.0-only"),
+    ("LGPLv2.1", "LGPL-2.1-only"),
+    ("LGPL-2.1", "LGPL-2.1-only"),
+    ("LGPLv3", "LGPL-3.0-only"),
+    ("LGPL-3.0", "LGPL-3.0-only"),
+    ("PSF", "PSF-2.0"),
+];
+
+/// The license a distribution declares in its core metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum License {
+    /// An SPDX license expression from the License-Expression field.
+    Expression(String),
+    /// The licenses of the `License ::` classifiers. Which of them applies is ambiguous, so
+    /// all of them have to be accepted.
+    Classifiers(Vec<String>),
+    /// The first line of the License field, or the SPDX identifier of the license it names.
+    Text(String),
+    Unknown,
+}
+
+impl License {
+    /// Get the `License` of a distribution. License-Expression takes precedence over the
+    /// license classifiers, which take precedence over the License field.
+    pub fn from_metadata(metadata: &CoreMetadata) -> License {
+        if let Some(it) = metadata.license_expression.as_ref() {
+            return License::Expression(it.clone());
+        }
+        let classifiers = metadata
+            .classifiers
+            .iter()
+            .filter_map(|it| it.strip_prefix(LICENSE_CLASSIFIER_PREFIX))
+            .filter_map(|it| {
+                let name = it.rsplit_once(" :: ").map_or(it, |(_, name)| name);
+                // Classifiers like "License :: OSI Approved" don't name a license.
+                if ["OSI Approved", "DFSG approved"].contains(&name) {
+                    return None;
+                }
+                let id = CLASSIFIER_IDENTIFIERS
+                    .iter()
+                    .find(|(classifier, _)| *classifier == name)
+                    .map_or(name, |(_, id)| id);
+                Some(id.to_string())
+            })
+            .collect::<Vec<_>>();
+        if !classifiers.is_empty() {
+            return License::Classifiers(classifiers);
+        }
+        match metadata.license.as_deref() {
+            Some(it) if !it.eq_ignore_ascii_case("UNKNOWN") => {
+                License::Text(name_identifier(it).unwrap_or(it).to_string())
+            }
+            _ => License::Unknown,
+        }
+    }
+
+    /// Get the name of the field the `License` was read from.
+    pub fn source(&self) -> &str {
+        match self {
+            License::Expression(_) => "License-Expression",
+            License::Classifiers(_) => "Classifier",
+            License::Text(_) => "License",
+            License::Unknown => "-",
+        }
+    }
+
+    /// Get the `License` as an SPDX license expression. Classifiers and License fields
+    /// that don't map to SPDX identifiers have no expression.
+    pub fn spdx_expression(&self) -> Option<String> {
+        match self {
+            License::Expression(it) => Some(it.clone()),
+            License::Classifiers(it) if it.iter().all(|id| is_spdx_id(id)) => {
+                Some(it.join(" AND "))
+            }
+            License::Text(it) if is_spdx_id(it) => Some(it.clone()),
+            _ => None,
+        }
+    }
+
+    /// Get the `LicenseTerm` the `License` is granted under, if it's known. Identifiers of
+    /// classifiers and License fields Huak doesn't know are `LicenseTerm::Unknown`.
+    fn term(&self) -> Option<LicenseTerm> {
+        match self {
+            License::Expression(it) => Some(
+                LicenseTerm::parse(it)
+                    .unwrap_or_else(|| LicenseTerm::Id(it.clone())),
+            ),
+            License::Classifiers(it) if it.len() == 1 => {
+                Some(LicenseTerm::Id(it[0].clone()).unknown_ids())
+            }
+            License::Classifiers(it) => Some(
+                LicenseTerm::All(
+                    it.iter().cloned().map(LicenseTerm::Id).collect(),
+                )
+                .unknown_ids(),
+            ),
+            License::Text(it) => Some(
+                LicenseTerm::parse(it)
+                    .unwrap_or_else(|| LicenseTerm::Id(it.clone()))
+                    .unknown_ids(),
+            ),
+            License::Unknown => None,
+        }
+    }
+}
+
+impl Display for License {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            License::Expression(it) | License::Text(it) => write!(f, "{it}"),
+            License::Classifiers(it) => write!(f, "{}", it.join(" AND ")),
+            License::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+/// A parsed SPDX license expression. Exceptions stay part of the identifier they apply to
+/// (like `GPL-2.0-only WITH Classpath-exception-2.0`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LicenseTerm {
+    Id(String),
+    /// An identifier that isn't a license Huak knows, like the text of a License field.
+    Unknown(String),
+    /// Every term applies (AND).
+    All(Vec<LicenseTerm>),
+    /// Any of the terms may be chosen (OR).
+    Any(Vec<LicenseTerm>),
+}
+
+impl LicenseTerm {
+    /// Make the identifiers Huak doesn't know `LicenseTerm::Unknown`.
+    fn unknown_ids(self) -> LicenseTerm {
+        match self {
+            LicenseTerm::Id(it) if !is_known_id(&it) => {
+                LicenseTerm::Unknown(it)
+            }
+            LicenseTerm::All(it) => LicenseTerm::All(
+                it.into_iter().map(LicenseTerm::unknown_ids).collect(),
+            ),
+            LicenseTerm::Any(it) => LicenseTerm::Any(
+                it.into_iter().map(LicenseTerm::unknown_ids).collect(),
+            ),
+            it => it,
+        }
+    }
+
+    /// Parse an SPDX license expression. `None` is returned if it isn't well-formed.
+    fn parse(expression: &str) -> Option<LicenseTerm> {
+        let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+        let tokens = spaced.split_whitespace().collect::<Vec<_>>();
+        let mut pos = 0;
+        let term = LicenseTerm::parse_any(&tokens, &mut pos)?;
+
+        (pos == tokens.len()).then_some(term)
+    }
+
+    fn parse_any(tokens: &[&str], pos: &mut usize) -> Option<LicenseTerm> {
+        let mut terms = vec![LicenseTerm::parse_all(tokens, pos)?];
+        while tokens
+            .get(*pos)
+            .map_or(false, |it| it.eq_ignore_ascii_case("OR"))
+        {
+            *pos += 1;
+            terms.push(LicenseTerm::parse_all(tokens, pos)?);
+        }
+
+        Some(match terms.len() {
+            1 => terms.remove(0),
+            _ => LicenseTerm::Any(terms),
+        })
+    }
+
+    fn parse_all(tokens: &[&str], pos: &mut usize) -> Option<LicenseTerm> {
+        let mut terms = vec![LicenseTerm::parse_id(tokens, pos)?];
+        while tokens
+            .get(*pos)
+            .map_or(false, |it| it.eq_ignore_ascii_case("AND"))
+        {
+            *pos += 1;
+            terms.push(LicenseTerm::parse_id(tokens, pos)?);
+        }
+
+        Some(match terms.len() {
+            1 => terms.remove(0),
+            _ => LicenseTerm::All(terms),
+        })
+    }
+
+    fn parse_id(tokens: &[&str], pos: &mut usize) -> Option<LicenseTerm> {
+        let token = *tokens.get(*pos)?;
+        *pos += 1;
+        if token == "(" {
+            let term = LicenseTerm::parse_any(tokens, pos)?;
+            if tokens.get(*pos) != Some(&")") {
+                return None;
+            }
+            *pos += 1;
+            return Some(term);
+        }
+        if token == ")"
+            || ["AND", "OR", "WITH"]
+                .iter()
+                .any(|it| token.eq_ignore_ascii_case(it))
+        {
+            return None;
+        }
+        match tokens.get(*pos) {
+            Some(it) if it.eq_ignore_ascii_case("WITH") => {
+                let exception = tokens.get(*pos + 1)?;
+                *pos += 2;
+                Some(LicenseTerm::Id(format!("{token} WITH {exception}")))
+            }
+            _ => Some(LicenseTerm::Id(token.to_string())),
+        }
+    }
+}
+
+/// The licenses a project accepts from the `[tool.huak.licenses]` table.
+///
+/// ```toml
+/// [tool.huak.licenses]
+/// allow = ["MIT", "BSD*", "Apache-2.0"]
+/// deny = ["GPL*", "AGPL*"]
+/// ```
+///
+/// Patterns are matched case-insensitively against license identifiers and can use `*`
+/// wildcards.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct LicensePolicy {
+    /// If any licenses are allowed, licenses that aren't allowed are rejected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Licenses that are rejected even if they're allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl LicensePolicy {
+    /// Check a `License` against the `LicensePolicy`. The reason it's rejected is returned.
+    ///
+    /// A choice of licenses (OR) is accepted if any of them is, and a combination (AND) if
+    /// all of them are. Unknown licenses are rejected by any policy that allows or denies
+    /// licenses, and licenses Huak can't identify are rejected unless they're allowed.
+    pub fn rejection(&self, license: &License) -> HuakResult<Option<String>> {
+        if self == &LicensePolicy::default() {
+            return Ok(None);
+        }
+        let Some(term) = license.term() else {
+            return Ok(Some("the license is unknown".to_string()));
+        };
+        let allow = patterns(&self.allow)?;
+        let deny = patterns(&self.deny)?;
+
+        let mut reasons = Vec::new();
+        if self.accepts(&term, &allow, &deny, &mut reasons) {
+            return Ok(None);
+        }
+
+        Ok(Some(reasons.join(", ")))
+    }
+
+    fn accepts(
+        &self,
+        term: &LicenseTerm,
+        allow: &[Pattern],
+        deny: &[Pattern],
+        reasons: &mut Vec<String>,
+    ) -> bool {
+        match term {
+            LicenseTerm::Id(id) | LicenseTerm::Unknown(id) => {
+                let known = matches!(term, LicenseTerm::Id(_));
+                let reason = if matches_any(deny, id) {
+                    Some(format!("{id} is denied"))
+                } else if allow.is_empty() {
+                    (!known).then(|| format!("{id} isn't a known license"))
+                } else {
+                    (!matches_any(allow, id))
+                        .then(|| format!("{id} is not allowed"))
+                };
+                let accepted = reason.is_none();
+                reasons.extend(reason);
+                accepted
+            }
+            // Every term is visited so each rejected identifier is reported.
+            LicenseTerm::All(terms) | LicenseTerm::Any(terms) => {
+                let accepted = terms
+                    .iter()
+                    .map(|it| self.accepts(it, allow, deny, reasons))
+                    .collect::<Vec<_>>();
+                match term {
+                    LicenseTerm::All(_) => accepted.iter().all(|it| *it),
+                    _ => accepted.iter().any(|it| *it),
+                }
+            }
+        }
+    }
+}
+
+/// Check if an identifier is one of the SPDX identifiers Huak knows. Classifiers for
+/// unversioned licenses (like "BSD License") map to identifiers that aren't SPDX's.
+fn is_spdx_id(id: &str) -> bool {
+    !["BSD", "GPL", "LGPL"].contains(&id) && is_known_id(id)
+}
+
+/// Check if an identifier is one Huak maps licenses to.
+fn is_known_id(id: &str) -> bool {
+    SPDX_IDENTIFIERS.contains(&id)
+        || CLASSIFIER_IDENTIFIERS.iter().any(|(_, it)| *it == id)
+}
+
+/// Get the identifier of the license a License field names, like "GPL-3.0-only" for "GNU
+/// General Public License v3".
+fn name_identifier(name: &str) -> Option<&'static str> {
+    let key = license_key(name);
+    if key.is_empty() {
+        return None;
+    }
+
+    CLASSIFIER_IDENTIFIERS
+        .iter()
+        .copied()
+        .chain(CLASSIFIER_IDENTIFIERS.iter().map(|(_, id)| (*id, *id)))
+        .chain(SPDX_IDENTIFIERS.iter().map(|id| (*id, *id)))
+        .chain(NAME_IDENTIFIERS.iter().copied())
+        .find(|(it, _)| license_key(it) == key)
+        .map(|(_, id)| id)
+}
+
+/// Get a key to compare license names by. Case, punctuation, parenthesized abbreviations,
+/// and the words "the", "license", and "version" are ignored.
+fn license_key(name: &str) -> String {
+    let mut unparenthesized = String::new();
+    let mut depth = 0;
+    for c in name.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            _ if depth == 0 => unparenthesized.push(c),
+            _ => (),
+        }
+    }
+
+    unparenthesized
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|it| {
+            !["the", "license", "licence", "version"]
+                .contains(&it.to_ascii_lowercase().as_str())
+        })
+        .flat_map(str::chars)
+        .filter(|it| it.is_ascii_alphanumeric() || *it == '+')
+        .map(|it| it.to_ascii_lowercase())
+        .collect()
+}
+
+fn patterns(values: &[String]) -> HuakResult<Vec<Pattern>> {
+    values
+        .iter()
+        .map(|it| Pattern::new(it).map_err(Into::into))
+        .collect()
+}
+
+fn matches_any(patterns: &[Pattern], id: &str) -> bool {
+    let options = MatchOptions {
+        case_sensitive: false,
+        ..MatchOptions::new()
+    };
+    patterns.iter().any(|it| it.matches_with(id, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn license(fields: &str) -> License {
+        let metadata = CoreMetadata::from_str(&format!(
+            "Metadata-Version: 2.4\nName: mock\nVersion: 1.0\n{fields}"
+        ))
+        .unwrap();
+
+        License::from_metadata(&metadata)
+    }
+
+    #[test]
+    fn license_from_metadata() {
+        let expression = license(
+            "License-Expression: MIT OR Apache-2.0\nClassifier: License :: OSI Approved :: MIT License",
+        );
+        let classifiers = license(
+            "License: BSD\nClassifier: License :: OSI Approved\nClassifier: License :: OSI Approved :: GNU General Public License v3 (GPLv3)\nClassifier: License :: Other/Proprietary License",
+        );
+
+        assert_eq!(expression.to_string(), "MIT OR Apache-2.0");
+        assert_eq!(expression.source(), "License-Expression");
+        assert_eq!(
+            classifiers,
+            License::Classifiers(vec![
+                "GPL-3.0-only".to_string(),
+                "Other/Proprietary License".to_string()
+            ])
+        );
+        assert_eq!(license("License: BSD-3-Clause").source(), "License");
+        assert_eq!(license("License: UNKNOWN"), License::Unknown);
+        for (text, id) in [
+            ("GNU General Public License v3", "GPL-3.0-only"),
+            ("Apache License, Version 2.0", "Apache-2.0"),
+            ("The MIT License (MIT)", "MIT"),
+            ("new bsd", "BSD-3-Clause"),
+            ("GPLv2+", "GPL-2.0-or-later"),
+        ] {
+            assert_eq!(
+                license(&format!("License: {text}")),
+                License::Text(id.to_string())
+            );
+        }
+        assert_eq!(
+            license("License: Proprietary"),
+            License::Text("Proprietary".to_string())
+        );
+        assert_eq!(
+            expression.spdx_expression().as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+        assert_eq!(classifiers.spdx_expression(), None);
+        assert_eq!(
+            license("License: BSD-3-Clause")
+                .spdx_expression()
+                .as_deref(),
+            Some("BSD-3-Clause")
+        );
+    }
+
+    #[test]
+    fn license_expression_terms() {
+        assert_eq!(
+            LicenseTerm::parse(
+                "MIT AND (Apache-2.0 OR GPL-2.0-only WITH Classpath-exception-2.0)"
+            ),
+            Some(LicenseTerm::All(vec![
+                LicenseTerm::Id("MIT".to_string()),
+                LicenseTerm::Any(vec![
+                    LicenseTerm::Id("Apache-2.0".to_string()),
+                    LicenseTerm::Id(
+                        "GPL-2.0-only WITH Classpath-exception-2.0".to_string()
+                    ),
+                ]),
+            ]))
+        );
+        assert_eq!(LicenseTerm::parse("MIT OR"), None);
+        assert_eq!(LicenseTerm::parse("(MIT"), None);
+    }
+
+    #[test]
+    fn license_policy_rejection() {
+        let policy = LicensePolicy {
+            allow: vec!["MIT".to_string(), "apache-*".to_string()],
+            deny: vec!["GPL*".to_string()],
+        };
+        let rejection = |license: License| policy.rejection(&license).unwrap();
+
+        assert_eq!(
+            rejection(License::Expression("MIT OR GPL-3.0-only".to_string())),
+            None
+        );
+        assert_eq!(
+            rejection(License::Expression(
+                "MIT AND (GPL-3.0-only OR BSD-3-Clause)".to_string()
+            )),
+            Some(
+                "GPL-3.0-only is denied, BSD-3-Clause is not allowed"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            rejection(License::Classifiers(vec!["Apache-2.0".to_string()])),
+            None
+        );
+        assert_eq!(
+            rejection(License::Unknown),
+            Some("the license is unknown".to_string())
+        );
+        assert_eq!(
+            LicensePolicy::default()
+                .rejection(&License::Unknown)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn license_policy_deny_only() {
+        let policy = LicensePolicy {
+            allow: Vec::new(),
+            deny: vec!["GPL*".to_string()],
+        };
+        let rejection = |license: License| policy.rejection(&license).unwrap();
+
+        assert_eq!(
+            rejection(License::Unknown),
+            Some("the license is unknown".to_string())
+        );
+        assert_eq!(
+            rejection(License::Classifiers(vec![
+                "GPL-3.0-only".to_string(),
+                "Other/Proprietary License".to_string()
+            ])),
+            Some(
+                "GPL-3.0-only is denied, Other/Proprietary License isn't a known license"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            rejection(license("License: GNU General Public License v3")),
+            Some("GPL-3.0-only is denied".to_string())
+        );
+        assert_eq!(
+            rejection(License::Text("Proprietary".to_string())),
+            Some("Proprietary isn't a known license".to_string())
+        );
+        assert_eq!(
+            rejection(License::Text("MIT OR Apache-2.0".to_string())),
+            None
+        );
+        assert_eq!(
+            rejection(License::Expression("LicenseRef-Mock".to_string())),
+            None
+        );
+    }
+}
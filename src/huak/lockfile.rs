@@ -0,0 +1,421 @@
+use std::{collections::HashSet, path::Path, str::FromStr};
+
+use indexmap::IndexMap;
+use pep440_rs::Version;
+use pep508_rs::Requirement;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    package::normalized_package_name,
+    python_environment::{InstallationReport, InstallationReportItem},
+    Error, HuakResult,
+};
+
+const DEFAULT_LOCKFILE_NAME: &str = "huak.lock";
+const LOCKFILE_VERSION: usize = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+/// The `Lockfile` records every `Package` resolved for a project (huak.lock).
+///
+/// ```toml
+/// version = 1
+/// content-hash = "sha256:..."
+///
+/// [[package]]
+/// name = "click"
+/// version = "8.1.3"
+/// source = "https://files.pythonhosted.org/packages/.../click-8.1.3-py3-none-any.whl"
+/// groups = ["required"]
+/// hashes = ["sha256:..."]
+/// dependencies = []
+/// ```
+pub struct Lockfile {
+    /// The version of the `Lockfile` format.
+    version: usize,
+    /// A hash of the dependency groups the `Lockfile` was resolved for.
+    #[serde(default)]
+    content_hash: String,
+    /// The `LockedPackage`s sorted by name.
+    #[serde(default, rename = "package")]
+    packages: Vec<LockedPackage>,
+}
+
+impl Lockfile {
+    /// Initialize a `Lockfile` from its path.
+    pub fn new<T: AsRef<Path>>(path: T) -> HuakResult<Lockfile> {
+        let contents = std::fs::read_to_string(path)?;
+        let lockfile: Lockfile = toml::from_str(&contents)?;
+
+        if lockfile.version != LOCKFILE_VERSION {
+            return Err(Error::Unimplemented(format!(
+                "lockfile version {}",
+                lockfile.version
+            )));
+        }
+
+        Ok(lockfile)
+    }
+
+    /// Resolve a `Lockfile` from an `InstallationReport` and the project's dependency groups.
+    ///
+    /// Every distribution in the report reachable from a group's requirements is locked with
+    /// that group. Distributions nothing declared depends on are left out.
+    pub fn from_installation_report(
+        report: &InstallationReport,
+        groups: &IndexMap<String, Vec<Requirement>>,
+    ) -> HuakResult<Lockfile> {
+        let env = report.marker_environment()?;
+
+        // Index the report's distributions and their requirements by normalized name.
+        let mut items: IndexMap<
+            String,
+            (&InstallationReportItem, Vec<Requirement>),
+        > = IndexMap::new();
+        for item in report.install.iter() {
+            let requires = item
+                .metadata
+                .requires_dist
+                .iter()
+                .map(|it| Requirement::from_str(it))
+                .collect::<Result<Vec<_>, _>>()?;
+            items.insert(
+                normalized_package_name(&item.metadata.name),
+                (item, requires),
+            );
+        }
+
+        let mut locked: IndexMap<String, LockedPackage> = IndexMap::new();
+        for (group, reqs) in groups {
+            // Walk the requirements of the group, carrying along the extras requested at each hop.
+            let mut visited = HashSet::new();
+            let mut stack = reqs
+                .iter()
+                .filter(|req| req.evaluate_markers(&env, Vec::new()))
+                .map(|req| {
+                    (
+                        normalized_package_name(&req.name),
+                        req.extras.clone().unwrap_or_default(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            while let Some((name, extras)) = stack.pop() {
+                if !visited.insert((name.clone(), extras.clone())) {
+                    continue;
+                }
+                let Some((item, requires)) = items.get(&name) else {
+                    continue;
+                };
+
+                if !locked.contains_key(&name) {
+                    locked.insert(
+                        name.clone(),
+                        LockedPackage::from_report_item(item)?,
+                    );
+                }
+                let package = &mut locked[&name];
+                if !package.groups.contains(group) {
+                    package.groups.push(group.clone());
+                }
+
+                for req in requires
+                    .iter()
+                    .filter(|req| req.evaluate_markers(&env, extras.clone()))
+                {
+                    let dep_name = normalized_package_name(&req.name);
+                    if items.contains_key(&dep_name)
+                        && !package.dependencies.contains(&dep_name)
+                    {
+                        package.dependencies.push(dep_name.clone());
+                    }
+                    stack.push((
+                        dep_name,
+                        req.extras.clone().unwrap_or_default(),
+                    ));
+                }
+            }
+        }
+
+        let mut packages = locked.into_values().collect::<Vec<_>>();
+        packages.sort_by(|a, b| {
+            normalized_package_name(&a.name)
+                .cmp(&normalized_package_name(&b.name))
+        });
+        packages
+            .iter_mut()
+            .for_each(|package| package.dependencies.sort());
+
+        Ok(Lockfile {
+            version: LOCKFILE_VERSION,
+            content_hash: content_hash(groups),
+            packages,
+        })
+    }
+
+    /// Get a reference to the `LockedPackage`s.
+    pub fn packages(&self) -> &[LockedPackage] {
+        &self.packages
+    }
+
+    /// Check if the `Lockfile` was resolved for the dependency groups provided.
+    pub fn is_fresh(
+        &self,
+        groups: &IndexMap<String, Vec<Requirement>>,
+    ) -> bool {
+        self.content_hash == content_hash(groups)
+    }
+
+    /// Write the `Lockfile` to a path.
+    pub fn write_file<T: AsRef<Path>>(&self, path: T) -> HuakResult<()> {
+        let string = self.to_string_pretty()?;
+        Ok(std::fs::write(path, string)?)
+    }
+
+    /// Serialize the `Lockfile` to a formatted string.
+    pub fn to_string_pretty(&self) -> HuakResult<String> {
+        Ok(toml_edit::ser::to_string_pretty(self)?)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+/// A `Package` pinned by the `Lockfile`.
+pub struct LockedPackage {
+    /// The name of the `Package`.
+    pub name: String,
+    /// The PEP 440 `Version` the `Package` is locked to.
+    pub version: Version,
+    /// Where the `Package`'s distribution was resolved from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// The dependency groups that require the `Package`.
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// Hashes of the `Package`'s distribution formatted as `<algorithm>:<digest>`.
+    #[serde(default)]
+    pub hashes: Vec<String>,
+    /// The normalized names of the locked `Package`s this `Package` depends on.
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+impl LockedPackage {
+    /// Get the `LockedPackage` as a pinned requirement line with its hashes.
+    ///
+    /// Distributions without hashes (like local directories or vcs checkouts) are pinned
+    /// to their source instead.
+    pub fn to_requirement_string(&self) -> String {
+        match (self.hashes.is_empty(), self.source.as_ref()) {
+            (true, Some(source)) => format!("{} @ {}", self.name, source),
+            _ => {
+                let mut line = format!("{}=={}", self.name, self.version);
+                for hash in self.hashes.iter() {
+                    line.push_str(&format!(" --hash={hash}"));
+                }
+                line
+            }
+        }
+    }
+
+    /// Check if the `LockedPackage` is required by any of the dependency groups provided.
+    pub fn in_groups(&self, groups: &[String]) -> bool {
+        self.groups.iter().any(|group| groups.contains(group))
+    }
+
+    /// Initialize a `LockedPackage` (without any groups) from an `InstallationReportItem`.
+    fn from_report_item(item: &InstallationReportItem) -> HuakResult<Self> {
+        let version = Version::from_str(&item.metadata.version)
+            .map_err(Error::InvalidVersionString)?;
+        let info = &item.download_info;
+        let source = match info.vcs_info.as_ref() {
+            Some(vcs) => format!("{}+{}@{}", vcs.vcs, info.url, vcs.commit_id),
+            None => info.url.clone(),
+        };
+
+        Ok(LockedPackage {
+            name: item.metadata.name.clone(),
+            version,
+            source: Some(source),
+            groups: Vec::new(),
+            hashes: info.hashes(),
+            dependencies: Vec::new(),
+        })
+    }
+}
+
+/// Hash the requirements of each dependency group formatted as `sha256:<digest>`.
+pub fn content_hash(groups: &IndexMap<String, Vec<Requirement>>) -> String {
+    let mut hasher = Sha256::new();
+    for (group, reqs) in groups {
+        for req in reqs {
+            hasher.update(format!("{group}:{req}\n"));
+        }
+    }
+
+    format!("sha256:{:x}", hasher.finalize())
+}
+
+pub fn default_lockfile_name() -> &'static str {
+    DEFAULT_LOCKFILE_NAME
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_report() -> InstallationReport {
+        serde_json::from_str(
+            r#"{
+  "version": "1",
+  "install": [
+    {
+      "download_info": {
+        "url": "https://files.pythonhosted.org/packages/click-8.1.3-py3-none-any.whl",
+        "archive_info": {"hash": "sha256=abc", "hashes": {"sha256": "abc"}}
+      },
+      "requested": true,
+      "metadata": {
+        "name": "click",
+        "version": "8.1.3",
+        "requires_dist": ["colorama ; platform_system == \"Windows\""]
+      }
+    },
+    {
+      "download_info": {
+        "url": "https://files.pythonhosted.org/packages/pytest-7.3.1-py3-none-any.whl",
+        "archive_info": {"hash": "sha256=def"}
+      },
+      "requested": true,
+      "metadata": {
+        "name": "pytest",
+        "version": "7.3.1",
+        "requires_dist": ["iniconfig", "Pygments ; extra == \"testing\""]
+      }
+    },
+    {
+      "download_info": {
+        "url": "https://files.pythonhosted.org/packages/iniconfig-2.0.0-py3-none-any.whl",
+        "archive_info": {"hashes": {"sha256": "ghi"}}
+      },
+      "requested": true,
+      "metadata": {"name": "iniconfig", "version": "2.0.0"}
+    },
+    {
+      "download_info": {
+        "url": "https://files.pythonhosted.org/packages/colorama-0.4.6-py3-none-any.whl",
+        "archive_info": {"hashes": {"sha256": "jkl"}}
+      },
+      "requested": true,
+      "metadata": {"name": "colorama", "version": "0.4.6"}
+    }
+  ],
+  "environment": {
+    "implementation_name": "cpython",
+    "implementation_version": "3.11.3",
+    "os_name": "posix",
+    "platform_machine": "x86_64",
+    "platform_release": "5.15.0",
+    "platform_system": "Linux",
+    "platform_version": "1 SMP",
+    "python_full_version": "3.11.3",
+    "platform_python_implementation": "CPython",
+    "python_version": "3.11",
+    "sys_platform": "linux"
+  }
+}"#,
+        )
+        .unwrap()
+    }
+
+    fn mock_groups() -> IndexMap<String, Vec<Requirement>> {
+        IndexMap::from([
+            (
+                "required".to_string(),
+                vec![Requirement::from_str("click==8.1.3").unwrap()],
+            ),
+            (
+                "dev".to_string(),
+                vec![
+                    Requirement::from_str("pytest>=6").unwrap(),
+                    Requirement::from_str("Click").unwrap(),
+                ],
+            ),
+        ])
+    }
+
+    #[test]
+    fn lockfile_from_installation_report() {
+        let lockfile =
+            Lockfile::from_installation_report(&mock_report(), &mock_groups())
+                .unwrap();
+        let names = lockfile
+            .packages()
+            .iter()
+            .map(|it| it.name.as_str())
+            .collect::<Vec<_>>();
+
+        // colorama is only required on Windows.
+        assert_eq!(names, vec!["click", "iniconfig", "pytest"]);
+        assert_eq!(
+            lockfile.packages()[0].groups,
+            vec!["required".to_string(), "dev".to_string()]
+        );
+        assert_eq!(lockfile.packages()[1].groups, vec!["dev".to_string()]);
+        assert_eq!(lockfile.packages()[0].hashes, vec!["sha256:abc"]);
+        assert_eq!(lockfile.packages()[2].hashes, vec!["sha256:def"]);
+        assert_eq!(lockfile.packages()[2].dependencies, vec!["iniconfig"]);
+    }
+
+    #[test]
+    fn lockfile_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(default_lockfile_name());
+        let lockfile =
+            Lockfile::from_installation_report(&mock_report(), &mock_groups())
+                .unwrap();
+
+        lockfile.write_file(&path).unwrap();
+
+        assert_eq!(Lockfile::new(&path).unwrap(), lockfile);
+    }
+
+    #[test]
+    fn lockfile_freshness() {
+        let mut groups = mock_groups();
+        let lockfile =
+            Lockfile::from_installation_report(&mock_report(), &groups)
+                .unwrap();
+
+        assert!(lockfile.is_fresh(&groups));
+
+        groups
+            .get_mut("required")
+            .unwrap()
+            .push(Requirement::from_str("colorama").unwrap());
+
+        assert!(!lockfile.is_fresh(&groups));
+    }
+
+    #[test]
+    fn locked_package_requirement_string() {
+        let lockfile =
+            Lockfile::from_installation_report(&mock_report(), &mock_groups())
+                .unwrap();
+        let mut package = lockfile.packages()[0].clone();
+
+        assert_eq!(
+            package.to_requirement_string(),
+            "click==8.1.3 --hash=sha256:abc"
+        );
+
+        package.hashes.clear();
+        package.source = Some("file:///path/to/click".to_string());
+
+        assert_eq!(
+            package.to_requirement_string(),
+            "click @ file:///path/to/click"
+        );
+    }
+}
@@ -10,9 +10,19 @@ use pep440_rs::Version;
 use pep508_rs::Requirement;
 use pyproject_toml::{BuildSystem, Project, PyProjectToml as ProjectToml};
 use serde::{Deserialize, Serialize};
-use toml::Table;
-
-use crate::{dependency::Dependency, Error, HuakResult};
+use toml::{Table, Value};
+
+use crate::{
+    credentials::check_url_without_password,
+    dependency::{Dependency, PinStrategy},
+    installer_backend::InstallerKind,
+    licenses::LicensePolicy,
+    package::normalized_package_name,
+    sources::{
+        default_index_url, package_index_urls, NamedIndex, PackageSource,
+    },
+    Error, HuakResult, InstallOptions,
+};
 
 const DEFAULT_METADATA_FILE_NAME: &str = "pyproject.toml";
 
@@ -54,6 +64,7 @@ impl LocalMetadata {
                     backend_path: None,
                 },
                 project: PyProjectToml::default().project.clone().unwrap(),
+                dependency_groups: None,
                 tool: None,
             },
             path: path.as_ref().to_path_buf(),
@@ -70,6 +81,11 @@ impl LocalMetadata {
         &mut self.metadata
     }
 
+    /// Get a reference to the path to the `LocalMetadata` file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     /// Write the `LocalMetadata` file to its path.
     pub fn write_file(&self) -> HuakResult<()> {
         let string = self.to_string_pretty()?;
@@ -78,7 +94,39 @@ impl LocalMetadata {
 
     /// Serialize the `Metadata` to a formatted string.
     pub fn to_string_pretty(&self) -> HuakResult<String> {
-        Ok(toml_edit::ser::to_string_pretty(&self.metadata)?)
+        let string = toml_edit::ser::to_string_pretty(&self.metadata)?;
+        if self.metadata.dependency_groups.is_none() {
+            return Ok(string);
+        }
+
+        // Dependency groups of only include-group tables are written as arrays of tables
+        // after the other groups, so they're written inline and in order like the rest.
+        let mut document = toml_edit::Document::from_str(&string)
+            .map_err(|e| Error::InternalError(e.to_string()))?;
+        let names = self
+            .metadata
+            .dependency_groups()
+            .map(|it| it.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        if let Some(groups) = document
+            .get_mut("dependency-groups")
+            .and_then(|it| it.as_table_mut())
+        {
+            for name in names {
+                let item = match groups.remove(&name) {
+                    Some(toml_edit::Item::ArrayOfTables(tables)) => {
+                        let mut array = tables.into_array();
+                        toml_edit::Array::fmt(&mut array);
+                        toml_edit::value(array)
+                    }
+                    Some(it) => it,
+                    None => continue,
+                };
+                groups.insert(&name, item);
+            }
+        }
+
+        Ok(document.to_string())
     }
 }
 
@@ -104,11 +152,13 @@ fn pyproject_toml_metadata<T: AsRef<Path>>(
     }
     .to_owned();
     let build_system = pyproject_toml.build_system.to_owned();
+    let dependency_groups = pyproject_toml.dependency_groups;
     let tool = pyproject_toml.tool;
 
     let metadata = Metadata {
         build_system,
         project,
+        dependency_groups,
         tool,
     };
     let local_metadata = LocalMetadata {
@@ -129,6 +179,9 @@ pub struct Metadata {
     build_system: BuildSystem,
     /// The `Project` table.
     project: Project,
+    /// The `[dependency-groups]` table (see https://peps.python.org/pep-0735/).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dependency_groups: Option<IndexMap<String, Vec<DependencyGroupEntry>>>,
     /// The `Tool` table.
     tool: Option<Table>,
 }
@@ -139,6 +192,68 @@ impl Metadata {
         &self.project
     }
 
+    /// Get Huak's configuration from the `[tool.huak]` table.
+    pub fn huak_table(&self) -> HuakResult<HuakTable> {
+        match self.tool.as_ref().and_then(|it| it.get("huak")) {
+            Some(it) => Ok(it.clone().try_into()?),
+            None => Ok(HuakTable::default()),
+        }
+    }
+
+    /// Install a local project `Dependency` in editable mode by adding it to the
+    /// `[tool.huak]` table's editable array.
+    pub fn add_editable_dependency(&mut self, dependency: &Dependency) {
+        let huak = self
+            .tool
+            .get_or_insert_with(Table::new)
+            .entry("huak")
+            .or_insert_with(|| Value::Table(Table::new()));
+        let Some(editable) = huak.as_table_mut().map(|it| {
+            it.entry("editable")
+                .or_insert_with(|| Value::Array(Vec::new()))
+        }) else {
+            return;
+        };
+        if let Some(names) = editable.as_array_mut() {
+            if !names
+                .iter()
+                .any(|it| it.as_str() == Some(dependency.name()))
+            {
+                names.push(Value::String(dependency.name().to_string()));
+            }
+        }
+    }
+
+    /// Remove a `Dependency` from the `[tool.huak]` table's editable array. Tables left empty
+    /// are removed.
+    pub fn remove_editable_dependency(&mut self, dependency: &Dependency) {
+        let name = normalized_package_name(dependency.name());
+        let Some(tool) = self.tool.as_mut() else {
+            return;
+        };
+        if let Some(huak) =
+            tool.get_mut("huak").and_then(|it| it.as_table_mut())
+        {
+            if let Some(names) =
+                huak.get_mut("editable").and_then(|it| it.as_array_mut())
+            {
+                names.retain(|it| {
+                    it.as_str().map(normalized_package_name)
+                        != Some(name.clone())
+                });
+                if names.is_empty() {
+                    huak.remove("editable");
+                }
+            }
+            if huak.is_empty() {
+                tool.remove("huak");
+            }
+        }
+        if tool.is_empty() {
+            self.tool = None;
+        }
+    }
+
     pub fn project_name(&self) -> &str {
         self.project.name.as_str()
     }
@@ -177,6 +292,14 @@ impl Metadata {
             return Ok(true);
         }
 
+        if let Some(groups) = self.dependency_groups() {
+            for g in groups.keys() {
+                if self.contains_group_dependency(dependency, g)? {
+                    return Ok(true);
+                }
+            }
+        }
+
         if let Some(deps) = self.optional_dependencies().as_ref() {
             if deps.is_empty() {
                 return Ok(false);
@@ -244,7 +367,7 @@ impl Metadata {
             .optional_dependencies
             .get_or_insert_with(IndexMap::new)
             .entry(group.to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(dependency.requirement().to_owned());
     }
 
@@ -272,6 +395,169 @@ impl Metadata {
             });
     }
 
+    /// Get the `[dependency-groups]` table as it's declared.
+    pub fn dependency_groups(
+        &self,
+    ) -> Option<&IndexMap<String, Vec<DependencyGroupEntry>>> {
+        self.dependency_groups.as_ref()
+    }
+
+    /// Get the name a dependency group is declared with. Group names are compared
+    /// normalized like package names.
+    fn dependency_group_name(&self, group: &str) -> Option<&str> {
+        let name = normalized_package_name(group);
+        self.dependency_groups.as_ref().and_then(|groups| {
+            groups
+                .keys()
+                .find(|it| normalized_package_name(it) == name)
+                .map(String::as_str)
+        })
+    }
+
+    /// Check if a dependency group is declared in the `[dependency-groups]` table.
+    pub fn has_dependency_group(&self, group: &str) -> bool {
+        self.dependency_group_name(group).is_some()
+    }
+
+    /// Get the requirements of a dependency group with the groups it includes expanded.
+    pub fn dependency_group(
+        &self,
+        group: &str,
+    ) -> HuakResult<Vec<Requirement>> {
+        let mut requirements = Vec::new();
+        self.expand_dependency_group(
+            group,
+            &mut Vec::new(),
+            &mut requirements,
+        )?;
+
+        Ok(requirements)
+    }
+
+    fn expand_dependency_group(
+        &self,
+        group: &str,
+        parents: &mut Vec<String>,
+        requirements: &mut Vec<Requirement>,
+    ) -> HuakResult<()> {
+        let Some(name) = self.dependency_group_name(group) else {
+            return Err(Error::DependencyGroupNotFound(group.to_string()));
+        };
+        if parents.iter().any(|it| it == name) {
+            parents.push(name.to_string());
+            return Err(Error::DependencyGroupCycle(parents.join(" -> ")));
+        }
+        parents.push(name.to_string());
+        let entries = self
+            .dependency_groups
+            .as_ref()
+            .and_then(|groups| groups.get(name))
+            .cloned()
+            .unwrap_or_default();
+        for entry in entries {
+            match entry {
+                DependencyGroupEntry::Requirement(it) => {
+                    if !requirements.contains(&it) {
+                        requirements.push(it);
+                    }
+                }
+                DependencyGroupEntry::IncludeGroup { include_group } => self
+                    .expand_dependency_group(
+                        &include_group,
+                        parents,
+                        requirements,
+                    )?,
+            }
+        }
+        parents.pop();
+
+        Ok(())
+    }
+
+    /// Check if a dependency group lists a `Dependency` itself, not through an included group.
+    pub fn contains_group_dependency(
+        &self,
+        dependency: &Dependency,
+        group: &str,
+    ) -> HuakResult<bool> {
+        let name = normalized_package_name(dependency.name());
+        let Some(entries) = self
+            .dependency_group_name(group)
+            .and_then(|it| self.dependency_groups.as_ref()?.get(it))
+        else {
+            return Ok(false);
+        };
+
+        Ok(entries.iter().any(|it| match it {
+            DependencyGroupEntry::Requirement(req) => {
+                normalized_package_name(&req.name) == name
+            }
+            DependencyGroupEntry::IncludeGroup { .. } => false,
+        }))
+    }
+
+    /// Add a `Dependency` to a dependency group in the `[dependency-groups]` table.
+    pub fn add_group_dependency(
+        &mut self,
+        dependency: Dependency,
+        group: &str,
+    ) {
+        let group = self
+            .dependency_group_name(group)
+            .unwrap_or(group)
+            .to_string();
+        self.dependency_groups
+            .get_or_insert_with(IndexMap::new)
+            .entry(group)
+            .or_default()
+            .push(DependencyGroupEntry::Requirement(
+                dependency.requirement().to_owned(),
+            ));
+    }
+
+    /// Remove a `Dependency` from a dependency group in the `[dependency-groups]` table.
+    pub fn remove_group_dependency(
+        &mut self,
+        dependency: &Dependency,
+        group: &str,
+    ) {
+        let name = normalized_package_name(dependency.name());
+        let Some(group) = self.dependency_group_name(group).map(String::from)
+        else {
+            return;
+        };
+        if let Some(entries) = self
+            .dependency_groups
+            .as_mut()
+            .and_then(|groups| groups.get_mut(&group))
+        {
+            entries.retain(|it| match it {
+                DependencyGroupEntry::Requirement(req) => {
+                    normalized_package_name(&req.name) != name
+                }
+                DependencyGroupEntry::IncludeGroup { .. } => true,
+            });
+        }
+    }
+
+    /// Add a tool `Dependency` huak installed for one of its commands to the project's dev
+    /// dependencies, in the table the `[tool.huak]` table's tool-dependencies chooses.
+    pub fn add_tool_dependency(
+        &mut self,
+        dependency: Dependency,
+    ) -> HuakResult<()> {
+        match self.huak_table()?.tool_dependencies.unwrap_or_default() {
+            ToolDependencies::OptionalDependencies => {
+                self.add_optional_dependency(dependency, "dev")
+            }
+            ToolDependencies::DependencyGroups => {
+                self.add_group_dependency(dependency, "dev")
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn add_script(&mut self, name: &str, entrypoint: &str) {
         self.project
             .scripts
@@ -296,6 +582,7 @@ impl Default for Metadata {
         Metadata {
             build_system,
             project,
+            dependency_groups: None,
             tool: None,
         }
     }
@@ -303,18 +590,115 @@ impl Default for Metadata {
 
 impl PartialEq for Metadata {
     fn eq(&self, other: &Self) -> bool {
-        self.project == other.project && self.tool == other.tool
+        self.project == other.project
+            && self.dependency_groups == other.dependency_groups
+            && self.tool == other.tool
     }
 }
 
 impl Eq for Metadata {}
 
+/// An entry of a dependency group in the `[dependency-groups]` table (see
+/// https://peps.python.org/pep-0735/).
+///
+/// ```toml
+/// [dependency-groups]
+/// test = ["pytest>=8"]
+/// dev = [{include-group = "test"}, "ruff"]
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum DependencyGroupEntry {
+    /// A PEP 508 requirement.
+    Requirement(Requirement),
+    /// Every entry of another dependency group.
+    IncludeGroup {
+        #[serde(rename = "include-group")]
+        include_group: String,
+    },
+}
+
+/// Where the tools huak installs for its commands (like ruff and pytest) are added.
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToolDependencies {
+    /// The dev extra in `[project.optional-dependencies]`.
+    #[default]
+    OptionalDependencies,
+    /// The dev group in `[dependency-groups]`, which isn't published with the project.
+    DependencyGroups,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+/// Huak's configuration from the `[tool.huak]` table of a pyproject.toml.
+pub struct HuakTable {
+    /// The default `PinStrategy` used when adding dependencies.
+    pub pin: Option<PinStrategy>,
+    /// The `InstallerKind` packages are installed with.
+    pub installer: Option<InstallerKind>,
+    /// Where the tools huak installs for its commands are added.
+    pub tool_dependencies: Option<ToolDependencies>,
+    /// The default `InstallOptions` for installing packages.
+    #[serde(flatten)]
+    pub install: InstallOptions,
+    /// Names of local project dependencies installed in editable mode.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub editable: Vec<String>,
+    /// Package indexes by name.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub indexes: IndexMap<String, NamedIndex>,
+    /// Where specific packages are installed from, by package name.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub sources: IndexMap<String, PackageSource>,
+    /// The licenses the project accepts from its dependencies.
+    pub licenses: Option<LicensePolicy>,
+}
+
+impl HuakTable {
+    /// Get the default `InstallOptions` with the indexes filled in. The default named index
+    /// is used if no index-url is set.
+    pub fn install_options(&self) -> HuakResult<InstallOptions> {
+        let mut options = self.install.clone();
+        let urls = options
+            .index_url
+            .iter()
+            .chain(options.extra_index_urls.iter())
+            .chain(options.find_links.iter())
+            .chain(self.indexes.values().map(|it| &it.url));
+        for url in urls {
+            check_url_without_password(url)?;
+        }
+        if options.index_url.is_none() {
+            options.index_url = default_index_url(&self.indexes)?;
+        }
+        options.package_index_urls =
+            package_index_urls(&self.indexes, &self.sources)?;
+        options.indexes = self.indexes.clone();
+
+        Ok(options)
+    }
+
+    /// Check if a `Dependency` is a local project directory installed in editable mode.
+    pub fn is_editable(&self, dependency: &Dependency) -> bool {
+        let name = normalized_package_name(dependency.name());
+        dependency.local_path().map_or(false, |it| it.is_dir())
+            && self
+                .editable
+                .iter()
+                .any(|it| normalized_package_name(it) == name)
+    }
+}
+
 /// A pyproject.toml as specified in PEP 621 with tool table.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct PyProjectToml {
     #[serde(flatten)]
     inner: ProjectToml,
+    dependency_groups: Option<IndexMap<String, Vec<DependencyGroupEntry>>>,
     tool: Option<Table>,
 }
 
@@ -347,6 +731,7 @@ impl Default for PyProjectToml {
         Self {
             inner: ProjectToml::new(&default_pyproject_toml_contents(""))
                 .expect("valid pyproject.toml contents"),
+            dependency_groups: None,
             tool: None,
         }
     }
@@ -444,7 +829,7 @@ dev = [
         let local_metadata = LocalMetadata::new(path).unwrap();
 
         assert_eq!(
-            local_metadata.metadata.dependencies().unwrap().deref(),
+            local_metadata.metadata.dependencies().unwrap(),
             vec![Requirement::from_str("click==8.1.3").unwrap()]
         );
     }
@@ -627,4 +1012,153 @@ dev = [
 "#
         )
     }
+
+    #[test]
+    fn toml_dependency_groups() {
+        let mut metadata: Metadata = toml::from_str(&format!(
+            r#"{}
+[dependency-groups]
+test = ["pytest>=8"]
+Dev = [{{ include-group = "test" }}, "ruff"]
+cycle-a = [{{ include-group = "cycle-b" }}]
+cycle-b = [{{ include-group = "cycle_a" }}]
+"#,
+            default_pyproject_toml_contents("mock-project")
+        ))
+        .unwrap();
+
+        assert_eq!(
+            metadata.dependency_group("dev").unwrap(),
+            [
+                Requirement::from_str("pytest>=8").unwrap(),
+                Requirement::from_str("ruff").unwrap()
+            ]
+        );
+        assert!(matches!(
+            metadata.dependency_group("cycle-a"),
+            Err(Error::DependencyGroupCycle(_))
+        ));
+        assert!(matches!(
+            metadata.dependency_group("docs"),
+            Err(Error::DependencyGroupNotFound(_))
+        ));
+
+        let ruff = Dependency::from_str("ruff").unwrap();
+        let pytest = Dependency::from_str("pytest").unwrap();
+        assert!(metadata.contains_group_dependency(&ruff, "dev").unwrap());
+        assert!(!metadata.contains_group_dependency(&pytest, "dev").unwrap());
+        assert!(metadata.contains_dependency_any(&pytest).unwrap());
+
+        metadata.remove_group_dependency(&ruff, "dev");
+        metadata.add_group_dependency(
+            Dependency::from_str("mypy==1.8.0").unwrap(),
+            "dev",
+        );
+        metadata.add_group_dependency(
+            Dependency::from_str("sphinx").unwrap(),
+            "docs",
+        );
+        let local_metadata = LocalMetadata {
+            metadata,
+            path: PathBuf::new(),
+        };
+        let string = local_metadata.to_string_pretty().unwrap();
+
+        assert!(string.ends_with(
+            r#"[dependency-groups]
+test = ["pytest >=8"]
+Dev = [
+    { include-group = "test" },
+    "mypy ==1.8.0",
+]
+cycle-a = [{ include-group = "cycle-b" }]
+cycle-b = [{ include-group = "cycle_a" }]
+docs = ["sphinx"]
+"#
+        ));
+    }
+
+    #[test]
+    fn toml_tool_dependencies() {
+        let mut metadata: Metadata = toml::from_str(&format!(
+            r#"{}
+[tool.huak]
+tool-dependencies = "dependency-groups"
+"#,
+            default_pyproject_toml_contents("mock-project")
+        ))
+        .unwrap();
+        let ruff = Dependency::from_str("ruff").unwrap();
+
+        metadata.add_tool_dependency(ruff.clone()).unwrap();
+
+        assert!(metadata.contains_group_dependency(&ruff, "dev").unwrap());
+        assert!(metadata.optional_dependencies().is_none());
+    }
+
+    #[test]
+    fn toml_huak_table() {
+        let metadata: Metadata = toml::from_str(&format!(
+            r#"{}
+[tool.huak]
+pin = "lower-bound"
+installer = "uv"
+index-url = "https://mock.index/simple"
+find-links = ["./wheels"]
+no-cache = true
+
+[tool.huak.indexes]
+internal = {{ url = "https://internal.index/simple" }}
+
+[tool.huak.sources]
+ourlib = {{ index = "internal" }}
+
+[tool.huak.licenses]
+deny = ["GPL*"]
+"#,
+            default_pyproject_toml_contents("mock-project")
+        ))
+        .unwrap();
+        let table = metadata.huak_table().unwrap();
+
+        assert_eq!(table.pin, Some(PinStrategy::LowerBound));
+        assert_eq!(table.installer, Some(InstallerKind::Uv));
+        assert_eq!(
+            table.install,
+            InstallOptions {
+                index_url: Some("https://mock.index/simple".to_string()),
+                find_links: vec!["./wheels".to_string()],
+                no_cache: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            table.install_options().unwrap().package_index_urls["ourlib"],
+            "https://internal.index/simple"
+        );
+        assert_eq!(table.licenses.unwrap().deny, ["GPL*"]);
+    }
+
+    #[test]
+    fn toml_editable_dependencies() {
+        let path = crate::test_resources_dir_path()
+            .join("mock-project")
+            .join("pyproject.toml");
+        let mut local_metadata = LocalMetadata::new(path).unwrap();
+        let dep = Dependency::from_path(
+            crate::test_resources_dir_path().join("mock-project"),
+        )
+        .unwrap();
+
+        local_metadata.metadata.add_editable_dependency(&dep);
+        local_metadata.metadata.add_editable_dependency(&dep);
+
+        let table = local_metadata.metadata.huak_table().unwrap();
+        assert_eq!(table.editable, ["mock_project"]);
+        assert!(table.is_editable(&dep));
+
+        local_metadata.metadata.remove_editable_dependency(&dep);
+
+        assert!(local_metadata.metadata.tool.is_none());
+    }
 }
@@ -1,12 +1,19 @@
+use super::{
+    install_dependencies, lock::update_project_lockfile, pin_strategy,
+};
 use crate::{
-    dependency::{dependency_iter, Dependency},
-    Config, HuakResult, InstallOptions,
+    dependency::{Dependency, PinStrategy},
+    metadata::{LocalMetadata, Metadata},
+    Config, Error, HuakResult, InstallOptions,
 };
-use pep440_rs::VersionSpecifiers;
-use pep508_rs::VersionOrUrl;
-use std::str::FromStr;
+use std::{path::Path, str::FromStr};
 
 pub struct AddOptions {
+    /// The `PinStrategy` used for dependencies added without version data. Defaults to the
+    /// project's `[tool.huak]` pin or `PinStrategy::Exact`.
+    pub pin: Option<PinStrategy>,
+    /// Install local project directory dependencies in editable mode.
+    pub editable: bool,
     pub install_options: InstallOptions,
 }
 
@@ -20,37 +27,43 @@ pub fn add_project_dependencies(
     let mut metadata = workspace.current_local_metadata()?;
 
     // Collect all dependencies that need to be added to the metadata file.
-    let mut deps: Vec<Dependency> = dependency_iter(dependencies)
-        .filter(|dep| {
-            !metadata
-                .metadata()
-                .contains_dependency(dep)
-                .unwrap_or_default()
-        })
-        .collect::<Vec<_>>();
+    let mut deps: Vec<Dependency> =
+        parse_dependencies(dependencies, &config.cwd)?
+            .into_iter()
+            .filter(|dep| {
+                !metadata
+                    .metadata()
+                    .contains_dependency(dep)
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
 
     if deps.is_empty() {
         return Ok(());
     }
 
+    if options.editable {
+        add_editable_dependencies(&deps, &mut metadata)?;
+    }
+
     let python_env = workspace.resolve_python_environment()?;
-    python_env.install_packages(&deps, &options.install_options, config)?;
+    install_dependencies(
+        &python_env,
+        &deps,
+        metadata.metadata(),
+        &options.install_options,
+        config,
+    )?;
 
     // If there's no version data then get the installed version and add to metadata file.
+    let strategy = pin_strategy(options.pin, metadata.metadata())?;
     let packages = python_env.installed_packages()?; // TODO: Only run if versions weren't provided.
     for dep in deps.iter_mut() {
         if dep.requirement().version_or_url.is_none() {
             // TODO: Optimize this .find
             if let Some(pkg) = packages.iter().find(|p| p.name() == dep.name())
             {
-                dep.requirement_mut().version_or_url =
-                    Some(VersionOrUrl::VersionSpecifier(
-                        VersionSpecifiers::from_str(&format!(
-                            "=={}",
-                            pkg.version()
-                        ))
-                        .expect("package should have a version"),
-                    ));
+                dep.pin(pkg.version(), strategy)?;
             }
         }
 
@@ -63,7 +76,13 @@ pub fn add_project_dependencies(
         metadata.write_file()?;
     }
 
-    Ok(())
+    update_project_lockfile(
+        &workspace,
+        metadata.metadata(),
+        &python_env,
+        &options.install_options,
+        config,
+    )
 }
 
 pub fn add_project_optional_dependencies(
@@ -71,17 +90,47 @@ pub fn add_project_optional_dependencies(
     group: &str,
     config: &Config,
     options: &AddOptions,
+) -> HuakResult<()> {
+    add_grouped_dependencies(
+        dependencies,
+        DependencyTable::Optional(group),
+        config,
+        options,
+    )
+}
+
+/// Add dependencies to a PEP 735 dependency group in the `[dependency-groups]` table. Unlike
+/// optional dependencies, dependency groups aren't published with the project.
+pub fn add_project_group_dependencies(
+    dependencies: &[String],
+    group: &str,
+    config: &Config,
+    options: &AddOptions,
+) -> HuakResult<()> {
+    add_grouped_dependencies(
+        dependencies,
+        DependencyTable::Group(group),
+        config,
+        options,
+    )
+}
+
+fn add_grouped_dependencies(
+    dependencies: &[String],
+    table: DependencyTable,
+    config: &Config,
+    options: &AddOptions,
 ) -> HuakResult<()> {
     let workspace = config.workspace();
     let package = workspace.current_package()?;
     let mut metadata = workspace.current_local_metadata()?;
 
     // Collect all dependencies that need to be added.
-    let mut deps = dependency_iter(dependencies)
+    let mut deps = parse_dependencies(dependencies, &config.cwd)?
+        .into_iter()
         .filter(|dep| {
-            !metadata
-                .metadata()
-                .contains_optional_dependency(dep, group)
+            !table
+                .contains_dependency(metadata.metadata(), dep)
                 .unwrap_or_default()
         })
         .collect::<Vec<Dependency>>();
@@ -90,34 +139,33 @@ pub fn add_project_optional_dependencies(
         return Ok(());
     };
 
+    if options.editable {
+        add_editable_dependencies(&deps, &mut metadata)?;
+    }
+
     let python_env = workspace.resolve_python_environment()?;
-    python_env.install_packages(&deps, &options.install_options, config)?;
+    install_dependencies(
+        &python_env,
+        &deps,
+        metadata.metadata(),
+        &options.install_options,
+        config,
+    )?;
 
     // If there's no version data then get the installed version and add to metadata file.
+    let strategy = pin_strategy(options.pin, metadata.metadata())?;
     let packages = python_env.installed_packages()?; // TODO: Only run if versions weren't provided.
     for dep in deps.iter_mut() {
         if dep.requirement().version_or_url.is_none() {
             // TODO: Optimize this .find
             if let Some(pkg) = packages.iter().find(|p| p.name() == dep.name())
             {
-                dep.requirement_mut().version_or_url =
-                    Some(VersionOrUrl::VersionSpecifier(
-                        VersionSpecifiers::from_str(&format!(
-                            "=={}",
-                            pkg.version()
-                        ))
-                        .expect("package should have a version"),
-                    ));
+                dep.pin(pkg.version(), strategy)?;
             }
         }
 
-        if !metadata
-            .metadata()
-            .contains_optional_dependency(dep, group)?
-        {
-            metadata
-                .metadata_mut()
-                .add_optional_dependency(dep.clone(), group);
+        if !table.contains_dependency(metadata.metadata(), dep)? {
+            table.add_dependency(metadata.metadata_mut(), dep.clone());
         }
     }
 
@@ -125,6 +173,80 @@ pub fn add_project_optional_dependencies(
         metadata.write_file()?;
     }
 
+    update_project_lockfile(
+        &workspace,
+        metadata.metadata(),
+        &python_env,
+        &options.install_options,
+        config,
+    )
+}
+
+/// The table of the metadata file a group of dependencies is added to.
+#[derive(Clone, Copy)]
+enum DependencyTable<'a> {
+    /// An optional dependency group in `[project.optional-dependencies]`.
+    Optional(&'a str),
+    /// A dependency group in `[dependency-groups]`.
+    Group(&'a str),
+}
+
+impl DependencyTable<'_> {
+    fn contains_dependency(
+        &self,
+        metadata: &Metadata,
+        dependency: &Dependency,
+    ) -> HuakResult<bool> {
+        match self {
+            DependencyTable::Optional(group) => {
+                metadata.contains_optional_dependency(dependency, group)
+            }
+            DependencyTable::Group(group) => {
+                metadata.contains_group_dependency(dependency, group)
+            }
+        }
+    }
+
+    fn add_dependency(&self, metadata: &mut Metadata, dependency: Dependency) {
+        match self {
+            DependencyTable::Optional(group) => {
+                metadata.add_optional_dependency(dependency, group)
+            }
+            DependencyTable::Group(group) => {
+                metadata.add_group_dependency(dependency, group)
+            }
+        }
+    }
+}
+
+/// Parse `Dependency`s from PEP 508 strings or paths to local wheels and project
+/// directories relative to the current working directory.
+fn parse_dependencies(
+    dependencies: &[String],
+    cwd: &Path,
+) -> HuakResult<Vec<Dependency>> {
+    dependencies
+        .iter()
+        .map(|it| match Dependency::from_str(it) {
+            Ok(dep) => Ok(dep),
+            Err(e) if !cwd.join(it).exists() => Err(e),
+            Err(_) => Dependency::from_path(cwd.join(it)),
+        })
+        .collect()
+}
+
+/// Mark local project directory `Dependency`s to be installed in editable mode.
+fn add_editable_dependencies(
+    dependencies: &[Dependency],
+    metadata: &mut LocalMetadata,
+) -> HuakResult<()> {
+    for dep in dependencies {
+        if !dep.local_path().map_or(false, |it| it.is_dir()) {
+            return Err(Error::DependencyNotEditable(dep.to_string()));
+        }
+        metadata.metadata_mut().add_editable_dependency(dep);
+    }
+
     Ok(())
 }
 
@@ -136,6 +258,7 @@ mod tests {
         ops::{test_config, test_venv},
         test_resources_dir_path, Verbosity,
     };
+    use std::str::FromStr;
     use tempfile::tempdir;
 
     #[test]
@@ -153,7 +276,9 @@ mod tests {
         test_venv(&ws);
         let venv = ws.resolve_python_environment().unwrap();
         let options = AddOptions {
-            install_options: InstallOptions { values: None },
+            pin: None,
+            editable: false,
+            install_options: InstallOptions::default(),
         };
 
         add_project_dependencies(&[String::from("ruff")], &config, &options)
@@ -182,7 +307,9 @@ mod tests {
         test_venv(&ws);
         let venv = ws.resolve_python_environment().unwrap();
         let options = AddOptions {
-            install_options: InstallOptions { values: None },
+            pin: None,
+            editable: false,
+            install_options: InstallOptions::default(),
         };
 
         add_project_optional_dependencies(
@@ -202,4 +329,40 @@ mod tests {
             .contains_optional_dependency(&dep, "dev")
             .unwrap());
     }
+
+    #[test]
+    fn test_parse_dependencies() {
+        let cwd = test_resources_dir_path();
+        let deps = parse_dependencies(
+            &[
+                String::from("click==8.1.3"),
+                String::from(
+                    "mock-package @ git+https://github.com/mock/mock-package",
+                ),
+                String::from("./mock-wheels/mock_utils-1.0.0-py3-none-any.whl"),
+                String::from("mock-project"),
+                String::from("./mock-project"),
+            ],
+            &cwd,
+        )
+        .unwrap();
+
+        assert_eq!(
+            deps.iter().map(Dependency::name).collect::<Vec<_>>(),
+            [
+                "click",
+                "mock-package",
+                "mock_utils",
+                "mock-project",
+                "mock_project"
+            ]
+        );
+        assert!(deps[1].local_path().is_none());
+        assert!(deps[2].local_path().unwrap().is_file());
+        assert!(deps[3].url().is_none());
+        assert!(deps[4].local_path().unwrap().is_dir());
+        assert!(
+            parse_dependencies(&[String::from("./missing.whl")], &cwd).is_err()
+        );
+    }
 }
@@ -0,0 +1,389 @@
+use super::{installed_distributions, lock::dependency_groups};
+use crate::{
+    advisory::{AdvisoryDatabase, Severity},
+    dependency_graph::DependencyGraph,
+    lockfile::Lockfile,
+    package::normalized_package_name,
+    Config, Error, HuakResult,
+};
+use pep440_rs::Version;
+use serde::Serialize;
+use serde_json::json;
+use std::{fmt::Write, path::PathBuf, str::FromStr};
+use termcolor::Color;
+
+pub struct AuditOptions {
+    /// The directory or zip archive of OSV advisories. Defaults to `HUAK_ADVISORY_DB`.
+    pub db: Option<PathBuf>,
+    /// The ids (or aliases) of advisories to ignore.
+    pub ignore: Vec<String>,
+    /// Audit the packages pinned in huak.lock instead of the installed packages.
+    pub locked: bool,
+    pub format: AuditFormat,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The output format of `huak audit`.
+pub enum AuditFormat {
+    #[default]
+    Text,
+    Json,
+    /// A SARIF 2.1.0 log for code scanning tools.
+    Sarif,
+}
+
+impl FromStr for AuditFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(AuditFormat::Text),
+            "json" => Ok(AuditFormat::Json),
+            "sarif" => Ok(AuditFormat::Sarif),
+            _ => Err(Error::HuakConfigurationError(format!(
+                "invalid audit format {s} (expected one of text, json, sarif)"
+            ))),
+        }
+    }
+}
+
+/// Check the project's installed (or locked) packages against an OSV advisory database.
+///
+/// The report is printed before returning an error if any package is affected by an
+/// advisory that isn't ignored.
+pub fn audit_project(
+    config: &Config,
+    options: &AuditOptions,
+) -> HuakResult<()> {
+    let workspace = config.workspace();
+    let db_path = options
+        .db
+        .clone()
+        .or_else(AdvisoryDatabase::path_from_env)
+        .ok_or(Error::AdvisoryDatabaseNotFound)?;
+    let db = AdvisoryDatabase::from_path(db_path)?;
+    let mut terminal = config.terminal();
+    for it in db.warnings() {
+        terminal.print_warning(it)?;
+    }
+
+    let (packages, location) = if options.locked {
+        let lockfile_path = workspace.current_lockfile_path()?;
+        if !lockfile_path.exists() {
+            return Err(Error::LockfileNotFound);
+        }
+        let packages = Lockfile::new(&lockfile_path)?
+            .packages()
+            .iter()
+            .map(|it| AuditedPackage {
+                name: it.name.clone(),
+                version: it.version.clone(),
+                groups: it.groups.clone(),
+            })
+            .collect::<Vec<_>>();
+        (packages, lockfile_path)
+    } else {
+        let metadata = workspace.current_local_metadata()?;
+        let python_env = workspace.resolve_python_environment()?;
+        let graph = DependencyGraph::new(
+            dependency_groups(metadata.metadata())?,
+            &installed_distributions(&python_env, config)?,
+            &python_env.marker_environment()?,
+        );
+        (installed_packages(&graph), metadata.path().to_path_buf())
+    };
+    let location = location
+        .strip_prefix(&config.cwd)
+        .unwrap_or(&location)
+        .display()
+        .to_string()
+        .replace('\\', "/");
+
+    let findings = audit_packages(&db, &packages, &options.ignore);
+    match options.format {
+        AuditFormat::Text if findings.is_empty() => {
+            terminal.print_custom(
+                "audited",
+                format!(
+                    "{} packages against {} advisories, no vulnerabilities found",
+                    packages.len(),
+                    db.len()
+                ),
+                Color::Green,
+                false,
+            )?;
+        }
+        AuditFormat::Text => {
+            terminal.print_stdout(text(&findings).trim_end())?;
+        }
+        AuditFormat::Json => {
+            terminal.print_stdout(serde_json::to_string_pretty(&findings)?)?;
+        }
+        AuditFormat::Sarif => {
+            terminal.print_stdout(serde_json::to_string_pretty(&sarif(
+                &findings, &location,
+            ))?)?;
+        }
+    }
+
+    if findings.is_empty() {
+        return Ok(());
+    }
+    let mut affected = findings
+        .iter()
+        .map(|it| it.name.as_str())
+        .collect::<Vec<_>>();
+    affected.dedup();
+    let advisories = match findings.len() {
+        1 => "1 advisory affects".to_string(),
+        n => format!("{n} advisories affect"),
+    };
+    Err(Error::VulnerabilitiesFound(format!(
+        "{advisories} {}",
+        affected.join(", ")
+    )))
+}
+
+/// A package to audit with the dependency groups that require it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AuditedPackage {
+    name: String,
+    version: Version,
+    groups: Vec<String>,
+}
+
+/// A package affected by an advisory.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    name: String,
+    version: String,
+    groups: Vec<String>,
+    id: String,
+    aliases: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<String>,
+    /// The affected versions as version specifiers.
+    affected: Vec<String>,
+    fixed: Vec<String>,
+    severity: Vec<Severity>,
+}
+
+/// Get every installed package of a `DependencyGraph`. Packages nothing declared
+/// requires are audited without a group.
+fn installed_packages(graph: &DependencyGraph) -> Vec<AuditedPackage> {
+    let mut packages = graph
+        .packages()
+        .map(|it| AuditedPackage {
+            name: it.name.clone(),
+            version: it.version.clone(),
+            groups: graph
+                .requiring_groups(&it.name)
+                .into_iter()
+                .map(ToString::to_string)
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    packages.sort_by_key(|it| normalized_package_name(&it.name));
+
+    packages
+}
+
+/// Find the advisories that affect each package. Advisories are ignored by their id or
+/// any of their aliases.
+fn audit_packages(
+    db: &AdvisoryDatabase,
+    packages: &[AuditedPackage],
+    ignore: &[String],
+) -> Vec<Finding> {
+    let is_ignored =
+        |id: &str| ignore.iter().any(|it| it.eq_ignore_ascii_case(id));
+
+    packages
+        .iter()
+        .flat_map(|package| {
+            db.matches(&package.name, &package.version)
+                .into_iter()
+                .filter(|it| {
+                    !is_ignored(&it.advisory.id)
+                        && !it.advisory.aliases.iter().any(|it| is_ignored(it))
+                })
+                .map(|it| Finding {
+                    name: package.name.clone(),
+                    version: package.version.to_string(),
+                    groups: package.groups.clone(),
+                    id: it.advisory.id.clone(),
+                    aliases: it.advisory.aliases.clone(),
+                    summary: it.advisory.summary.clone(),
+                    details: it.advisory.details.clone(),
+                    affected: it.affected,
+                    fixed: it.fixed.iter().map(ToString::to_string).collect(),
+                    severity: it.advisory.severity.clone(),
+                })
+        })
+        .collect()
+}
+
+fn text(findings: &[Finding]) -> String {
+    let mut out = String::new();
+    for it in findings {
+        let groups = match it.groups.is_empty() {
+            true => "not required by the project".to_string(),
+            false => it.groups.join(", "),
+        };
+        writeln!(out, "{} {} ({groups})", it.name, it.version).ok();
+        let mut id = it.id.clone();
+        if !it.aliases.is_empty() {
+            write!(id, " ({})", it.aliases.join(", ")).ok();
+        }
+        match it.summary.as_ref() {
+            Some(summary) => writeln!(out, "  {id}: {summary}").ok(),
+            None => writeln!(out, "  {id}").ok(),
+        };
+        writeln!(out, "    affected: {}", it.affected.join(" || ")).ok();
+        let fixed = match it.fixed.is_empty() {
+            true => "no fix available".to_string(),
+            false => it.fixed.join(", "),
+        };
+        writeln!(out, "    fixed: {fixed}").ok();
+    }
+
+    out
+}
+
+/// Build a SARIF 2.1.0 log with a rule for each advisory and a result for each finding.
+/// Results are located at the file the packages were read from.
+fn sarif(findings: &[Finding], location: &str) -> serde_json::Value {
+    let mut rules = Vec::new();
+    let mut ids = Vec::new();
+    for it in findings {
+        if ids.contains(&it.id) {
+            continue;
+        }
+        ids.push(it.id.clone());
+        let summary = it.summary.clone().unwrap_or_else(|| it.id.clone());
+        rules.push(json!({
+            "id": it.id,
+            "shortDescription": { "text": summary },
+            "fullDescription": {
+                "text": it.details.clone().unwrap_or(summary),
+            },
+            "helpUri": format!("https://osv.dev/vulnerability/{}", it.id),
+            "properties": { "tags": ["security"], "aliases": it.aliases },
+        }));
+    }
+
+    let results = findings
+        .iter()
+        .map(|it| {
+            let fixed = match it.fixed.is_empty() {
+                true => "no fix is available".to_string(),
+                false => format!("fixed in {}", it.fixed.join(", ")),
+            };
+            json!({
+                "ruleId": it.id,
+                "ruleIndex": ids.iter().position(|id| id == &it.id),
+                "level": "error",
+                "message": {
+                    "text": format!(
+                        "{} {} is affected by {} ({fixed})",
+                        it.name, it.version, it.id
+                    ),
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": location },
+                    },
+                }],
+                "properties": {
+                    "package": it.name,
+                    "version": it.version,
+                    "groups": it.groups,
+                    "affected": it.affected,
+                    "fixed": it.fixed,
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "huak",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/cnpryer/huak",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        site_packages::installed_distributions, test_marker_environment,
+        test_resources_dir_path,
+    };
+    use indexmap::IndexMap;
+    use pep508_rs::Requirement;
+
+    #[test]
+    fn test_audit_packages() {
+        let db = AdvisoryDatabase::from_path(
+            test_resources_dir_path().join("mock-advisories"),
+        )
+        .unwrap();
+        let dists = installed_distributions(
+            test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+        let mut groups = IndexMap::new();
+        groups.insert(
+            "dev".to_string(),
+            vec![Requirement::from_str("mock-legacy").unwrap()],
+        );
+        let graph =
+            DependencyGraph::new(groups, &dists, &test_marker_environment());
+        let packages = installed_packages(&graph);
+
+        let findings = audit_packages(&db, &packages, &[]);
+
+        assert_eq!(
+            text(&findings),
+            r#"mock_utils 1.0.0 (dev)
+  MOCK-2024-1 (CVE-2024-0001): Mock utils parses untrusted input unsafely
+    affected: <1.5.0
+    fixed: 1.5.0
+mock-web 2.0 (not required by the project)
+  MOCK-2024-2: Mock web leaks request headers
+    affected: >=1.0, <1.2 || >=2.0, <=2.1
+    fixed: 1.2
+"#
+        );
+        assert_eq!(
+            audit_packages(&db, &packages, &["cve-2024-0001".to_string()])
+                .iter()
+                .map(|it| it.id.as_str())
+                .collect::<Vec<_>>(),
+            ["MOCK-2024-2"]
+        );
+
+        let log = sarif(&findings, "pyproject.toml");
+        let run = &log["runs"][0];
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][1]["id"], "MOCK-2024-2");
+        assert_eq!(run["results"][0]["ruleId"], "MOCK-2024-1");
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]
+                ["artifactLocation"]["uri"],
+            "pyproject.toml"
+        );
+    }
+}
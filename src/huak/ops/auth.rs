@@ -0,0 +1,161 @@
+use crate::{
+    credentials::{netrc_path, KeyringCredentials, Netrc},
+    Config, Error, HuakResult, InstallOptions,
+};
+use std::path::Path;
+use termcolor::Color;
+use url::Url;
+
+pub struct LoginOptions {
+    /// The name of an index in the `[tool.huak.indexes]` table, or an index URL.
+    pub index: String,
+    pub username: String,
+    pub password: String,
+    /// Store the password in the system keyring instead of the netrc file. The username
+    /// is still stored in the netrc file so the keyring can be asked for its password.
+    pub keyring: bool,
+}
+
+/// Store the credentials of a package index in the user's netrc file (or keyring).
+pub fn login_to_index(
+    config: &Config,
+    options: &LoginOptions,
+) -> HuakResult<()> {
+    let Some(path) = netrc_path() else {
+        return Err(Error::HuakConfigurationError(
+            "a netrc file could not be found (set NETRC)".to_string(),
+        ));
+    };
+
+    login_with_netrc(config, options, &path)
+}
+
+/// Store the credentials of a package index in the netrc file at `path` (or keyring).
+fn login_with_netrc(
+    config: &Config,
+    options: &LoginOptions,
+    path: &Path,
+) -> HuakResult<()> {
+    let url = index_url(config, &options.index)?;
+    let host = url.host_str().unwrap_or_default();
+    let mut netrc = match path.exists() {
+        true => Netrc::from_path(path)?,
+        false => Netrc::default(),
+    };
+
+    let password = match options.keyring {
+        true => {
+            KeyringCredentials::set(
+                url.as_str(),
+                &options.username,
+                &options.password,
+            )?;
+            None
+        }
+        false => Some(options.password.as_str()),
+    };
+    netrc.set(host, &options.username, password);
+    netrc.write_file(path)?;
+
+    let location = match options.keyring {
+        true => "the keyring".to_string(),
+        false => path.display().to_string(),
+    };
+    config.terminal().print_custom(
+        "logged in",
+        format!("{} to {host} (saved to {location})", options.username),
+        Color::Green,
+        false,
+    )
+}
+
+/// Remove the credentials of a package index from the user's netrc file and keyring.
+pub fn logout_of_index(config: &Config, index: &str) -> HuakResult<()> {
+    logout_with_netrc(config, index, netrc_path().as_deref())
+}
+
+/// Remove the credentials of a package index from the netrc file at `path` and keyring.
+fn logout_with_netrc(
+    config: &Config,
+    index: &str,
+    path: Option<&Path>,
+) -> HuakResult<()> {
+    let url = index_url(config, index)?;
+    let host = url.host_str().unwrap_or_default();
+    let mut terminal = config.terminal();
+
+    let entry = match path.filter(|it| it.exists()) {
+        Some(path) => {
+            let mut netrc = Netrc::from_path(path)?;
+            let entry = netrc.remove(host);
+            if entry.is_some() {
+                netrc.write_file(path)?;
+            }
+            entry
+        }
+        None => None,
+    };
+    let Some(entry) = entry else {
+        return terminal
+            .print_warning(format!("no credentials are stored for {host}"));
+    };
+    if let Some(username) = entry.login.as_ref() {
+        KeyringCredentials::delete(url.as_str(), username);
+    }
+
+    terminal.print_custom("logged out", host, Color::Green, false)
+}
+
+/// Get the URL of an index from its name in the `[tool.huak.indexes]` table or its URL.
+fn index_url(config: &Config, index: &str) -> HuakResult<Url> {
+    let indexes = config.install_options(&InstallOptions::default())?.indexes;
+    let url = match indexes.get(index) {
+        Some(it) => Url::parse(&it.url),
+        None => Url::parse(index),
+    };
+
+    match url {
+        Ok(it) if it.host_str().is_some() && it.password().is_none() => Ok(it),
+        _ => Err(Error::HuakConfigurationError(format!(
+            "{index} isn't an index URL or the name of an index in [tool.huak.indexes]"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ops::test_config, Verbosity};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_login_and_logout() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".netrc");
+        let config = test_config(dir.path(), dir.path(), Verbosity::Quiet);
+        let options = LoginOptions {
+            index: "https://pypi.example.com/simple".to_string(),
+            username: "alice".to_string(),
+            password: "secret".to_string(),
+            keyring: false,
+        };
+
+        login_with_netrc(&config, &options, &path).unwrap();
+
+        let netrc = Netrc::from_path(&path).unwrap();
+        let entry = netrc.get("pypi.example.com").unwrap();
+        assert_eq!(entry.login.as_deref(), Some("alice"));
+        assert_eq!(entry.password.as_deref(), Some("secret"));
+
+        logout_with_netrc(
+            &config,
+            "https://pypi.example.com/simple",
+            Some(&path),
+        )
+        .unwrap();
+
+        let netrc = Netrc::from_path(&path).unwrap();
+        assert!(netrc.get("pypi.example.com").is_none());
+        assert!(index_url(&config, "internal").is_err());
+    }
+}
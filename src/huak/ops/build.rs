@@ -1,4 +1,4 @@
-use super::make_venv_command;
+use super::{make_venv_command, pin_strategy, pinned_dependency};
 use crate::{dependency::Dependency, Config, HuakResult, InstallOptions};
 use std::{process::Command, str::FromStr};
 
@@ -29,15 +29,15 @@ pub fn build_project(
 
     // Add the installed `build` package to the metadata file.
     if !metadata.metadata().contains_dependency_any(&build_dep)? {
+        let strategy = pin_strategy(None, metadata.metadata())?;
         for pkg in python_env
             .installed_packages()?
             .iter()
             .filter(|pkg| pkg.name() == build_dep.name())
         {
-            metadata.metadata_mut().add_optional_dependency(
-                Dependency::from_str(&pkg.to_string())?,
-                "dev",
-            );
+            metadata
+                .metadata_mut()
+                .add_tool_dependency(pinned_dependency(pkg, strategy)?)?;
         }
     }
 
@@ -82,7 +82,7 @@ mod tests {
         test_venv(&ws);
         let options = BuildOptions {
             values: None,
-            install_options: InstallOptions { values: None },
+            install_options: InstallOptions::default(),
         };
 
         build_project(&config, &options).unwrap();
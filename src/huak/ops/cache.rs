@@ -0,0 +1,41 @@
+use crate::{cache::Cache, fs, Config, HuakResult};
+use termcolor::Color;
+
+pub fn display_cache_dir(config: &Config) -> HuakResult<()> {
+    let cache = Cache::from_env()?;
+
+    config.terminal().print_stdout(cache.root().display())
+}
+
+pub fn display_cache_info(config: &Config) -> HuakResult<()> {
+    let cache = Cache::from_env()?;
+    let info = cache.info()?;
+
+    let mut terminal = config.terminal();
+    terminal.print_stdout(format!("location: {}", cache.root().display()))?;
+    terminal.print_stdout(format!("wheels: {}", info.wheels))?;
+    terminal.print_stdout(format!("unpacked: {}", info.unpacked))?;
+    terminal.print_stdout(format!("size: {}", fs::format_size(info.size)))
+}
+
+pub fn clean_cache(config: &Config) -> HuakResult<()> {
+    let freed = Cache::from_env()?.clean()?;
+
+    config.terminal().print_custom(
+        "cleaned",
+        format!("{} freed", fs::format_size(freed)),
+        Color::Green,
+        false,
+    )
+}
+
+pub fn prune_cache(config: &Config) -> HuakResult<()> {
+    let freed = Cache::from_env()?.prune()?;
+
+    config.terminal().print_custom(
+        "pruned",
+        format!("{} freed", fs::format_size(freed)),
+        Color::Green,
+        false,
+    )
+}
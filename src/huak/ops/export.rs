@@ -1,13 +1,102 @@
-use crate::{dependency::Dependency, Config, Error, HuakResult};
+use super::{
+    dependencies_for_environment, installed_distributions,
+    lock::dependency_groups,
+};
+use crate::{
+    dependency::Dependency,
+    dependency_graph::DependencyGraph,
+    installer::parse_requirements,
+    licenses::License,
+    lockfile::{LockedPackage, Lockfile},
+    metadata::HuakTable,
+    package::normalized_package_name,
+    python_environment::marker_environment_for_python,
+    resolver::override_requirement,
+    sbom::{sbom_timestamp, Sbom, SbomPackage},
+    site_packages::InstalledDistribution,
+    workspace::Workspace,
+    Config, Error, HuakResult, InstallOptions,
+};
 use indexmap::IndexMap;
+use pep508_rs::Requirement;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::{
+    value, Array, ArrayOfTables, Document, InlineTable, Item, Table, Value,
+};
+use url::Url;
 
 pub struct ExportOptions {
     pub include: Option<String>,
     pub exclude: Option<String>,
     pub output_file: String,
+    /// Python versions or interpreter paths to export a requirements file for each. Only
+    /// dependencies whose environment markers apply to the interpreter are exported.
+    pub python: Option<Vec<String>>,
+    pub format: ExportFormat,
+    /// Export the distributions pinned in huak.lock instead of the installed distributions.
+    /// SBOMs are exported from the environment by default, other locked formats always read
+    /// huak.lock.
+    pub locked: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The format of the file `huak export` writes.
+pub enum ExportFormat {
+    /// A requirements file of the project's declared dependencies.
+    #[default]
+    RequirementsTxt,
+    /// A CycloneDX 1.5 JSON SBOM.
+    CycloneDxJson,
+    /// An SPDX 2.3 JSON SBOM.
+    SpdxJson,
+    /// A requirements file of the locked distributions with their hashes and `# via`
+    /// comments.
+    PinnedRequirementsTxt,
+    /// A pip constraints file pinning every locked distribution.
+    ConstraintsTxt,
+    /// A PEP 751 lock file.
+    PylockToml,
+    /// A conda environment file installing the locked distributions with pip.
+    EnvironmentYml,
+}
+
+impl ExportFormat {
+    /// Get the name of the file the format is exported to by default.
+    pub fn default_file_name(&self) -> &str {
+        match self {
+            ExportFormat::RequirementsTxt => "requirements.txt",
+            ExportFormat::CycloneDxJson => "bom.cdx.json",
+            ExportFormat::SpdxJson => "bom.spdx.json",
+            ExportFormat::PinnedRequirementsTxt => "requirements.txt",
+            ExportFormat::ConstraintsTxt => "constraints.txt",
+            ExportFormat::PylockToml => "pylock.toml",
+            ExportFormat::EnvironmentYml => "environment.yml",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "requirements-txt" => Ok(ExportFormat::RequirementsTxt),
+            "cyclonedx-json" => Ok(ExportFormat::CycloneDxJson),
+            "spdx-json" => Ok(ExportFormat::SpdxJson),
+            "pinned-requirements-txt" => Ok(ExportFormat::PinnedRequirementsTxt),
+            "constraints-txt" => Ok(ExportFormat::ConstraintsTxt),
+            "pylock-toml" => Ok(ExportFormat::PylockToml),
+            "environment-yml" => Ok(ExportFormat::EnvironmentYml),
+            _ => Err(Error::HuakConfigurationError(format!(
+                "invalid export format {s} (expected one of requirements-txt, \
+                 pinned-requirements-txt, constraints-txt, pylock-toml, environment-yml, \
+                 cyclonedx-json, spdx-json)"
+            ))),
+        }
+    }
 }
 
 pub fn export_dependencies_to_file(
@@ -34,10 +123,32 @@ pub fn export_dependencies_to_file(
         }
     }
 
+    match options.format {
+        ExportFormat::CycloneDxJson | ExportFormat::SpdxJson => {
+            return export_sbom(config, options, &output_file_path)
+        }
+        ExportFormat::PinnedRequirementsTxt
+        | ExportFormat::ConstraintsTxt
+        | ExportFormat::PylockToml
+        | ExportFormat::EnvironmentYml => {
+            return export_locked(config, options, &output_file_path)
+        }
+        ExportFormat::RequirementsTxt if options.locked => {
+            return Err(Error::HuakConfigurationError(
+                "--locked can't be used to export requirements-txt (use pinned-requirements-txt)"
+                    .to_string(),
+            ))
+        }
+        ExportFormat::RequirementsTxt => (),
+    }
+
     let dependencies = metadata.metadata().dependencies();
     let optional_dependencies = metadata.metadata().optional_dependencies();
+    let dependency_groups = metadata.metadata().dependency_groups();
 
-    if dependencies.is_none() || optional_dependencies.is_none() {
+    if dependencies.is_none()
+        || (optional_dependencies.is_none() && dependency_groups.is_none())
+    {
         return Err(Error::ProjectDependenciesNotFound);
     }
 
@@ -54,7 +165,7 @@ pub fn export_dependencies_to_file(
     for dep in &dependencies {
         all_dependencies
             .entry("required".to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(dep.clone());
     }
     if let Some(opt_deps) = optional_dependencies {
@@ -63,6 +174,17 @@ pub fn export_dependencies_to_file(
             all_dependencies.insert(group.clone(), deps);
         }
     }
+    if let Some(groups) = dependency_groups {
+        for group in groups.keys() {
+            let deps = all_dependencies.entry(group.clone()).or_default();
+            for req in metadata.metadata().dependency_group(group)? {
+                let dep = Dependency::from(req);
+                if !deps.contains(&dep) {
+                    deps.push(dep);
+                }
+            }
+        }
+    }
 
     let include = options
         .include
@@ -79,24 +201,775 @@ pub fn export_dependencies_to_file(
     let processed_dependencies =
         process_dependencies(include_slice, exclude_slice, &all_dependencies)?;
 
-    let mut output_file = match File::create(&output_file_path) {
+    // Overrides replace the requirements exported for their packages. The rest are
+    // written to a constraints file with the project's constraints, since pip can't
+    // override the requirements of other packages.
+    let install_options = config.install_options(&InstallOptions::default())?;
+    let overrides = parse_requirements(&install_options.overrides)?;
+    let processed_dependencies = processed_dependencies
+        .into_iter()
+        .map(
+            |dep| match override_requirement(dep.requirement(), &overrides) {
+                Some(it) => Dependency::from(it),
+                None => dep,
+            },
+        )
+        .collect::<Vec<_>>();
+    let mut constraints = parse_requirements(&install_options.constraints)?;
+    for it in overrides {
+        let name = normalized_package_name(&it.name);
+        if !processed_dependencies
+            .iter()
+            .any(|dep| normalized_package_name(dep.name()) == name)
+        {
+            config.terminal().print_warning(format!(
+                "the override {it} is exported as a constraint"
+            ))?;
+            constraints.push(it);
+        }
+    }
+    let constraints_file_path = match constraints.is_empty() {
+        true => None,
+        false => {
+            let path = constraints_file_path(&output_file_path);
+            write_constraints_file(&path, &constraints)?;
+            Some(path)
+        }
+    };
+
+    let table = metadata.metadata().huak_table()?;
+    let Some(pythons) = options.python.as_ref() else {
+        return write_requirements_file(
+            &output_file_path,
+            &processed_dependencies,
+            constraints_file_path.as_deref(),
+            &table,
+        );
+    };
+
+    for python in pythons {
+        let env =
+            marker_environment_for_python(python_path(&workspace, python)?)?;
+        let dependencies = dependencies_for_environment(
+            processed_dependencies.clone(),
+            &env,
+            config,
+        )?;
+        let path = interpreter_output_file_path(
+            &output_file_path,
+            &env.python_version.1.release,
+        );
+        write_requirements_file(
+            path,
+            &dependencies,
+            constraints_file_path.as_deref(),
+            &table,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write an SBOM of the project and the distributions installed to its environment (or
+/// pinned in its lockfile).
+///
+/// Hashes are read from the lockfile for installed distributions it pins at the same
+/// version. If groups are included or excluded only the distributions the exported groups
+/// require are written.
+fn export_sbom(
+    config: &Config,
+    options: &ExportOptions,
+    path: &Path,
+) -> HuakResult<()> {
+    if options.python.is_some() {
+        return Err(python_option_error());
+    }
+    let workspace = config.workspace();
+    let metadata = workspace.current_local_metadata()?;
+    let groups = dependency_groups(metadata.metadata())?;
+    let selected = selected_groups(&groups, options)?;
+
+    let lockfile_path = workspace.current_lockfile_path()?;
+    let lockfile = match lockfile_path.exists() {
+        true => Some(Lockfile::new(&lockfile_path)?),
+        false => None,
+    };
+    let packages = if options.locked {
+        let Some(lockfile) = lockfile.as_ref() else {
+            return Err(Error::LockfileNotFound);
+        };
+        // Licenses are only known for locked packages installed at the same version.
+        let installed = workspace
+            .current_python_environment()
+            .and_then(|it| installed_distributions(&it, config))
+            .unwrap_or_default();
+        locked_sbom_packages(
+            lockfile.packages(),
+            &installed,
+            selected.as_deref(),
+        )
+    } else {
+        let python_env = workspace.resolve_python_environment()?;
+        let distributions = installed_distributions(&python_env, config)?;
+        let graph = DependencyGraph::new(
+            groups.clone(),
+            &distributions,
+            &python_env.marker_environment()?,
+        );
+        installed_sbom_packages(
+            &distributions,
+            &graph,
+            lockfile
+                .as_ref()
+                .map(Lockfile::packages)
+                .unwrap_or_default(),
+            metadata.metadata().project_name(),
+            selected.as_deref(),
+        )
+    };
+
+    let root = SbomPackage {
+        name: metadata.metadata().project_name().to_string(),
+        version: metadata
+            .metadata()
+            .project_version()
+            .map(ToString::to_string),
+        hashes: Vec::new(),
+        license: License::Unknown,
+        source: None,
+        dependencies: groups
+            .iter()
+            .filter(|(group, _)| {
+                selected.as_ref().map_or(true, |it| it.contains(group))
+            })
+            .flat_map(|(_, reqs)| reqs.iter())
+            .map(|it| normalized_package_name(&it.name))
+            .collect(),
+    };
+    let sbom = Sbom {
+        root,
+        packages,
+        timestamp: sbom_timestamp(),
+    };
+    let document = match options.format {
+        ExportFormat::CycloneDxJson => sbom.to_cyclonedx_json(),
+        _ => sbom.to_spdx_json(),
+    };
+
+    let mut file = File::create(path)?;
+    writeln!(file, "{}", serde_json::to_string_pretty(&document)?)?;
+
+    Ok(())
+}
+
+/// Get the dependency groups to export if any are included or excluded.
+fn selected_groups(
+    groups: &IndexMap<String, Vec<Requirement>>,
+    options: &ExportOptions,
+) -> HuakResult<Option<Vec<String>>> {
+    if options.include.is_none() && options.exclude.is_none() {
+        return Ok(None);
+    }
+    let split = |it: &Option<String>| {
+        it.as_deref()
+            .map(|s| s.split(',').map(String::from).collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+    let include = split(&options.include);
+    let exclude = split(&options.exclude);
+    check_group_conflicts(&include, &exclude)?;
+
+    Ok(Some(
+        groups
+            .keys()
+            .filter(|it| include.is_empty() || include.contains(it))
+            .filter(|it| !exclude.contains(it))
+            .cloned()
+            .collect(),
+    ))
+}
+
+/// Return an error if any dependency group is both included and excluded.
+fn check_group_conflicts(
+    include: &[String],
+    exclude: &[String],
+) -> HuakResult<()> {
+    let conflicts = include
+        .iter()
+        .filter(|it| exclude.contains(it))
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::DependencyGroupConflict(conflicts.join(", ")))
+    }
+}
+
+/// Get an `SbomPackage` for each installed distribution other than the project.
+fn installed_sbom_packages(
+    distributions: &[InstalledDistribution],
+    graph: &DependencyGraph,
+    locked: &[LockedPackage],
+    project_name: &str,
+    groups: Option<&[String]>,
+) -> Vec<SbomPackage> {
+    let project_name = normalized_package_name(project_name);
+
+    distributions
+        .iter()
+        .filter(|it| it.normalized_name() != project_name)
+        .filter(|it| {
+            groups.map_or(true, |groups| {
+                graph
+                    .requiring_groups(it.name())
+                    .iter()
+                    .any(|group| groups.iter().any(|it| it == group))
+            })
+        })
+        .map(|dist| {
+            let locked = locked.iter().find(|it| {
+                normalized_package_name(&it.name) == dist.normalized_name()
+                    && &it.version == dist.version()
+            });
+            let (hashes, source) = match (locked, dist.direct_url.as_ref()) {
+                (Some(it), _) => (it.hashes.clone(), it.source.clone()),
+                (None, Some(it)) => (it.hashes(), Some(it.url.clone())),
+                (None, None) => (Vec::new(), None),
+            };
+            SbomPackage {
+                name: dist.name().to_string(),
+                version: Some(dist.version().to_string()),
+                hashes,
+                license: License::from_metadata(&dist.metadata),
+                source,
+                dependencies: graph
+                    .package(dist.name())
+                    .map(|it| {
+                        it.requires
+                            .iter()
+                            .map(|req| normalized_package_name(&req.name))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// Get an `SbomPackage` for each `LockedPackage`.
+fn locked_sbom_packages(
+    locked: &[LockedPackage],
+    installed: &[InstalledDistribution],
+    groups: Option<&[String]>,
+) -> Vec<SbomPackage> {
+    locked
+        .iter()
+        .filter(|it| groups.map_or(true, |groups| it.in_groups(groups)))
+        .map(|package| {
+            let license = installed
+                .iter()
+                .find(|it| {
+                    it.normalized_name()
+                        == normalized_package_name(&package.name)
+                        && it.version() == &package.version
+                })
+                .map_or(License::Unknown, |it| {
+                    License::from_metadata(&it.metadata)
+                });
+            SbomPackage {
+                name: package.name.clone(),
+                version: Some(package.version.to_string()),
+                hashes: package.hashes.clone(),
+                license,
+                source: package.source.clone(),
+                dependencies: package.dependencies.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Write the distributions pinned in the project's lockfile in a format other tools install
+/// from.
+///
+/// The lockfile must be up to date with the project's dependencies. If groups are included
+/// or excluded only the distributions the exported groups require are written.
+fn export_locked(
+    config: &Config,
+    options: &ExportOptions,
+    path: &Path,
+) -> HuakResult<()> {
+    if options.python.is_some() {
+        return Err(python_option_error());
+    }
+    let workspace = config.workspace();
+    let metadata = workspace.current_local_metadata()?;
+    let groups = dependency_groups(metadata.metadata())?;
+    let lockfile_path = workspace.current_lockfile_path()?;
+    if !lockfile_path.exists() {
+        return Err(Error::LockfileNotFound);
+    }
+    let lockfile = Lockfile::new(&lockfile_path)?;
+    if !lockfile.is_fresh(&groups) {
+        return Err(Error::LockfileOutOfDate(format!(
+            "{} has changed since {} was written (run `huak lock`)",
+            metadata.path().display(),
+            lockfile_path.display()
+        )));
+    }
+
+    let selected = selected_groups(&groups, options)?
+        .unwrap_or_else(|| groups.keys().cloned().collect());
+    let groups = groups
+        .into_iter()
+        .filter(|(group, _)| selected.contains(group))
+        .collect::<IndexMap<_, _>>();
+    let packages = lockfile
+        .packages()
+        .iter()
+        .filter(|it| it.in_groups(&selected))
+        .collect::<Vec<_>>();
+    let project = metadata.metadata().project();
+    let requires_python =
+        project.requires_python.as_ref().map(ToString::to_string);
+
+    let contents = match options.format {
+        ExportFormat::PinnedRequirementsTxt => {
+            for it in packages.iter().filter(|it| it.hashes.is_empty()) {
+                config.terminal().print_warning(format!(
+                    "{} has no hashes and can't be installed with --require-hashes",
+                    it.name
+                ))?;
+            }
+            pinned_requirements_txt(&packages, &groups, &project.name)
+        }
+        ExportFormat::ConstraintsTxt => constraints_txt(&packages),
+        ExportFormat::PylockToml => {
+            let extras = metadata
+                .metadata()
+                .optional_dependencies()
+                .map(|it| it.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            let dependency_groups = metadata
+                .metadata()
+                .dependency_groups()
+                .map(|it| it.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            pylock_toml(
+                &packages,
+                &selected,
+                &extras,
+                &dependency_groups,
+                requires_python.as_deref(),
+                path.parent().unwrap_or(workspace.root()),
+            )?
+        }
+        ExportFormat::EnvironmentYml => {
+            // Prefer the version of the project's environment to its requires-python.
+            let python = match workspace
+                .current_python_environment()
+                .and_then(|it| it.marker_environment())
+            {
+                Ok(env) => Some(format!(
+                    "={}",
+                    env.python_version
+                        .1
+                        .release
+                        .iter()
+                        .take(2)
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(".")
+                )),
+                Err(_) => requires_python.map(|it| it.replace(' ', "")),
+            };
+            environment_yml(&packages, &project.name, python.as_deref())
+        }
+        ExportFormat::RequirementsTxt
+        | ExportFormat::CycloneDxJson
+        | ExportFormat::SpdxJson => {
+            return Err(Error::InternalError(format!(
+                "{:?} isn't exported from the lockfile",
+                options.format
+            )))
+        }
+    };
+    std::fs::write(path, contents)?;
+
+    Ok(())
+}
+
+fn python_option_error() -> Error {
+    Error::HuakConfigurationError(
+        "--python can only be used to export requirements-txt".to_string(),
+    )
+}
+
+/// Build a requirements file pinning each `LockedPackage` with its hashes. Every package is
+/// followed by `# via` comments naming the dependency groups and packages that require it.
+///
+/// Packages without hashes are pinned to their source instead.
+fn pinned_requirements_txt(
+    packages: &[&LockedPackage],
+    groups: &IndexMap<String, Vec<Requirement>>,
+    project_name: &str,
+) -> String {
+    let mut out =
+        String::from("# This file was exported from huak.lock by huak.\n");
+    for package in packages {
+        let name = normalized_package_name(&package.name);
+        let mut via = groups
+            .iter()
+            .filter(|(_, reqs)| {
+                reqs.iter()
+                    .any(|it| normalized_package_name(&it.name) == name)
+            })
+            .map(|(group, _)| match group.as_str() {
+                "required" => project_name.to_string(),
+                _ => format!("{project_name} ({group})"),
+            })
+            .collect::<Vec<_>>();
+        via.extend(
+            packages
+                .iter()
+                .filter(|it| it.dependencies.contains(&name))
+                .map(|it| it.name.clone()),
+        );
+
+        match (package.hashes.is_empty(), package.source.as_ref()) {
+            (true, Some(source)) => {
+                out.push_str(&format!("{} @ {source}\n", package.name));
+            }
+            _ => {
+                out.push_str(&format!("{}=={}", package.name, package.version));
+                for hash in package.hashes.iter() {
+                    out.push_str(&format!(" \\\n    --hash={hash}"));
+                }
+                out.push('\n');
+            }
+        }
+        match via.as_slice() {
+            [] => (),
+            [it] => out.push_str(&format!("    # via {it}\n")),
+            _ => {
+                out.push_str("    # via\n");
+                for it in via {
+                    out.push_str(&format!("    #   {it}\n"));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Build a pip constraints file pinning the version of each `LockedPackage`.
+fn constraints_txt(packages: &[&LockedPackage]) -> String {
+    let mut out =
+        String::from("# This file was exported from huak.lock by huak.\n");
+    for package in packages {
+        out.push_str(&format!("{}=={}\n", package.name, package.version));
+    }
+
+    out
+}
+
+/// Build a PEP 751 lock file of the `LockedPackage`s.
+///
+/// Packages only optional groups require get a marker for the extras or dependency groups
+/// that require them. Local paths are written relative to the lock file's directory. Every
+/// package needs a source PEP 751 can describe (with hashes for wheels and archives), so an
+/// error is returned if one doesn't.
+fn pylock_toml(
+    packages: &[&LockedPackage],
+    groups: &[String],
+    extras: &[String],
+    dependency_groups: &[String],
+    requires_python: Option<&str>,
+    dir: &Path,
+) -> HuakResult<String> {
+    let extras = extras
+        .iter()
+        .filter(|it| groups.contains(it))
+        .collect::<Vec<_>>();
+    let dependency_groups = dependency_groups
+        .iter()
+        .filter(|it| groups.contains(it))
+        .collect::<Vec<_>>();
+
+    let mut document = Document::new();
+    document["lock-version"] = value("1.0");
+    if let Some(it) = requires_python {
+        document["requires-python"] = value(it);
+    }
+    document["extras"] =
+        value(Array::from_iter(extras.iter().map(|it| it.as_str())));
+    document["dependency-groups"] = value(Array::from_iter(
+        dependency_groups.iter().map(|it| it.as_str()),
+    ));
+    document["created-by"] = value("huak");
+
+    let mut tables = ArrayOfTables::new();
+    for package in packages {
+        let mut table = Table::new();
+        table["name"] = value(package.name.as_str());
+        table["version"] = value(package.version.to_string());
+        let required = groups.iter().any(|it| it == "required")
+            && package.groups.iter().any(|it| it == "required");
+        let marker = package
+            .groups
+            .iter()
+            .flat_map(|group| {
+                let mut terms = Vec::new();
+                if extras.contains(&group) {
+                    terms.push(format!("'{group}' in extras"));
+                }
+                if dependency_groups.contains(&group) {
+                    terms.push(format!("'{group}' in dependency_groups"));
+                }
+                terms
+            })
+            .collect::<Vec<_>>();
+        if !required && !marker.is_empty() {
+            table["marker"] = value(marker.join(" or "));
+        }
+        table["dependencies"] =
+            value(Array::from_iter(package.dependencies.iter().map(|name| {
+                let mut it = InlineTable::new();
+                it.insert("name", name.as_str().into());
+                it
+            })));
+        let Some((key, source)) = pylock_source(package, dir) else {
+            return Err(Error::Unimplemented(format!(
+                "exporting {} {} to pylock.toml without a directory, vcs, or hashed wheel or archive source",
+                package.name, package.version
+            )));
+        };
+        table[key] = value(source);
+        tables.push(table);
+    }
+    document["packages"] = Item::ArrayOfTables(tables);
+
+    Ok(document.to_string())
+}
+
+/// Get the PEP 751 key and value describing where a `LockedPackage` is installed from.
+///
+/// Sources are locked as a vcs URL (`<vcs>+<url>@<commit>`), a local directory, or the URL
+/// of a wheel, source distribution, or other archive. PEP 751 requires hashes for wheels
+/// and archives, so `None` is returned for one locked without any.
+fn pylock_source(
+    package: &LockedPackage,
+    dir: &Path,
+) -> Option<(&'static str, Value)> {
+    let source = package.source.as_ref()?;
+    if let Some((vcs, rest)) = source
+        .split_once('+')
+        .filter(|(vcs, _)| ["git", "hg", "svn", "bzr"].contains(vcs))
+    {
+        let (url, commit) = rest.rsplit_once('@')?;
+        let mut it = InlineTable::new();
+        it.insert("type", vcs.into());
+        it.insert("url", url.into());
+        it.insert("commit-id", commit.into());
+        return Some(("vcs", it.into()));
+    }
+
+    let url = Url::parse(source).ok()?;
+    let filename = url
+        .path_segments()
+        .and_then(|mut it| it.next_back())
+        .unwrap_or_default()
+        .to_string();
+    let mut it = InlineTable::new();
+    let is_archive = [".whl", ".tar.gz", ".zip"]
+        .iter()
+        .any(|it| filename.ends_with(it));
+    if is_archive {
+        it.insert("name", filename.as_str().into());
+    }
+    match url.scheme() {
+        "file" => {
+            let path = url.to_file_path().ok()?;
+            let path = path.strip_prefix(dir).unwrap_or(&path);
+            let path = path.display().to_string().replace('\\', "/");
+            it.insert("path", path.into());
+            if !is_archive {
+                return Some(("directory", it.into()));
+            }
+        }
+        _ => {
+            it.insert("url", source.as_str().into());
+        }
+    }
+    let mut hashes = InlineTable::new();
+    for (algorithm, digest) in
+        package.hashes.iter().filter_map(|it| it.split_once(':'))
+    {
+        hashes.insert(algorithm, digest.into());
+    }
+    if hashes.is_empty() {
+        return None;
+    }
+    it.insert("hashes", hashes.into());
+
+    if filename.ends_with(".whl") {
+        Some(("wheels", Array::from_iter([it]).into()))
+    } else if is_archive {
+        Some(("sdist", it.into()))
+    } else {
+        Some(("archive", it.into()))
+    }
+}
+
+/// Build a conda environment file installing Python and pip with conda and the
+/// `LockedPackage`s with pip.
+fn environment_yml(
+    packages: &[&LockedPackage],
+    project_name: &str,
+    python: Option<&str>,
+) -> String {
+    let mut out =
+        format!("name: {}\ndependencies:\n", yaml_scalar(project_name));
+    out.push_str(&format!(
+        "  - {}\n  - pip\n  - pip:\n",
+        yaml_scalar(&format!("python{}", python.unwrap_or_default()))
+    ));
+    for package in packages {
+        let requirement =
+            match (package.hashes.is_empty(), package.source.as_ref()) {
+                (true, Some(source)) => format!("{} @ {source}", package.name),
+                _ => format!("{}=={}", package.name, package.version),
+            };
+        out.push_str(&format!("    - {}\n", yaml_scalar(&requirement)));
+    }
+
+    out
+}
+
+/// Quote a YAML scalar if it would be read as something other than a plain string.
+fn yaml_scalar(value: &str) -> String {
+    let plain = !value.is_empty()
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.starts_with(|it: char| "-?:,[]{}#&*!|>'\"%@`".contains(it));
+    match plain {
+        true => value.to_string(),
+        false => {
+            format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+    }
+}
+
+/// Write `Dependency`s to a requirements file. Editable dependencies are written as
+/// `-e <path>`, and a constraints file next to it is referenced with `-c <file>`.
+fn write_requirements_file<T: AsRef<Path>>(
+    path: T,
+    dependencies: &[Dependency],
+    constraints_file: Option<&Path>,
+    table: &HuakTable,
+) -> HuakResult<()> {
+    let mut output_file = match File::create(path) {
         Ok(file) => file,
         Err(e) => return Err(Error::IOError(e)),
     };
 
-    for dependency in processed_dependencies {
-        let line = format!("{}\n", dependency);
+    if let Some(name) = constraints_file.and_then(|it| it.file_name()) {
+        writeln!(output_file, "-c {}", name.to_string_lossy())?;
+    }
+
+    for dependency in dependencies {
+        let line = match dependency.local_path() {
+            Some(it) if table.is_editable(dependency) => {
+                format!("-e {}\n", it.display())
+            }
+            _ => format!("{}\n", dependency),
+        };
         write!(output_file, "{}", line)?;
     }
 
     Ok(())
 }
 
+/// Write the project's constraints to a constraints file.
+fn write_constraints_file(
+    path: &Path,
+    constraints: &[Requirement],
+) -> HuakResult<()> {
+    let mut output_file = File::create(path)?;
+    for it in constraints {
+        writeln!(output_file, "{it}")?;
+    }
+
+    Ok(())
+}
+
+/// Get the path to the constraints file exported with a requirements file by suffixing
+/// its stem (e.g. requirements-constraints.txt).
+fn constraints_file_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|it| it.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut filename = format!("{stem}-constraints");
+    if let Some(extension) = path.extension() {
+        filename.push('.');
+        filename.push_str(&extension.to_string_lossy());
+    }
+
+    path.with_file_name(filename)
+}
+
+/// Get the path to a Python interpreter from a path or a version of an interpreter found
+/// in the environment (e.g. "3.11").
+fn python_path(workspace: &Workspace, python: &str) -> HuakResult<PathBuf> {
+    let path = PathBuf::from(python);
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    workspace
+        .environment()
+        .interpreters()
+        .interpreters()
+        .iter()
+        .filter(|interpreter| {
+            let version = interpreter.version().to_string();
+            version == python || version.starts_with(&format!("{python}."))
+        })
+        .max()
+        .map(|interpreter| interpreter.path().clone())
+        .ok_or(Error::PythonNotFound)
+}
+
+/// Get the path to an interpreter's requirements file by suffixing the output file's stem
+/// with its Python version (e.g. requirements-py311.txt).
+fn interpreter_output_file_path(path: &Path, release: &[usize]) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|it| it.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let suffix = release
+        .iter()
+        .take(2)
+        .map(ToString::to_string)
+        .collect::<String>();
+    let mut filename = format!("{stem}-py{suffix}");
+    if let Some(extension) = path.extension() {
+        filename.push('.');
+        filename.push_str(&extension.to_string_lossy());
+    }
+
+    path.with_file_name(filename)
+}
+
 fn process_dependencies(
     include: &[String],
     exclude: &[String],
     all_dependencies: &IndexMap<String, Vec<Dependency>>,
 ) -> HuakResult<Vec<Dependency>> {
+    check_group_conflicts(include, exclude)?;
+
     // We initialize an empty vector to hold the dependencies that pass the filters.
     let mut processed_dependencies: Vec<Dependency> = Vec::new();
 
@@ -142,6 +1015,9 @@ mod tests {
             include: None,
             exclude: None,
             output_file: "requirements.txt".to_string(),
+            python: None,
+            format: ExportFormat::RequirementsTxt,
+            locked: false,
         };
 
         export_dependencies_to_file(&config, &options).unwrap();
@@ -162,7 +1038,7 @@ mod tests {
         for dep in dependencies.unwrap_or(&[]).iter().map(Dependency::from) {
             all_dependencies
                 .entry("required".to_string())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(dep);
         }
         if let Some(opt_deps) = optional_dependencies {
@@ -198,6 +1074,9 @@ mod tests {
             include: Some("dev".to_string()),
             exclude: Some("dev".to_string()),
             output_file: "requirements.txt".to_string(),
+            python: None,
+            format: ExportFormat::RequirementsTxt,
+            locked: false,
         };
 
         let result = export_dependencies_to_file(&config, &options);
@@ -207,4 +1086,346 @@ mod tests {
             _ => panic!("Expected Error::DependencyGroupConflict"),
         }
     }
+
+    #[test]
+    fn test_export_constraints_and_overrides() {
+        let dir = tempdir().unwrap();
+        fs::copy_dir(
+            test_resources_dir_path().join("mock-project"),
+            dir.path().join("mock-project"),
+        )
+        .unwrap();
+        let root = dir.path().join("mock-project");
+        let mut pyproject =
+            std::fs::read_to_string(root.join("pyproject.toml")).unwrap();
+        pyproject.push_str(
+            "\n[tool.huak]\nconstraints = [\"urllib3 >= 2.2.2\"]\n\
+             overrides = [\"click >= 8\", \"colorama == 0.4.6\"]\n",
+        );
+        std::fs::write(root.join("pyproject.toml"), pyproject).unwrap();
+        let config = test_config(root.clone(), root.clone(), Verbosity::Quiet);
+        let options = ExportOptions {
+            include: Some("required".to_string()),
+            exclude: None,
+            output_file: "requirements.txt".to_string(),
+            python: None,
+            format: ExportFormat::RequirementsTxt,
+            locked: false,
+        };
+
+        export_dependencies_to_file(&config, &options).unwrap();
+
+        let requirements =
+            std::fs::read_to_string(root.join("requirements.txt")).unwrap();
+        let constraints =
+            std::fs::read_to_string(root.join("requirements-constraints.txt"))
+                .unwrap();
+        assert_eq!(
+            requirements,
+            "-c requirements-constraints.txt\nclick >=8\n"
+        );
+        assert_eq!(constraints, "urllib3 >=2.2.2\ncolorama ==0.4.6\n");
+    }
+
+    #[test]
+    fn test_export_dependency_groups() {
+        let dir = tempdir().unwrap();
+        fs::copy_dir(
+            test_resources_dir_path().join("mock-project"),
+            dir.path().join("mock-project"),
+        )
+        .unwrap();
+        let root = dir.path().join("mock-project");
+        let mut pyproject =
+            std::fs::read_to_string(root.join("pyproject.toml")).unwrap();
+        pyproject.push_str(
+            "\n[dependency-groups]\nlint = [\"ruff\"]\n\
+             dev = [{ include-group = \"lint\" }, \"mypy\"]\n",
+        );
+        std::fs::write(root.join("pyproject.toml"), pyproject).unwrap();
+        let config = test_config(root.clone(), root.clone(), Verbosity::Quiet);
+        let options = ExportOptions {
+            include: Some("dev".to_string()),
+            exclude: None,
+            output_file: "requirements.txt".to_string(),
+            python: None,
+            format: ExportFormat::RequirementsTxt,
+            locked: false,
+        };
+
+        export_dependencies_to_file(&config, &options).unwrap();
+
+        let requirements =
+            std::fs::read_to_string(root.join("requirements.txt")).unwrap();
+        assert_eq!(
+            requirements,
+            "pytest >=6\nblack ==22.8.0\nisort ==5.12.0\nruff\nmypy\n"
+        );
+    }
+
+    #[test]
+    fn test_sbom_packages() {
+        let dists = crate::site_packages::installed_distributions(
+            test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+        let mut groups = IndexMap::new();
+        groups.insert(
+            "required".to_string(),
+            vec![Requirement::from_str("mock-web").unwrap()],
+        );
+        groups.insert(
+            "dev".to_string(),
+            vec![Requirement::from_str("mock-legacy").unwrap()],
+        );
+        let graph = DependencyGraph::new(
+            groups,
+            &dists,
+            &crate::test_marker_environment(),
+        );
+        let locked = vec![LockedPackage {
+            name: "mock-utils".to_string(),
+            version: pep440_rs::Version::from_str("1.0.0").unwrap(),
+            source: Some(
+                "https://files.mock.index/mock_utils-1.0.0.whl".to_string(),
+            ),
+            groups: vec!["required".to_string()],
+            hashes: vec!["sha256:abc123".to_string()],
+            dependencies: Vec::new(),
+        }];
+
+        let installed = installed_sbom_packages(
+            &dists,
+            &graph,
+            &locked,
+            "mock-legacy",
+            Some(&["required".to_string()]),
+        );
+        let from_lock = locked_sbom_packages(&locked, &dists, None);
+
+        assert_eq!(
+            installed
+                .iter()
+                .map(|it| (it.name.as_str(), it.dependencies.clone()))
+                .collect::<Vec<_>>(),
+            [
+                ("mock_utils", Vec::new()),
+                ("mock-web", vec!["mock-utils".to_string()])
+            ]
+        );
+        assert_eq!(installed[0].hashes, ["sha256:abc123"]);
+        assert_eq!(from_lock[0].license, License::Text("MIT".to_string()));
+    }
+
+    #[test]
+    fn test_export_locked() {
+        let dir = tempdir().unwrap();
+        fs::copy_dir(
+            test_resources_dir_path().join("mock-project"),
+            dir.path().join("mock-project"),
+        )
+        .unwrap();
+        let root = dir.path().join("mock-project");
+        let config = test_config(root.clone(), root.clone(), Verbosity::Quiet);
+        let mut options = ExportOptions {
+            include: None,
+            exclude: None,
+            output_file: "requirements.txt".to_string(),
+            python: None,
+            format: ExportFormat::PinnedRequirementsTxt,
+            locked: false,
+        };
+
+        let res = export_dependencies_to_file(&config, &options);
+        assert!(matches!(res, Err(Error::LockfileNotFound)));
+
+        std::fs::write(root.join("huak.lock"), "version = 1\n").unwrap();
+        let res = export_dependencies_to_file(&config, &options);
+        assert!(matches!(res, Err(Error::LockfileOutOfDate(_))));
+
+        let metadata = config.workspace().current_local_metadata().unwrap();
+        let hash = crate::lockfile::content_hash(
+            &dependency_groups(metadata.metadata()).unwrap(),
+        );
+        std::fs::write(
+            root.join("huak.lock"),
+            format!(
+                r#"version = 1
+content-hash = "{hash}"
+
+[[package]]
+name = "click"
+version = "8.1.3"
+source = "https://files.mock.index/click-8.1.3-py3-none-any.whl"
+groups = ["required"]
+hashes = ["sha256:abc123"]
+dependencies = ["colorama"]
+
+[[package]]
+name = "colorama"
+version = "0.4.6"
+source = "https://files.mock.index/colorama-0.4.6.tar.gz"
+groups = ["required", "dev"]
+hashes = ["sha256:def456"]
+
+[[package]]
+name = "pytest"
+version = "7.3.1"
+source = "git+https://github.com/pytest-dev/pytest@0123abc"
+groups = ["dev"]
+dependencies = ["colorama"]
+"#
+            ),
+        )
+        .unwrap();
+
+        export_dependencies_to_file(&config, &options).unwrap();
+        let requirements =
+            std::fs::read_to_string(root.join("requirements.txt")).unwrap();
+        assert_eq!(
+            requirements,
+            r#"# This file was exported from huak.lock by huak.
+click==8.1.3 \
+    --hash=sha256:abc123
+    # via mock_project
+colorama==0.4.6 \
+    --hash=sha256:def456
+    # via
+    #   click
+    #   pytest
+pytest @ git+https://github.com/pytest-dev/pytest@0123abc
+    # via mock_project (dev)
+"#
+        );
+
+        options.format = ExportFormat::ConstraintsTxt;
+        options.output_file = "constraints.txt".to_string();
+        options.exclude = Some("dev".to_string());
+        export_dependencies_to_file(&config, &options).unwrap();
+        let constraints =
+            std::fs::read_to_string(root.join("constraints.txt")).unwrap();
+        assert_eq!(
+            constraints.lines().skip(1).collect::<Vec<_>>(),
+            ["click==8.1.3", "colorama==0.4.6"]
+        );
+
+        options.include = Some("required,dev".to_string());
+        assert!(matches!(
+            export_dependencies_to_file(&config, &options),
+            Err(Error::DependencyGroupConflict(it)) if it == "dev"
+        ));
+
+        options.include = None;
+        options.python = Some(vec!["3.11".to_string()]);
+        assert!(export_dependencies_to_file(&config, &options).is_err());
+    }
+
+    #[test]
+    fn test_locked_formats() {
+        let package = |name: &str, version: &str, source: &str| LockedPackage {
+            name: name.to_string(),
+            version: pep440_rs::Version::from_str(version).unwrap(),
+            source: Some(source.to_string()),
+            groups: vec!["required".to_string()],
+            hashes: vec!["sha256:abc123".to_string()],
+            dependencies: Vec::new(),
+        };
+        let mut click = package(
+            "click",
+            "8.1.3",
+            "https://files.mock.index/click-8.1.3-py3-none-any.whl",
+        );
+        click.dependencies.push("mock-local".to_string());
+        let mut local =
+            package("mock-local", "0.1.0", "file:///project/mock-local");
+        local.hashes.clear();
+        let mut pytest = package(
+            "pytest",
+            "7.3.1",
+            "git+https://github.com/pytest-dev/pytest@0123abc",
+        );
+        pytest.groups = vec!["dev".to_string(), "test".to_string()];
+        pytest.hashes.clear();
+        let packages = [&click, &local, &pytest];
+
+        let pylock = pylock_toml(
+            &packages,
+            &[
+                "required".to_string(),
+                "dev".to_string(),
+                "test".to_string(),
+            ],
+            &["test".to_string()],
+            &["dev".to_string()],
+            Some(">=3.8"),
+            Path::new("/project"),
+        )
+        .unwrap();
+        let environment =
+            environment_yml(&packages, "mock-project", Some("=3.11"));
+
+        assert_eq!(
+            pylock,
+            r#"lock-version = "1.0"
+requires-python = ">=3.8"
+extras = ["test"]
+dependency-groups = ["dev"]
+created-by = "huak"
+
+[[packages]]
+name = "click"
+version = "8.1.3"
+dependencies = [{ name = "mock-local" }]
+wheels = [{ name = "click-8.1.3-py3-none-any.whl", url = "https://files.mock.index/click-8.1.3-py3-none-any.whl", hashes = { sha256 = "abc123" } }]
+
+[[packages]]
+name = "mock-local"
+version = "0.1.0"
+dependencies = []
+directory = { path = "mock-local" }
+
+[[packages]]
+name = "pytest"
+version = "7.3.1"
+marker = "'dev' in dependency_groups or 'test' in extras"
+dependencies = []
+vcs = { type = "git", url = "https://github.com/pytest-dev/pytest", commit-id = "0123abc" }
+"#
+        );
+        assert_eq!(
+            environment,
+            r#"name: mock-project
+dependencies:
+  - python=3.11
+  - pip
+  - pip:
+    - click==8.1.3
+    - mock-local @ file:///project/mock-local
+    - pytest @ git+https://github.com/pytest-dev/pytest@0123abc
+"#
+        );
+        assert_eq!(yaml_scalar("name: value"), "\"name: value\"");
+
+        // A package without a source, or a wheel without hashes, can't be locked.
+        click.hashes.clear();
+        assert!(matches!(
+            pylock_toml(&[&click], &[], &[], &[], None, Path::new("/project")),
+            Err(Error::Unimplemented(_))
+        ));
+        click.source = None;
+        assert!(matches!(
+            pylock_toml(&[&click], &[], &[], &[], None, Path::new("/project")),
+            Err(Error::Unimplemented(_))
+        ));
+    }
+
+    #[test]
+    fn test_interpreter_output_file_path() {
+        let path = interpreter_output_file_path(
+            Path::new("/tmp/requirements.txt"),
+            &[3, 11],
+        );
+
+        assert_eq!(path, PathBuf::from("/tmp/requirements-py311.txt"));
+    }
 }
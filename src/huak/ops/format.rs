@@ -1,4 +1,4 @@
-use super::make_venv_command;
+use super::{make_venv_command, pin_strategy, pinned_dependency};
 use crate::{dependency::Dependency, Config, HuakResult, InstallOptions};
 use std::{process::Command, str::FromStr};
 
@@ -51,15 +51,15 @@ pub fn format_project(
         .collect::<Vec<_>>();
 
     if !new_format_deps.is_empty() {
+        let strategy = pin_strategy(None, metadata.metadata())?;
         for pkg in python_env
             .installed_packages()?
             .iter()
             .filter(|pkg| new_format_deps.contains(&pkg.name()))
         {
-            metadata.metadata_mut().add_optional_dependency(
-                Dependency::from_str(&pkg.to_string())?,
-                "dev",
-            );
+            metadata
+                .metadata_mut()
+                .add_tool_dependency(pinned_dependency(pkg, strategy)?)?;
         }
     }
 
@@ -122,7 +122,7 @@ def fn( ):
         std::fs::write(&fmt_filepath, pre_fmt_str).unwrap();
         let options = FormatOptions {
             values: None,
-            install_options: InstallOptions { values: None },
+            install_options: InstallOptions::default(),
         };
 
         format_project(&config, &options).unwrap();
@@ -1,4 +1,17 @@
-use crate::{dependency::Dependency, Config, HuakResult, InstallOptions};
+use super::{
+    dependencies_for_environment, install_dependencies,
+    lock::dependency_groups, report_constraints,
+};
+use crate::{
+    dependency::Dependency, lockfile::Lockfile, Config, Error, HuakResult,
+    InstallOptions,
+};
+
+pub struct InstallLockedOptions {
+    /// Install from the lockfile without checking it against the metadata file.
+    pub frozen: bool,
+    pub install_options: InstallOptions,
+}
 
 pub fn install_project_dependencies(
     groups: Option<&Vec<String>>,
@@ -25,7 +38,7 @@ pub fn install_project_dependencies(
                 dependencies.extend(reqs.iter().map(Dependency::from));
             }
         } else {
-            gs.iter().for_each(|g| {
+            for g in gs {
                 package
                     .metadata()
                     .optional_dependency_group(g)
@@ -34,11 +47,20 @@ pub fn install_project_dependencies(
                     .for_each(|req| {
                         dependencies.push(Dependency::from(req));
                     });
-            })
+                if package.metadata().has_dependency_group(g) {
+                    dependencies.extend(
+                        package
+                            .metadata()
+                            .dependency_group(g)?
+                            .iter()
+                            .map(Dependency::from),
+                    );
+                }
+            }
         }
     } else {
         // If no groups are passed then install all dependencies listed in the metadata file
-        // including the optional dependencies.
+        // including the optional dependencies and dependency groups.
         if let Some(reqs) = package.metadata().dependencies() {
             dependencies.extend(reqs.iter().map(Dependency::from));
         }
@@ -49,6 +71,17 @@ pub fn install_project_dependencies(
                 )
             });
         }
+        if let Some(deps) = metadata.metadata().dependency_groups() {
+            for g in deps.keys() {
+                dependencies.extend(
+                    metadata
+                        .metadata()
+                        .dependency_group(g)?
+                        .iter()
+                        .map(Dependency::from),
+                );
+            }
+        }
     }
 
     dependencies.dedup();
@@ -58,7 +91,71 @@ pub fn install_project_dependencies(
     }
 
     let python_env = workspace.resolve_python_environment()?;
-    python_env.install_packages(&dependencies, options, config)
+    let dependencies = dependencies_for_environment(
+        dependencies,
+        &python_env.marker_environment()?,
+        config,
+    )?;
+
+    if dependencies.is_empty() {
+        return Ok(());
+    }
+
+    install_dependencies(
+        &python_env,
+        &dependencies,
+        metadata.metadata(),
+        options,
+        config,
+    )?;
+
+    report_constraints(&python_env, metadata.metadata(), options, config)
+}
+
+pub fn install_locked_project_dependencies(
+    groups: Option<&Vec<String>>,
+    config: &Config,
+    options: &InstallLockedOptions,
+) -> HuakResult<()> {
+    let workspace = config.workspace();
+    let metadata = workspace.current_local_metadata()?;
+    let lockfile_path = workspace.current_lockfile_path()?;
+
+    if !lockfile_path.exists() {
+        return Err(Error::LockfileNotFound);
+    }
+
+    let lockfile = Lockfile::new(&lockfile_path)?;
+
+    // Unless the install is frozen the lockfile must have been resolved for the current
+    // dependencies listed in the metadata file.
+    if !options.frozen
+        && !lockfile.is_fresh(&dependency_groups(metadata.metadata())?)
+    {
+        return Err(Error::LockfileOutOfDate(format!(
+            "{} has changed since {} was written (run `huak lock`)",
+            metadata.path().display(),
+            lockfile_path.display()
+        )));
+    }
+
+    // If no groups are passed then install every locked package.
+    let packages = lockfile
+        .packages()
+        .iter()
+        .filter(|pkg| groups.map_or(true, |gs| pkg.in_groups(gs)))
+        .collect::<Vec<_>>();
+
+    if packages.is_empty() {
+        return Ok(());
+    }
+
+    let python_env = workspace.resolve_python_environment()?;
+    python_env.install_locked_packages(
+        &packages,
+        &options.install_options,
+        config,
+    )
 }
 
 #[cfg(test)]
@@ -86,7 +183,7 @@ mod tests {
         let config = test_config(&root, &cwd, Verbosity::Quiet);
         let ws = config.workspace();
         test_venv(&ws);
-        let options = InstallOptions { values: None };
+        let options = InstallOptions::default();
         let venv = ws.resolve_python_environment().unwrap();
         let test_package = Package::from_str("click==8.1.3").unwrap();
         let had_package = venv.contains_package(&test_package);
@@ -110,7 +207,7 @@ mod tests {
         let config = test_config(&root, &cwd, Verbosity::Quiet);
         let ws = config.workspace();
         test_venv(&ws);
-        let options = InstallOptions { values: None };
+        let options = InstallOptions::default();
         let venv = ws.resolve_python_environment().unwrap();
         let had_package = venv.contains_module("pytest").unwrap();
 
@@ -124,4 +221,28 @@ mod tests {
         assert!(!had_package);
         assert!(venv.contains_module("pytest").unwrap());
     }
+
+    #[test]
+    fn test_install_locked_project_dependencies_out_of_date() {
+        let dir = tempdir().unwrap();
+        fs::copy_dir(
+            &test_resources_dir_path().join("mock-project"),
+            &dir.path().join("mock-project"),
+        )
+        .unwrap();
+        let root = dir.path().join("mock-project");
+        let cwd = root.to_path_buf();
+        let config = test_config(&root, &cwd, Verbosity::Quiet);
+        let options = InstallLockedOptions {
+            frozen: false,
+            install_options: InstallOptions::default(),
+        };
+
+        let res = install_locked_project_dependencies(None, &config, &options);
+        assert!(matches!(res, Err(Error::LockfileNotFound)));
+
+        std::fs::write(root.join("huak.lock"), "version = 1\n").unwrap();
+        let res = install_locked_project_dependencies(None, &config, &options);
+        assert!(matches!(res, Err(Error::LockfileOutOfDate(_))));
+    }
 }
@@ -0,0 +1,286 @@
+use super::{installed_distributions, lock::dependency_groups};
+use crate::{
+    dependency_graph::DependencyGraph,
+    licenses::{License, LicensePolicy},
+    package::normalized_package_name,
+    site_packages::InstalledDistribution,
+    Config, Error, HuakResult,
+};
+use serde::Serialize;
+use std::{fmt::Write, str::FromStr};
+use termcolor::Color;
+
+pub struct LicensesOptions {
+    pub format: LicensesFormat,
+    /// Check the licenses against the `[tool.huak.licenses]` policy.
+    pub check: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The output format of `huak licenses`.
+pub enum LicensesFormat {
+    #[default]
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for LicensesFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(LicensesFormat::Table),
+            "csv" => Ok(LicensesFormat::Csv),
+            "json" => Ok(LicensesFormat::Json),
+            _ => Err(Error::HuakConfigurationError(format!(
+                "invalid licenses format {s} (expected one of table, csv, json)"
+            ))),
+        }
+    }
+}
+
+/// Display the license of each distribution installed to the project's environment.
+///
+/// When checking, packages the `[tool.huak.licenses]` policy rejects are reported after
+/// the licenses and an error is returned.
+pub fn display_licenses(
+    config: &Config,
+    options: &LicensesOptions,
+) -> HuakResult<()> {
+    let workspace = config.workspace();
+    let metadata = workspace.current_local_metadata()?;
+    let python_env = workspace.resolve_python_environment()?;
+    let distributions = installed_distributions(&python_env, config)?;
+    let graph = DependencyGraph::new(
+        dependency_groups(metadata.metadata())?,
+        &distributions,
+        &python_env.marker_environment()?,
+    );
+    let policy = match options.check {
+        true => Some(
+            metadata
+                .metadata()
+                .huak_table()?
+                .licenses
+                .unwrap_or_default(),
+        ),
+        false => None,
+    };
+
+    let packages = licensed_packages(
+        &distributions,
+        &graph,
+        metadata.metadata().project_name(),
+        policy.as_ref(),
+    )?;
+
+    let mut terminal = config.terminal();
+    let output = match options.format {
+        LicensesFormat::Table => table(&packages),
+        LicensesFormat::Csv => csv(&packages),
+        LicensesFormat::Json => serde_json::to_string_pretty(&packages)?,
+    };
+    terminal.print_stdout(output.trim_end())?;
+
+    let Some(policy) = policy else {
+        return Ok(());
+    };
+    if policy == LicensePolicy::default() {
+        return terminal.print_warning(
+            "no licenses are allowed or denied in [tool.huak.licenses]",
+        );
+    }
+    let rejected = packages
+        .iter()
+        .filter_map(|it| Some((it, it.rejection.as_ref()?)))
+        .collect::<Vec<_>>();
+    if rejected.is_empty() {
+        return terminal.print_custom(
+            "checked",
+            format!("{} packages against the license policy", packages.len()),
+            Color::Green,
+            false,
+        );
+    }
+    for (package, reason) in rejected.iter() {
+        terminal.print_custom(
+            "rejected",
+            format!("{} {} ({reason})", package.name, package.version),
+            Color::Red,
+            false,
+        )?;
+    }
+
+    Err(Error::LicensesRejected(
+        rejected
+            .iter()
+            .map(|(it, _)| it.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+    ))
+}
+
+/// An installed distribution and the license it declares.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct LicensedPackage {
+    name: String,
+    version: String,
+    /// The license the package is distributed under.
+    license: String,
+    /// The metadata field the license was read from.
+    source: String,
+    /// The dependency groups that require the package.
+    groups: Vec<String>,
+    /// Why the license policy rejects the package's license.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rejection: Option<String>,
+}
+
+/// Get the license of each installed distribution other than the project itself. Licenses
+/// are checked if a `LicensePolicy` is given.
+fn licensed_packages(
+    distributions: &[InstalledDistribution],
+    graph: &DependencyGraph,
+    project_name: &str,
+    policy: Option<&LicensePolicy>,
+) -> HuakResult<Vec<LicensedPackage>> {
+    let project_name = normalized_package_name(project_name);
+    let mut packages = Vec::new();
+
+    for dist in distributions
+        .iter()
+        .filter(|it| it.normalized_name() != project_name)
+    {
+        let license = License::from_metadata(&dist.metadata);
+        let rejection = match policy {
+            Some(it) => it.rejection(&license)?,
+            None => None,
+        };
+        packages.push(LicensedPackage {
+            name: dist.name().to_string(),
+            version: dist.version().to_string(),
+            license: license.to_string(),
+            source: license.source().to_string(),
+            groups: graph
+                .requiring_groups(dist.name())
+                .into_iter()
+                .map(ToString::to_string)
+                .collect(),
+            rejection,
+        });
+    }
+
+    Ok(packages)
+}
+
+fn table(packages: &[LicensedPackage]) -> String {
+    let header = ["Package", "Version", "License", "Source", "Groups"];
+    let rows = packages
+        .iter()
+        .map(|it| {
+            [
+                it.name.clone(),
+                it.version.clone(),
+                it.license.clone(),
+                it.source.clone(),
+                match it.groups.is_empty() {
+                    true => "-".to_string(),
+                    false => it.groups.join(", "),
+                },
+            ]
+        })
+        .collect::<Vec<_>>();
+    let mut widths = header.map(str::len);
+    for row in rows.iter() {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let header = header.map(ToString::to_string);
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line.trim_end()).ok();
+    }
+
+    out
+}
+
+fn csv(packages: &[LicensedPackage]) -> String {
+    let field = |value: &str| match value.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    };
+    let mut out = String::from("name,version,license,source,groups\n");
+    for it in packages {
+        let row = [
+            it.name.as_str(),
+            it.version.as_str(),
+            it.license.as_str(),
+            it.source.as_str(),
+            &it.groups.join(" "),
+        ]
+        .map(field);
+        writeln!(out, "{}", row.join(",")).ok();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        site_packages::installed_distributions, test_marker_environment,
+        test_resources_dir_path,
+    };
+    use indexmap::IndexMap;
+    use pep508_rs::Requirement;
+
+    #[test]
+    fn test_licensed_packages() {
+        let dists = installed_distributions(
+            test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+        let mut groups = IndexMap::new();
+        groups.insert(
+            "required".to_string(),
+            vec![Requirement::from_str("mock-web").unwrap()],
+        );
+        let graph =
+            DependencyGraph::new(groups, &dists, &test_marker_environment());
+        let policy = LicensePolicy {
+            allow: Vec::new(),
+            deny: vec!["GPL*".to_string()],
+        };
+
+        let packages =
+            licensed_packages(&dists, &graph, "mock-legacy", Some(&policy))
+                .unwrap();
+
+        assert_eq!(
+            table(&packages),
+            r#"Package     Version  License       Source      Groups
+mock_utils  1.0.0    MIT           License     required
+mock-web    2.0      GPL-3.0-only  Classifier  required
+"#
+        );
+        assert_eq!(
+            csv(&packages).lines().nth(2),
+            Some("mock-web,2.0,GPL-3.0-only,Classifier,required")
+        );
+        assert_eq!(
+            packages[1].rejection.as_deref(),
+            Some("GPL-3.0-only is denied")
+        );
+        assert!(packages[0].rejection.is_none());
+    }
+}
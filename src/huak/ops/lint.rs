@@ -1,4 +1,4 @@
-use super::make_venv_command;
+use super::{make_venv_command, pin_strategy, pinned_dependency};
 use crate::{dependency::Dependency, Config, HuakResult, InstallOptions};
 use std::{process::Command, str::FromStr};
 
@@ -80,15 +80,15 @@ pub fn lint_project(config: &Config, options: &LintOptions) -> HuakResult<()> {
         .collect::<Vec<_>>();
 
     if !new_lint_deps.is_empty() {
+        let strategy = pin_strategy(None, metadata.metadata())?;
         for pkg in python_env
             .installed_packages()?
             .iter()
             .filter(|pkg| new_lint_deps.contains(&pkg.name()))
         {
-            metadata.metadata_mut().add_optional_dependency(
-                Dependency::from_str(&pkg.to_string())?,
-                "dev",
-            );
+            metadata
+                .metadata_mut()
+                .add_tool_dependency(pinned_dependency(pkg, strategy)?)?;
         }
     }
 
@@ -120,7 +120,7 @@ mod tests {
         let options = LintOptions {
             values: None,
             include_types: true,
-            install_options: InstallOptions { values: None },
+            install_options: InstallOptions::default(),
         };
 
         lint_project(&config, &options).unwrap();
@@ -142,7 +142,7 @@ mod tests {
         let options = LintOptions {
             values: Some(vec![String::from("--fix")]),
             include_types: true,
-            install_options: InstallOptions { values: None },
+            install_options: InstallOptions::default(),
         };
         let lint_fix_filepath =
             ws.root().join("src").join("mock_project").join("fix_me.py");
@@ -0,0 +1,194 @@
+use super::{install_dependencies, installed_distributions};
+use crate::{
+    dependency::Dependency, dependency_graph::DependencyGraph,
+    lockfile::Lockfile, metadata::Metadata, package::normalized_package_name,
+    site_packages::InstalledDistribution, workspace::Workspace, Config,
+    HuakResult, InstallOptions, PythonEnvironment,
+};
+use indexmap::IndexMap;
+use pep508_rs::{Requirement, VersionOrUrl};
+
+pub struct LockOptions {
+    pub install_options: InstallOptions,
+}
+
+pub fn lock_project(config: &Config, options: &LockOptions) -> HuakResult<()> {
+    let workspace = config.workspace();
+    let metadata = workspace.current_local_metadata()?;
+    let python_env = workspace.resolve_python_environment()?;
+
+    // The lock is resolved from the environment, so install any declared dependencies missing from it.
+    let installed = installed_distributions(&python_env, config)?
+        .iter()
+        .map(InstalledDistribution::normalized_name)
+        .collect::<Vec<_>>();
+    let mut missing = Vec::new();
+    for reqs in dependency_groups(metadata.metadata())?.values() {
+        for req in reqs {
+            let dep = Dependency::from(req);
+            if !installed.contains(&normalized_package_name(dep.name()))
+                && !missing.contains(&dep)
+            {
+                missing.push(dep);
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        install_dependencies(
+            &python_env,
+            &missing,
+            metadata.metadata(),
+            &options.install_options,
+            config,
+        )?;
+    }
+
+    write_project_lockfile(
+        &workspace,
+        metadata.metadata(),
+        &python_env,
+        &options.install_options,
+        config,
+    )
+}
+
+/// Resolve the `Lockfile` for the project's `Metadata` from the packages installed
+/// to the `PythonEnvironment` and write it to the `Workspace`'s lockfile path.
+fn write_project_lockfile(
+    workspace: &Workspace,
+    metadata: &Metadata,
+    python_env: &PythonEnvironment,
+    options: &InstallOptions,
+    config: &Config,
+) -> HuakResult<()> {
+    let groups = dependency_groups(metadata)?;
+    let distributions = installed_distributions(python_env, config)?;
+    let graph = DependencyGraph::new(
+        groups.clone(),
+        &distributions,
+        &python_env.marker_environment()?,
+    );
+    let packages = lock_requirements(&distributions, &graph);
+    let report = python_env.installation_report(&packages, options, config)?;
+    let lockfile = Lockfile::from_installation_report(&report, &groups)?;
+
+    lockfile.write_file(workspace.current_lockfile_path()?)
+}
+
+/// Update the project's lockfile after its dependencies were changed. The metadata file is
+/// already written by then, so a lockfile that can't be resolved is only a warning.
+pub(super) fn update_project_lockfile(
+    workspace: &Workspace,
+    metadata: &Metadata,
+    python_env: &PythonEnvironment,
+    options: &InstallOptions,
+    config: &Config,
+) -> HuakResult<()> {
+    match write_project_lockfile(
+        workspace, metadata, python_env, options, config,
+    ) {
+        Ok(()) => Ok(()),
+        Err(e) => config.terminal().print_warning(format!(
+            "the lockfile couldn't be updated ({e}), run `huak lock` once it can be"
+        )),
+    }
+}
+
+/// Get a requirement pinning each installed distribution the dependency groups require.
+///
+/// Direct references are resolved from their URL instead of the package index: the URL
+/// the project declares, or else the one the distribution was installed from (see
+/// https://peps.python.org/pep-0610/).
+fn lock_requirements(
+    distributions: &[InstalledDistribution],
+    graph: &DependencyGraph,
+) -> Vec<String> {
+    let urls = graph
+        .groups()
+        .values()
+        .flatten()
+        .filter(|req| matches!(req.version_or_url, Some(VersionOrUrl::Url(_))))
+        .map(|req| (normalized_package_name(&req.name), req))
+        .collect::<IndexMap<_, _>>();
+
+    distributions
+        .iter()
+        .filter(|it| !graph.requiring_groups(it.name()).is_empty())
+        .map(|dist| {
+            match (urls.get(&dist.normalized_name()), dist.direct_url.as_ref())
+            {
+                (Some(req), _) => Dependency::from(*req).to_string(),
+                (None, Some(url)) => {
+                    format!("{} @ {}", dist.name(), url.requirement_url())
+                }
+                (None, None) => format!("{}=={}", dist.name(), dist.version()),
+            }
+        })
+        .collect()
+}
+
+/// Get the project's requirements by dependency group, with the required dependencies
+/// grouped as "required". Optional dependency groups and `[dependency-groups]` with the
+/// same name are merged.
+pub(super) fn dependency_groups(
+    metadata: &Metadata,
+) -> HuakResult<IndexMap<String, Vec<Requirement>>> {
+    let mut groups = IndexMap::new();
+    groups.insert(
+        "required".to_string(),
+        metadata.dependencies().unwrap_or_default().to_vec(),
+    );
+    if let Some(deps) = metadata.optional_dependencies() {
+        for (group, reqs) in deps {
+            groups
+                .entry(group.to_string())
+                .or_default()
+                .extend(reqs.iter().cloned());
+        }
+    }
+    if let Some(deps) = metadata.dependency_groups() {
+        for group in deps.keys() {
+            let reqs: &mut Vec<Requirement> =
+                groups.entry(group.to_string()).or_default();
+            for req in metadata.dependency_group(group)? {
+                if !reqs.contains(&req) {
+                    reqs.push(req);
+                }
+            }
+        }
+    }
+
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        site_packages::installed_distributions, test_marker_environment,
+        test_resources_dir_path,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn test_lock_requirements() {
+        let dists = installed_distributions(
+            test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+        let mut groups = IndexMap::new();
+        groups.insert(
+            "required".to_string(),
+            vec![Requirement::from_str("mock-web").unwrap()],
+        );
+        let graph =
+            DependencyGraph::new(groups, &dists, &test_marker_environment());
+
+        // mock-legacy isn't required, and mock-web was installed from a directory.
+        assert_eq!(
+            lock_requirements(&dists, &graph),
+            ["mock_utils==1.0.0", "mock-web @ file:///path/to/mock-web"]
+        );
+    }
+}
@@ -1,20 +1,30 @@
 mod activate;
 mod add;
+mod audit;
+mod auth;
 mod build;
+mod cache;
 mod clean;
 mod export;
 mod format;
 mod init;
 mod install;
+mod licenses;
 mod lint;
+mod lock;
 mod new;
+mod outdated;
 mod publish;
 mod python;
 mod remove;
 mod run;
+mod sync;
 mod test;
+mod tree;
 mod update;
+mod vendor;
 mod version;
+mod why;
 
 #[allow(unused_imports)]
 use crate::{
@@ -23,29 +33,56 @@ use crate::{
     workspace::Workspace,
 };
 use crate::{
-    environment::env_path_values, git, python_environment::PythonEnvironment,
-    Error, HuakResult,
+    constraints::{constraint_effects, ConstraintEffect},
+    dependency::{Dependency, PinStrategy},
+    dependency_graph::DependencyGraph,
+    environment::env_path_values,
+    git,
+    installer::{parse_requirements, NativeInstallOptions},
+    metadata::Metadata,
+    package::Package,
+    python_environment::{marker_environment_description, PythonEnvironment},
+    site_packages::InstalledDistribution,
+    Error, HuakResult, InstallOptions,
 };
 pub use activate::activate_python_environment;
 pub use add::{
-    add_project_dependencies, add_project_optional_dependencies, AddOptions,
+    add_project_dependencies, add_project_group_dependencies,
+    add_project_optional_dependencies, AddOptions,
 };
+pub use audit::{audit_project, AuditFormat, AuditOptions};
+pub use auth::{login_to_index, logout_of_index, LoginOptions};
 pub use build::{build_project, BuildOptions};
+pub use cache::{
+    clean_cache, display_cache_dir, display_cache_info, prune_cache,
+};
 pub use clean::{clean_project, CleanOptions};
-pub use export::{export_dependencies_to_file, ExportOptions};
+pub use export::{export_dependencies_to_file, ExportFormat, ExportOptions};
 pub use format::{format_project, FormatOptions};
 pub use init::{init_app_project, init_lib_project};
-pub use install::install_project_dependencies;
+pub use install::{
+    install_locked_project_dependencies, install_project_dependencies,
+    InstallLockedOptions,
+};
+pub use licenses::{display_licenses, LicensesFormat, LicensesOptions};
 pub use lint::{lint_project, LintOptions};
+pub use lock::{lock_project, LockOptions};
 pub use new::{new_app_project, new_lib_project};
+pub use outdated::{display_outdated_dependencies, OutdatedOptions};
+use pep508_rs::MarkerEnvironment;
 pub use publish::{publish_project, PublishOptions};
 pub use python::{list_python, use_python};
 pub use remove::{remove_project_dependencies, RemoveOptions};
 pub use run::run_command_str;
-use std::{path::Path, process::Command};
+use std::{path::Path, process::Command, str::FromStr};
+pub use sync::{sync_project, SyncOptions};
+use termcolor::Color;
 pub use test::{test_project, TestOptions};
+pub use tree::{display_project_tree, TreeFormat, TreeOptions};
 pub use update::{update_project_dependencies, UpdateOptions};
+pub use vendor::{vendor_dependencies, VendorOptions};
 pub use version::display_project_version;
+pub use why::display_dependency_chains;
 
 const DEFAULT_PYTHON_INIT_FILE_CONTENTS: &str = r#"__version__ = "0.0.1"
 "#;
@@ -67,7 +104,7 @@ fn make_venv_command(
     cmd: &mut Command,
     venv: &PythonEnvironment,
 ) -> HuakResult<()> {
-    let mut paths = env_path_values().unwrap_or(Vec::new());
+    let mut paths = env_path_values().unwrap_or_default();
 
     paths.insert(0, venv.executables_dir_path().clone());
     cmd.env(
@@ -80,6 +117,174 @@ fn make_venv_command(
     Ok(())
 }
 
+/// Install `Dependency`s to a `PythonEnvironment`. Local project directories listed in the
+/// `[tool.huak]` table's editable array are installed in editable mode.
+fn install_dependencies(
+    python_env: &PythonEnvironment,
+    dependencies: &[Dependency],
+    metadata: &Metadata,
+    options: &InstallOptions,
+    config: &Config,
+) -> HuakResult<()> {
+    let table = metadata.huak_table()?;
+    let (editable, dependencies): (Vec<&Dependency>, Vec<&Dependency>) =
+        dependencies.iter().partition(|dep| table.is_editable(dep));
+
+    if !dependencies.is_empty() {
+        python_env.install_packages(&dependencies, options, config)?;
+    }
+
+    if !editable.is_empty() {
+        let paths = editable
+            .iter()
+            .filter_map(|dep| dep.local_path())
+            .collect::<Vec<_>>();
+        python_env.install_editable_packages(&paths, options, config)?;
+    }
+
+    Ok(())
+}
+
+/// Report the packages installed to a `PythonEnvironment` that the project's constraints or
+/// overrides changed. Constraints are checked against the package indexes when they can be
+/// read, and reporting never fails an install: if the effects can't be found a warning is
+/// printed instead.
+fn report_constraints(
+    python_env: &PythonEnvironment,
+    metadata: &Metadata,
+    options: &InstallOptions,
+    config: &Config,
+) -> HuakResult<()> {
+    let mut terminal = config.terminal();
+    let effects = match installed_constraint_effects(
+        python_env, metadata, options, config,
+    ) {
+        Ok(it) => it,
+        Err(e) => {
+            return terminal.print_warning(format!(
+                "the effects of the constraints couldn't be reported ({e})"
+            ))
+        }
+    };
+    for effect in effects {
+        terminal.print_custom(effect.title(), &effect, Color::Yellow, false)?;
+    }
+
+    Ok(())
+}
+
+/// Get the `ConstraintEffect`s of the project's constraints and overrides on the packages
+/// installed to a `PythonEnvironment`.
+fn installed_constraint_effects(
+    python_env: &PythonEnvironment,
+    metadata: &Metadata,
+    options: &InstallOptions,
+    config: &Config,
+) -> HuakResult<Vec<ConstraintEffect>> {
+    let options = config.install_options(options)?;
+    if options.constraints.is_empty() && options.overrides.is_empty() {
+        return Ok(Vec::new());
+    }
+    let constraints = parse_requirements(&options.constraints)?;
+    let overrides = parse_requirements(&options.overrides)?;
+    let env = python_env.marker_environment()?;
+    let graph = DependencyGraph::new(
+        lock::dependency_groups(metadata)?,
+        &installed_distributions(python_env, config)?,
+        &env,
+    );
+    let cache = crate::cache::Cache::from_env().ok();
+    let index = match constraints.is_empty() {
+        true => None,
+        false => NativeInstallOptions::new(&options)
+            .and_then(|it| it.package_index(cache.as_ref()))
+            .ok(),
+    };
+
+    Ok(constraint_effects(
+        &graph,
+        &constraints,
+        &overrides,
+        &env,
+        index.as_deref(),
+        options.pre,
+    ))
+}
+
+/// Get the distributions installed to a `PythonEnvironment`. Distributions that can't be read
+/// and requirements that can't be parsed are skipped with a warning.
+fn installed_distributions(
+    python_env: &PythonEnvironment,
+    config: &Config,
+) -> HuakResult<Vec<InstalledDistribution>> {
+    let site_packages = python_env.site_packages()?;
+    let mut terminal = config.terminal();
+    for it in site_packages.warnings() {
+        terminal.print_warning(it)?;
+    }
+
+    Ok(site_packages.distributions)
+}
+
+/// Get the `PinStrategy` to use for dependencies added without version data. An explicit
+/// strategy takes precedence over the project's `[tool.huak]` pin.
+fn pin_strategy(
+    strategy: Option<PinStrategy>,
+    metadata: &Metadata,
+) -> HuakResult<PinStrategy> {
+    match strategy {
+        Some(it) => Ok(it),
+        None => Ok(metadata.huak_table()?.pin.unwrap_or_default()),
+    }
+}
+
+/// Create a `Dependency` for an installed `Package` pinned with a `PinStrategy`.
+fn pinned_dependency(
+    package: &Package,
+    strategy: PinStrategy,
+) -> HuakResult<Dependency> {
+    let mut dependency = Dependency::from_str(package.name())?;
+    dependency.pin(package.version(), strategy)?;
+
+    Ok(dependency)
+}
+
+/// Filter `Dependency`s to the ones whose environment markers apply to a `MarkerEnvironment`.
+/// Each `Dependency` skipped is reported with the marker that excluded it.
+fn dependencies_for_environment(
+    dependencies: Vec<Dependency>,
+    env: &MarkerEnvironment,
+    config: &Config,
+) -> HuakResult<Vec<Dependency>> {
+    let mut terminal = config.terminal();
+    let mut applicable = Vec::new();
+    for dependency in dependencies {
+        if dependency.requirement().evaluate_markers(env, Vec::new()) {
+            applicable.push(dependency);
+            continue;
+        }
+
+        let marker = dependency
+            .requirement()
+            .marker
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        terminal.print_custom(
+            "skipped",
+            format!(
+                "{} (`{marker}` is false for {})",
+                dependency.name(),
+                marker_environment_description(env)
+            ),
+            Color::Yellow,
+            false,
+        )?;
+    }
+
+    Ok(applicable)
+}
+
 /// Create a workspace directory on the system.
 fn create_workspace<T: AsRef<Path>>(path: T) -> HuakResult<()> {
     let root = path.as_ref();
@@ -121,6 +326,8 @@ fn test_config<T: AsRef<Path>>(
         workspace_root: root.as_ref().to_path_buf(),
         cwd: cwd.as_ref().to_path_buf(),
         terminal_options: TerminalOptions { verbosity },
+        installer: None,
+        offline: false,
     };
 
     config
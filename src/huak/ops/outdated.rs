@@ -0,0 +1,272 @@
+use super::{installed_distributions, lock::dependency_groups};
+use crate::{
+    index::{default_index_url, package_index, DistributionFile, PackageIndex},
+    package::normalized_package_name,
+    python_environment::is_remote,
+    Config, HuakResult, InstallOptions,
+};
+use indexmap::IndexMap;
+use pep440_rs::Version;
+use pep508_rs::{MarkerEnvironment, Requirement, VersionOrUrl};
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Write};
+use termcolor::Color;
+
+pub struct OutdatedOptions {
+    /// Print the report as JSON.
+    pub json: bool,
+    /// The URL or path of the package index to compare against. Defaults to pip's
+    /// `PIP_INDEX_URL` or PyPI.
+    pub index_url: Option<String>,
+}
+
+pub fn display_outdated_dependencies(
+    config: &Config,
+    options: &OutdatedOptions,
+) -> HuakResult<()> {
+    let workspace = config.workspace();
+    let metadata = workspace.current_local_metadata()?;
+    let python_env = workspace.resolve_python_environment()?;
+
+    let index_url = options.index_url.clone().unwrap_or_else(default_index_url);
+    if is_remote(&index_url) {
+        config.require_network(&format!("reading {index_url}"))?;
+    }
+    let credentials = config
+        .install_options(&InstallOptions::default())?
+        .credential_store()?;
+    let index = package_index(&index_url, &credentials)?;
+    let installed = installed_distributions(&python_env, config)?
+        .into_iter()
+        .map(|it| (it.normalized_name(), it.version().clone()))
+        .collect::<HashMap<_, _>>();
+
+    let report = outdated_packages(
+        &dependency_groups(metadata.metadata())?,
+        &installed,
+        index.as_ref(),
+        &python_env.marker_environment()?,
+    )?;
+
+    let mut terminal = config.terminal();
+    if options.json {
+        return terminal.print_stdout(serde_json::to_string_pretty(&report)?);
+    }
+    if report.values().all(Vec::is_empty) {
+        return terminal.print_custom(
+            "outdated",
+            "every dependency is up to date",
+            Color::Green,
+            false,
+        );
+    }
+
+    terminal.print_stdout(table(&report).trim_end())
+}
+
+/// A dependency with a newer release than the version installed.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct OutdatedPackage {
+    name: String,
+    /// The installed version.
+    installed: String,
+    /// The version specifiers the dependency is declared with.
+    specifier: Option<String>,
+    /// The newest version that satisfies the specifiers.
+    compatible: Option<String>,
+    /// The newest version available.
+    latest: String,
+}
+
+/// Find the outdated dependencies of each dependency group.
+///
+/// Yanked files and files that don't support the environment's Python are ignored. Pre-releases
+/// are only considered if the installed version or the specifiers are pre-releases.
+/// Dependencies that aren't installed or are direct references are skipped.
+fn outdated_packages(
+    groups: &IndexMap<String, Vec<Requirement>>,
+    installed: &HashMap<String, Version>,
+    index: &dyn PackageIndex,
+    env: &MarkerEnvironment,
+) -> HuakResult<IndexMap<String, Vec<OutdatedPackage>>> {
+    let mut files: HashMap<String, Vec<DistributionFile>> = HashMap::new();
+    let mut report = IndexMap::new();
+
+    for (group, reqs) in groups {
+        let mut packages = Vec::new();
+        for req in reqs {
+            let name = normalized_package_name(&req.name);
+            let Some(version) = installed.get(&name) else {
+                continue;
+            };
+            if !req.evaluate_markers(env, Vec::new()) {
+                continue;
+            }
+            let specifiers = match req.version_or_url.as_ref() {
+                Some(VersionOrUrl::Url(_)) => continue,
+                Some(VersionOrUrl::VersionSpecifier(it)) => Some(it),
+                None => None,
+            };
+
+            if !files.contains_key(&name) {
+                files.insert(name.clone(), index.files(&req.name)?);
+            }
+            let prereleases = version.any_prerelease()
+                || specifiers.map_or(false, |it| {
+                    it.iter().any(|spec| spec.version().any_prerelease())
+                });
+            let mut versions = files[&name]
+                .iter()
+                .filter(|file| !file.yanked)
+                .filter(|file| {
+                    file.requires_python.as_ref().map_or(true, |it| {
+                        it.contains(&env.python_full_version.1)
+                    })
+                })
+                .map(|file| &file.version)
+                .filter(|it| prereleases || !it.any_prerelease())
+                .collect::<Vec<_>>();
+            versions.sort();
+
+            let Some(latest) = versions.last() else {
+                continue;
+            };
+            if *latest <= version {
+                continue;
+            }
+            let compatible = versions
+                .iter()
+                .rev()
+                .find(|it| specifiers.map_or(true, |spec| spec.contains(it)));
+
+            packages.push(OutdatedPackage {
+                name: req.name.clone(),
+                installed: version.to_string(),
+                specifier: specifiers.map(ToString::to_string),
+                compatible: compatible.map(ToString::to_string),
+                latest: latest.to_string(),
+            });
+        }
+        report.insert(group.clone(), packages);
+    }
+
+    Ok(report)
+}
+
+/// Format the outdated dependencies of each group as a table.
+fn table(report: &IndexMap<String, Vec<OutdatedPackage>>) -> String {
+    let header = ["Package", "Installed", "Specifier", "Compatible", "Latest"];
+    let mut out = String::new();
+
+    for (group, packages) in report.iter().filter(|(_, it)| !it.is_empty()) {
+        let rows = packages
+            .iter()
+            .map(|it| {
+                [
+                    it.name.clone(),
+                    it.installed.clone(),
+                    it.specifier.clone().unwrap_or("-".to_string()),
+                    it.compatible.clone().unwrap_or("-".to_string()),
+                    it.latest.clone(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut widths = header.map(str::len);
+        for row in rows.iter() {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.len());
+            }
+        }
+
+        writeln!(out, "{group}").ok();
+        let header = header.map(ToString::to_string);
+        for row in std::iter::once(&header).chain(rows.iter()) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(out, "  {}", line.trim_end()).ok();
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        index::SimpleIndex, test_marker_environment, test_resources_dir_path,
+    };
+    use std::str::FromStr;
+    use url::Url;
+
+    fn mock_report() -> IndexMap<String, Vec<OutdatedPackage>> {
+        let path = test_resources_dir_path().join("mock-index");
+        let index =
+            SimpleIndex::new(Url::from_directory_path(path).unwrap().as_str())
+                .unwrap();
+        let mut groups = IndexMap::new();
+        groups.insert(
+            "required".to_string(),
+            vec![
+                Requirement::from_str("mock-utils <2").unwrap(),
+                Requirement::from_str("mock-web").unwrap(),
+                Requirement::from_str("mock-missing").unwrap(),
+            ],
+        );
+        groups.insert(
+            "dev".to_string(),
+            vec![Requirement::from_str("mock-db ==1.0.0").unwrap()],
+        );
+        let installed = [
+            ("mock-utils", "1.0.0"),
+            ("mock-web", "2.0.0"),
+            ("mock-db", "1.0.0"),
+        ]
+        .into_iter()
+        .map(|(name, version)| {
+            (name.to_string(), Version::from_str(version).unwrap())
+        })
+        .collect();
+
+        outdated_packages(
+            &groups,
+            &installed,
+            &index,
+            &test_marker_environment(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_outdated_packages() {
+        let report = mock_report();
+
+        // mock-web 3.0.0 is yanked and mock-db 2.0.0 requires Python >=4.
+        assert_eq!(
+            report["required"],
+            [OutdatedPackage {
+                name: "mock-utils".to_string(),
+                installed: "1.0.0".to_string(),
+                specifier: Some("<2".to_string()),
+                compatible: Some("1.5.0".to_string()),
+                latest: "2.0.0".to_string(),
+            }]
+        );
+        assert!(report["dev"].is_empty());
+    }
+
+    #[test]
+    fn test_outdated_table() {
+        assert_eq!(
+            table(&mock_report()),
+            r#"required
+  Package     Installed  Specifier  Compatible  Latest
+  mock-utils  1.0.0      <2         1.5.0       2.0.0
+"#
+        );
+    }
+}
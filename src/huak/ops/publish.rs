@@ -1,6 +1,23 @@
-use super::make_venv_command;
-use crate::{dependency::Dependency, Config, HuakResult, InstallOptions};
-use std::{process::Command, str::FromStr};
+use super::{make_venv_command, pin_strategy, pinned_dependency};
+use crate::{
+    credentials::Credentials, dependency::Dependency, Config, HuakResult,
+    InstallOptions,
+};
+use indexmap::IndexMap;
+use std::{path::PathBuf, process::Command, str::FromStr};
+use url::Url;
+
+/// The repository `twine` uploads to by default.
+const DEFAULT_REPOSITORY: &str = "pypi";
+/// The repositories `twine` knows the upload URLs of without a .pypirc.
+const KNOWN_REPOSITORY_URLS: [(&str, &str); 2] = [
+    ("pypi", "https://upload.pypi.org/legacy/"),
+    ("testpypi", "https://test.pypi.org/legacy/"),
+];
+const TWINE_USERNAME_ENV_VAR: &str = "TWINE_USERNAME";
+const TWINE_PASSWORD_ENV_VAR: &str = "TWINE_PASSWORD";
+const TWINE_REPOSITORY_ENV_VAR: &str = "TWINE_REPOSITORY";
+const TWINE_REPOSITORY_URL_ENV_VAR: &str = "TWINE_REPOSITORY_URL";
 
 pub struct PublishOptions {
     /// A values vector of publish options typically used for passing on arguments.
@@ -12,6 +29,7 @@ pub fn publish_project(
     config: &Config,
     options: &PublishOptions,
 ) -> HuakResult<()> {
+    config.require_network("publishing the project")?;
     let workspace = config.workspace();
     let package = workspace.current_package()?;
     let mut metadata = workspace.current_local_metadata()?;
@@ -29,15 +47,15 @@ pub fn publish_project(
 
     // Add the installed `twine` package to the metadata file if it isn't already there.
     if !metadata.metadata().contains_dependency_any(&pub_dep)? {
+        let strategy = pin_strategy(None, metadata.metadata())?;
         for pkg in python_env
             .installed_packages()?
             .iter()
             .filter(|pkg| pkg.name() == pub_dep.name())
         {
-            metadata.metadata_mut().add_optional_dependency(
-                Dependency::from_str(&pkg.to_string())?,
-                "dev",
-            );
+            metadata
+                .metadata_mut()
+                .add_tool_dependency(pinned_dependency(pkg, strategy)?)?;
         }
     }
 
@@ -52,6 +70,209 @@ pub fn publish_project(
         args.extend(v.iter().map(|item| item.as_str()));
     }
     make_venv_command(&mut cmd, &python_env)?;
+    authenticate_twine(&mut cmd, &args, config, options)?;
     cmd.args(args).current_dir(workspace.root());
     config.terminal().run_command(&mut cmd)
 }
+
+/// Give `twine` the credentials of the repository it uploads to with its environment
+/// variables, unless they're already set. They're never passed as arguments.
+///
+/// The repository is `--repository-url` or the upload URL of the `-r/--repository` named in
+/// the .pypirc (like `twine` itself, `TWINE_REPOSITORY_URL` and `TWINE_REPOSITORY` are used
+/// if they aren't passed).
+fn authenticate_twine(
+    cmd: &mut Command,
+    args: &[&str],
+    config: &Config,
+    options: &PublishOptions,
+) -> HuakResult<()> {
+    if std::env::var_os(TWINE_PASSWORD_ENV_VAR).is_some() {
+        return Ok(());
+    }
+    let env_var =
+        |name: &str| std::env::var(name).ok().filter(|it| !it.is_empty());
+    let pypirc = arg_value(args, &["--config-file"])
+        .map(|it| config.workspace().root().join(it))
+        .or_else(pypirc_path)
+        .and_then(|it| std::fs::read_to_string(it).ok());
+    let Some(url) = upload_url(
+        arg_value(args, &["--repository-url"])
+            .or_else(|| env_var(TWINE_REPOSITORY_URL_ENV_VAR)),
+        arg_value(args, &["-r", "--repository"])
+            .or_else(|| env_var(TWINE_REPOSITORY_ENV_VAR)),
+        pypirc.as_deref(),
+    ) else {
+        return Ok(());
+    };
+    let Ok(url) = Url::parse(&url) else {
+        return Ok(());
+    };
+    let credentials = config
+        .install_options(&options.install_options)?
+        .credential_store()?
+        .credentials(&url)?;
+    if let Some(Credentials {
+        username,
+        password: Some(password),
+    }) = credentials
+    {
+        if std::env::var_os(TWINE_USERNAME_ENV_VAR).is_none() {
+            cmd.env(TWINE_USERNAME_ENV_VAR, username);
+        }
+        cmd.env(TWINE_PASSWORD_ENV_VAR, password);
+    }
+
+    Ok(())
+}
+
+/// Get the URL `twine` uploads to from its repository URL or the name of a repository.
+/// Named repositories are read from the .pypirc's contents.
+///
+/// `None` is returned if a named repository can't be found (`twine` reports it) or the
+/// .pypirc already has its password.
+fn upload_url(
+    repository_url: Option<String>,
+    repository: Option<String>,
+    pypirc: Option<&str>,
+) -> Option<String> {
+    if repository_url.is_some() {
+        return repository_url;
+    }
+    let name = repository.unwrap_or(DEFAULT_REPOSITORY.to_string());
+    let section = pypirc
+        .and_then(|it| pypirc_sections(it).remove(&name))
+        .unwrap_or_default();
+    if section.contains_key("password") {
+        return None;
+    }
+
+    section.get("repository").cloned().or_else(|| {
+        KNOWN_REPOSITORY_URLS
+            .iter()
+            .find(|(it, _)| *it == name)
+            .map(|(_, url)| url.to_string())
+    })
+}
+
+/// Parse the sections of a .pypirc's contents into their keys and values.
+fn pypirc_sections(
+    contents: &str,
+) -> IndexMap<String, IndexMap<String, String>> {
+    let mut sections: IndexMap<String, IndexMap<String, String>> =
+        IndexMap::new();
+    let mut section = None;
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) =
+            line.strip_prefix('[').and_then(|it| it.strip_suffix(']'))
+        {
+            section = Some(name.trim().to_string());
+            continue;
+        }
+        let (Some(name), Some((key, value))) =
+            (section.as_ref(), line.split_once(['=', ':']))
+        else {
+            continue;
+        };
+        sections
+            .entry(name.clone())
+            .or_default()
+            .insert(key.trim().to_string(), value.trim().to_string());
+    }
+
+    sections
+}
+
+/// Get the value of the first of a command's flags passed as `--flag value` or
+/// `--flag=value`.
+fn arg_value(args: &[&str], flags: &[&str]) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        flags.iter().find_map(|flag| {
+            if arg == flag {
+                args.get(i + 1).map(ToString::to_string)
+            } else {
+                arg.strip_prefix(flag)
+                    .and_then(|it| it.strip_prefix('='))
+                    .map(ToString::to_string)
+            }
+        })
+    })
+}
+
+/// Get the path to the user's .pypirc.
+fn pypirc_path() -> Option<PathBuf> {
+    let var = match cfg!(windows) {
+        true => "USERPROFILE",
+        false => "HOME",
+    };
+
+    std::env::var_os(var)
+        .filter(|it| !it.is_empty())
+        .map(|it| PathBuf::from(it).join(".pypirc"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOCK_PYPIRC: &str = r#"[distutils]
+index-servers =
+    pypi
+    internal
+
+[pypi]
+username = __token__
+password = pypi-abc123
+
+[internal]
+repository: https://pypi.example.com/legacy/
+username = mock-user
+"#;
+
+    #[test]
+    fn test_upload_url() {
+        let url = |url: Option<&str>, name: Option<&str>, pypirc| {
+            upload_url(url.map(String::from), name.map(String::from), pypirc)
+        };
+
+        assert_eq!(
+            url(Some("https://mock.index/legacy/"), Some("internal"), None)
+                .as_deref(),
+            Some("https://mock.index/legacy/")
+        );
+        assert_eq!(
+            url(None, Some("internal"), Some(MOCK_PYPIRC)).as_deref(),
+            Some("https://pypi.example.com/legacy/")
+        );
+        assert_eq!(
+            url(None, Some("testpypi"), Some(MOCK_PYPIRC)).as_deref(),
+            Some("https://test.pypi.org/legacy/")
+        );
+        assert_eq!(
+            url(None, None, None).as_deref(),
+            Some("https://upload.pypi.org/legacy/")
+        );
+        // twine authenticates with the .pypirc's password and reports unknown repositories.
+        assert_eq!(url(None, None, Some(MOCK_PYPIRC)), None);
+        assert_eq!(url(None, Some("missing"), Some(MOCK_PYPIRC)), None);
+    }
+
+    #[test]
+    fn test_arg_value() {
+        let args = ["-m", "twine", "upload", "dist/*", "-r", "internal"];
+
+        assert_eq!(
+            arg_value(&args, &["-r", "--repository"]).as_deref(),
+            Some("internal")
+        );
+        assert_eq!(
+            arg_value(&["--config-file=.pypirc"], &["--config-file"])
+                .as_deref(),
+            Some(".pypirc")
+        );
+        assert_eq!(arg_value(&args, &["--repository-url"]), None);
+    }
+}
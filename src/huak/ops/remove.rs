@@ -1,8 +1,15 @@
+use super::lock::update_project_lockfile;
 use crate::{
-    dependency::dependency_iter, Config, Error, HuakResult, InstallOptions,
+    dependency::{dependency_iter, Dependency},
+    fs,
+    workspace::Workspace,
+    Config, Error, HuakResult, InstallOptions,
 };
+use termcolor::Color;
 
 pub struct RemoveOptions {
+    /// Report the dependencies and files that would be removed without changing anything.
+    pub dry_run: bool,
     pub install_options: InstallOptions,
 }
 
@@ -34,13 +41,26 @@ pub fn remove_project_dependencies(
     if let Some(deps) = metadata.metadata().optional_dependencies() {
         groups.extend(deps.keys().map(|key| key.to_string()));
     }
+    let dependency_groups = metadata
+        .metadata()
+        .dependency_groups()
+        .map(|it| it.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
     for dep in &deps {
         metadata.metadata_mut().remove_dependency(dep);
+        metadata.metadata_mut().remove_editable_dependency(dep);
         for group in &groups {
             metadata
                 .metadata_mut()
                 .remove_optional_dependency(dep, group);
         }
+        for group in &dependency_groups {
+            metadata.metadata_mut().remove_group_dependency(dep, group);
+        }
+    }
+
+    if options.dry_run {
+        return report_removal(&deps, &workspace, config);
     }
 
     if package.metadata() != metadata.metadata() {
@@ -48,13 +68,75 @@ pub fn remove_project_dependencies(
     }
 
     // Uninstall the dependencies from the Python environment if an environment is found.
-    match workspace.current_python_environment() {
-        Ok(it) => {
-            it.uninstall_packages(&deps, &options.install_options, config)
+    let python_env = match workspace.current_python_environment() {
+        Ok(it) => it,
+        Err(Error::PythonEnvironmentNotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    // Dependencies are uninstalled by name since direct references can't be uninstalled
+    // by their URL.
+    let names = deps.iter().map(|dep| dep.name()).collect::<Vec<_>>();
+    python_env.uninstall_packages(&names, &options.install_options, config)?;
+
+    update_project_lockfile(
+        &workspace,
+        metadata.metadata(),
+        &python_env,
+        &options.install_options,
+        config,
+    )
+}
+
+/// Report the `Dependency`s that would be removed and the files uninstalling them would
+/// delete. The files are printed to stdout.
+fn report_removal(
+    deps: &[Dependency],
+    workspace: &Workspace,
+    config: &Config,
+) -> HuakResult<()> {
+    let mut terminal = config.terminal();
+    for dep in deps {
+        terminal.print_custom("would remove", dep, Color::Red, false)?;
+    }
+
+    let python_env = match workspace.current_python_environment() {
+        Ok(it) => it,
+        Err(Error::PythonEnvironmentNotFound) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let names = deps.iter().map(|dep| dep.name()).collect::<Vec<_>>();
+    for it in python_env.uninstall_report(&names, config)? {
+        terminal.print_custom(
+            "would uninstall",
+            format!(
+                "{} {} ({})",
+                it.name,
+                it.version,
+                fs::format_size(it.freed)
+            ),
+            Color::Red,
+            false,
+        )?;
+        for path in it.outside.iter() {
+            terminal.print_warning(format!(
+                "would keep {} since it's outside the environment",
+                path.display()
+            ))?;
+        }
+        for (path, owner) in it.shared.iter() {
+            terminal.print_custom(
+                "would keep",
+                format!("{} (also installed by {owner})", path.display()),
+                Color::Yellow,
+                false,
+            )?;
+        }
+        for path in it.removed.iter() {
+            terminal.print_stdout(path.display())?;
         }
-        Err(Error::PythonEnvironmentNotFound) => Ok(()),
-        Err(e) => Err(e),
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -82,7 +164,8 @@ mod tests {
         let cwd = root.to_path_buf();
         let config = test_config(&root, &cwd, Verbosity::Quiet);
         let options = RemoveOptions {
-            install_options: InstallOptions { values: None },
+            dry_run: false,
+            install_options: InstallOptions::default(),
         };
         let ws = config.workspace();
         test_venv(&ws);
@@ -123,7 +206,8 @@ mod tests {
         let cwd = root.to_path_buf();
         let config = test_config(&root, &cwd, Verbosity::Quiet);
         let options = RemoveOptions {
-            install_options: InstallOptions { values: None },
+            dry_run: false,
+            install_options: InstallOptions::default(),
         };
         let ws = config.workspace();
         test_venv(&ws);
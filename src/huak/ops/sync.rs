@@ -0,0 +1,216 @@
+use super::{
+    install_dependencies, installed_distributions, lock::dependency_groups,
+};
+use crate::{
+    dependency::Dependency, package::normalized_package_name,
+    resolver::is_satisfied_by, site_packages::InstalledDistribution, Config,
+    HuakResult, InstallOptions,
+};
+use indexmap::IndexMap;
+use pep508_rs::{MarkerEnvironment, Requirement};
+use std::collections::HashSet;
+use termcolor::Color;
+
+/// Packages `sync` never uninstalls unless they're declared.
+const SEED_PACKAGES: [&str; 3] = ["pip", "setuptools", "wheel"];
+
+pub struct SyncOptions {
+    /// Report what would be installed and uninstalled without changing the environment.
+    pub dry_run: bool,
+    /// Exclude the "dev" dependency group.
+    pub no_dev: bool,
+    pub install_options: InstallOptions,
+}
+
+pub fn sync_project(
+    groups: Option<&Vec<String>>,
+    config: &Config,
+    options: &SyncOptions,
+) -> HuakResult<()> {
+    let workspace = config.workspace();
+    let package = workspace.current_package()?;
+    let metadata = workspace.current_local_metadata()?;
+    let python_env = workspace.resolve_python_environment()?;
+
+    // The required dependencies are always synced. If no groups are passed then every
+    // optional dependency group is synced.
+    let requirements = dependency_groups(metadata.metadata())?
+        .into_iter()
+        .filter(|(group, _)| {
+            group == "required"
+                || (groups.map_or(true, |gs| gs.contains(group))
+                    && !(options.no_dev && group == "dev"))
+        })
+        .flat_map(|(_, reqs)| reqs)
+        .collect::<Vec<_>>();
+
+    let project_name = normalized_package_name(package.name());
+    let env = python_env.marker_environment()?;
+    let mut distributions = installed_distributions(&python_env, config)?;
+    let mut plan = SyncPlan::new(&distributions, &requirements, &env);
+    let missing = plan
+        .missing
+        .iter()
+        .map(Dependency::from)
+        .collect::<Vec<_>>();
+
+    let mut terminal = config.terminal();
+    if options.dry_run {
+        for name in plan.extras(&distributions, &project_name) {
+            terminal.print_custom("would remove", name, Color::Red, false)?;
+        }
+        for dep in missing.iter() {
+            terminal.print_custom("would install", dep, Color::Green, false)?;
+        }
+        return Ok(());
+    }
+
+    // Missing packages are installed first since the dependencies they share with installed
+    // packages are only known to be required once they're installed.
+    if !missing.is_empty() {
+        install_dependencies(
+            &python_env,
+            &missing,
+            metadata.metadata(),
+            &options.install_options,
+            config,
+        )?;
+        distributions = installed_distributions(&python_env, config)?;
+        plan = SyncPlan::new(&distributions, &requirements, &env);
+    }
+
+    let extras = plan.extras(&distributions, &project_name);
+    if !extras.is_empty() {
+        // Trailing arguments are meant for pip install, so they aren't passed to pip uninstall.
+        python_env.uninstall_packages(
+            &extras,
+            &InstallOptions::default(),
+            config,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The result of walking declared requirements over the installed distributions.
+struct SyncPlan {
+    /// Normalized names of the installed distributions the requirements depend on.
+    required: HashSet<String>,
+    /// Requirements that aren't satisfied by the installed distributions.
+    missing: Vec<Requirement>,
+}
+
+impl SyncPlan {
+    fn new(
+        distributions: &[InstalledDistribution],
+        requirements: &[Requirement],
+        env: &MarkerEnvironment,
+    ) -> SyncPlan {
+        let installed = distributions
+            .iter()
+            .map(|it| (it.normalized_name(), it))
+            .collect::<IndexMap<_, _>>();
+
+        let mut required = HashSet::new();
+        let mut missing: Vec<Requirement> = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = requirements
+            .iter()
+            .filter(|req| req.evaluate_markers(env, Vec::new()))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        while let Some(req) = stack.pop() {
+            let name = normalized_package_name(&req.name);
+            let extras = req.extras.clone().unwrap_or_default();
+            if !visited.insert((name.clone(), extras.clone())) {
+                continue;
+            }
+
+            let Some(dist) = installed.get(&name) else {
+                if !missing
+                    .iter()
+                    .any(|it| normalized_package_name(&it.name) == name)
+                {
+                    missing.push(req);
+                }
+                continue;
+            };
+
+            if !is_satisfied_by(&req, dist.version())
+                && !missing
+                    .iter()
+                    .any(|it| normalized_package_name(&it.name) == name)
+            {
+                missing.push(req.clone());
+            }
+
+            required.insert(name);
+            stack.extend(
+                dist.metadata
+                    .requires_dist
+                    .iter()
+                    .filter(|it| it.evaluate_markers(env, extras.clone()))
+                    .cloned(),
+            );
+        }
+
+        SyncPlan { required, missing }
+    }
+
+    /// Get the names of the installed distributions nothing requires. The project and the
+    /// packages every environment is seeded with are never extras.
+    fn extras(
+        &self,
+        distributions: &[InstalledDistribution],
+        project_name: &str,
+    ) -> Vec<String> {
+        distributions
+            .iter()
+            .filter(|dist| {
+                let name = dist.normalized_name();
+                !self.required.contains(&name)
+                    && name != project_name
+                    && !SEED_PACKAGES.contains(&name.as_str())
+            })
+            .map(|dist| dist.name().to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        site_packages::installed_distributions, test_marker_environment,
+        test_resources_dir_path,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn test_sync_plan() {
+        let dists = installed_distributions(
+            test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+        let requirements = ["mock-web[speedups]>=2.0", "pytest>=7.1"]
+            .iter()
+            .map(|it| Requirement::from_str(it).unwrap())
+            .collect::<Vec<_>>();
+
+        let plan =
+            SyncPlan::new(&dists, &requirements, &test_marker_environment());
+        let mut required = plan.required.iter().cloned().collect::<Vec<_>>();
+        required.sort();
+        let mut missing = plan
+            .missing
+            .iter()
+            .map(|it| it.name.as_str())
+            .collect::<Vec<_>>();
+        missing.sort();
+
+        assert_eq!(required, ["mock-utils", "mock-web"]);
+        assert_eq!(missing, ["mock-speedups", "pytest"]);
+        assert_eq!(plan.extras(&dists, "mock-project"), ["Mock.Legacy"]);
+    }
+}
@@ -1,4 +1,4 @@
-use super::make_venv_command;
+use super::{make_venv_command, pin_strategy, pinned_dependency};
 use crate::{dependency::Dependency, Config, HuakResult, InstallOptions};
 use std::{process::Command, str::FromStr};
 
@@ -26,15 +26,15 @@ pub fn test_project(config: &Config, options: &TestOptions) -> HuakResult<()> {
 
     // Add the installed `pytest` package to the metadata file if it isn't already there.
     if !metadata.metadata().contains_dependency_any(&test_dep)? {
+        let strategy = pin_strategy(None, metadata.metadata())?;
         for pkg in python_env
             .installed_packages()?
             .iter()
             .filter(|pkg| pkg.name() == test_dep.name())
         {
-            metadata.metadata_mut().add_optional_dependency(
-                Dependency::from_str(&pkg.to_string())?,
-                "dev",
-            );
+            metadata
+                .metadata_mut()
+                .add_tool_dependency(pinned_dependency(pkg, strategy)?)?;
         }
     }
 
@@ -83,7 +83,7 @@ mod tests {
         test_venv(&ws);
         let options = TestOptions {
             values: None,
-            install_options: InstallOptions { values: None },
+            install_options: InstallOptions::default(),
         };
 
         test_project(&config, &options).unwrap();
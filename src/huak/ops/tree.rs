@@ -0,0 +1,480 @@
+use super::{installed_distributions, lock::dependency_groups};
+use crate::{
+    dependency_graph::{DependencyGraph, Dependent},
+    package::normalized_package_name,
+    Config, Error, HuakResult,
+};
+use pep508_rs::Requirement;
+use serde::Serialize;
+use std::{collections::HashSet, fmt::Write, str::FromStr};
+
+pub struct TreeOptions {
+    /// Show the packages that depend on a package instead of its dependencies.
+    pub invert: Option<String>,
+    /// The maximum depth of the tree.
+    pub depth: Option<usize>,
+    /// Only show packages required by more than one dependent (inverted).
+    pub duplicates: bool,
+    pub format: TreeFormat,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The output format of `huak tree`.
+pub enum TreeFormat {
+    /// An indented tree.
+    #[default]
+    Text,
+    Json,
+    /// A Graphviz DOT graph.
+    Dot,
+    /// A Mermaid flowchart.
+    Mermaid,
+}
+
+impl FromStr for TreeFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(TreeFormat::Text),
+            "json" => Ok(TreeFormat::Json),
+            "dot" => Ok(TreeFormat::Dot),
+            "mermaid" => Ok(TreeFormat::Mermaid),
+            _ => Err(Error::HuakConfigurationError(format!(
+                "invalid tree format {s} (expected one of text, json, dot, mermaid)"
+            ))),
+        }
+    }
+}
+
+pub fn display_project_tree(
+    config: &Config,
+    options: &TreeOptions,
+) -> HuakResult<()> {
+    let workspace = config.workspace();
+    let metadata = workspace.current_local_metadata()?;
+    let python_env = workspace.resolve_python_environment()?;
+    let graph = DependencyGraph::new(
+        dependency_groups(metadata.metadata())?,
+        &installed_distributions(&python_env, config)?,
+        &python_env.marker_environment()?,
+    );
+
+    let trees = dependency_trees(&graph, options)?;
+    let output = match options.format {
+        TreeFormat::Text => text(&trees),
+        TreeFormat::Json => serde_json::to_string_pretty(&trees)?,
+        TreeFormat::Dot => dot(&trees),
+        TreeFormat::Mermaid => mermaid(&trees),
+    };
+
+    config.terminal().print_stdout(output.trim_end())
+}
+
+/// A node of a dependency tree. Dependency groups are nodes without a version.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct TreeNode {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    /// The requirement that links the node to its parent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requirement: Option<String>,
+    /// The package is required but isn't installed.
+    #[serde(skip_serializing_if = "is_false")]
+    missing: bool,
+    /// The node was already expanded elsewhere in the tree.
+    #[serde(skip_serializing_if = "is_false")]
+    duplicate: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    fn group(name: &str) -> TreeNode {
+        TreeNode {
+            name: name.to_string(),
+            version: None,
+            requirement: None,
+            missing: false,
+            duplicate: false,
+            children: Vec::new(),
+        }
+    }
+
+    /// The label of the node used by each output format.
+    fn label(&self) -> String {
+        match self.version.as_ref() {
+            Some(it) => format!("{} v{}", self.name, it),
+            None => self.name.clone(),
+        }
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Build the dependency trees to display. Subtrees are only expanded the first time their
+/// package appears.
+fn dependency_trees(
+    graph: &DependencyGraph,
+    options: &TreeOptions,
+) -> HuakResult<Vec<TreeNode>> {
+    let mut seen = HashSet::new();
+
+    if let Some(name) = options.invert.as_ref() {
+        if graph.package(name).is_none() && graph.dependents(name).is_empty() {
+            return Err(Error::HuakConfigurationError(format!(
+                "{name} is not installed or required by the project"
+            )));
+        }
+        return Ok(vec![inverted_node(
+            graph,
+            name,
+            None,
+            0,
+            options.depth,
+            &mut seen,
+        )]);
+    }
+
+    if options.duplicates {
+        let mut names = graph
+            .packages()
+            .map(|pkg| pkg.name.as_str())
+            .chain(
+                graph
+                    .groups()
+                    .values()
+                    .flatten()
+                    .map(|req| req.name.as_str()),
+            )
+            .filter(|name| graph.dependents(name).len() > 1)
+            .map(normalized_package_name)
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+
+        return Ok(names
+            .iter()
+            .map(|name| {
+                inverted_node(graph, name, None, 0, options.depth, &mut seen)
+            })
+            .collect());
+    }
+
+    Ok(graph
+        .groups()
+        .iter()
+        .map(|(group, reqs)| {
+            let mut node = TreeNode::group(group);
+            if options.depth.map_or(true, |it| it > 0) {
+                node.children = reqs
+                    .iter()
+                    .map(|req| {
+                        dependency_node(graph, req, 1, options.depth, &mut seen)
+                    })
+                    .collect();
+            }
+            node
+        })
+        .collect())
+}
+
+fn dependency_node(
+    graph: &DependencyGraph,
+    requirement: &Requirement,
+    depth: usize,
+    max_depth: Option<usize>,
+    seen: &mut HashSet<String>,
+) -> TreeNode {
+    let Some(package) = graph.package(&requirement.name) else {
+        return TreeNode {
+            name: requirement.name.clone(),
+            version: None,
+            requirement: Some(requirement.to_string()),
+            missing: true,
+            duplicate: false,
+            children: Vec::new(),
+        };
+    };
+
+    let mut node = TreeNode {
+        name: package.name.clone(),
+        version: Some(package.version.to_string()),
+        requirement: Some(requirement.to_string()),
+        missing: false,
+        duplicate: false,
+        children: Vec::new(),
+    };
+    if package.requires.is_empty() || max_depth.map_or(false, |it| depth >= it)
+    {
+        return node;
+    }
+    if !seen.insert(normalized_package_name(&package.name)) {
+        node.duplicate = true;
+        return node;
+    }
+    node.children = package
+        .requires
+        .iter()
+        .map(|req| dependency_node(graph, req, depth + 1, max_depth, seen))
+        .collect();
+
+    node
+}
+
+fn inverted_node(
+    graph: &DependencyGraph,
+    name: &str,
+    requirement: Option<&Requirement>,
+    depth: usize,
+    max_depth: Option<usize>,
+    seen: &mut HashSet<String>,
+) -> TreeNode {
+    let package = graph.package(name);
+    let mut node = TreeNode {
+        name: package.map_or(name.to_string(), |it| it.name.clone()),
+        version: package.map(|it| it.version.to_string()),
+        requirement: requirement.map(ToString::to_string),
+        missing: package.is_none(),
+        duplicate: false,
+        children: Vec::new(),
+    };
+    let dependents = graph.dependents(name);
+    if dependents.is_empty() || max_depth.map_or(false, |it| depth >= it) {
+        return node;
+    }
+    if !seen.insert(normalized_package_name(name)) {
+        node.duplicate = true;
+        return node;
+    }
+    node.children = dependents
+        .into_iter()
+        .map(|it| match it {
+            Dependent::Group(group, req) => TreeNode {
+                requirement: Some(req.to_string()),
+                ..TreeNode::group(group)
+            },
+            Dependent::Package(pkg, req) => inverted_node(
+                graph,
+                &pkg.name,
+                Some(req),
+                depth + 1,
+                max_depth,
+                seen,
+            ),
+        })
+        .collect();
+
+    node
+}
+
+fn text(trees: &[TreeNode]) -> String {
+    fn write_children(out: &mut String, node: &TreeNode, prefix: &str) {
+        for (i, child) in node.children.iter().enumerate() {
+            let last = i == node.children.len() - 1;
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            let mut label = child.label();
+            if child.missing {
+                label.push_str(" (not installed)");
+            }
+            if child.duplicate {
+                label.push_str(" (*)");
+            }
+            writeln!(out, "{prefix}{branch}{label}").ok();
+            write_children(out, child, &format!("{prefix}{indent}"));
+        }
+    }
+
+    let mut out = String::new();
+    for tree in trees {
+        writeln!(out, "{}", tree.label()).ok();
+        write_children(&mut out, tree, "");
+    }
+
+    out
+}
+
+/// Collect the unique edges of the trees as pairs of labels.
+fn edges(trees: &[TreeNode]) -> Vec<(String, String)> {
+    fn collect(node: &TreeNode, edges: &mut Vec<(String, String)>) {
+        for child in node.children.iter() {
+            let edge = (node.label(), child.label());
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+            collect(child, edges);
+        }
+    }
+
+    let mut edges = Vec::new();
+    trees.iter().for_each(|it| collect(it, &mut edges));
+
+    edges
+}
+
+fn dot(trees: &[TreeNode]) -> String {
+    let mut out = String::from("digraph dependencies {\n");
+    for tree in trees.iter().filter(|it| it.children.is_empty()) {
+        writeln!(out, "    \"{}\";", tree.label()).ok();
+    }
+    for (from, to) in edges(trees) {
+        writeln!(out, "    \"{from}\" -> \"{to}\";").ok();
+    }
+    out.push('}');
+
+    out
+}
+
+fn mermaid(trees: &[TreeNode]) -> String {
+    // Mermaid node ids can't contain most punctuation, so nodes are numbered and labeled.
+    let mut ids: Vec<String> = Vec::new();
+    let mut id = |label: &str, out: &mut String| -> String {
+        match ids.iter().position(|it| it == label) {
+            Some(i) => format!("n{i}"),
+            None => {
+                ids.push(label.to_string());
+                let id = format!("n{}", ids.len() - 1);
+                writeln!(out, "    {id}[\"{label}\"]").ok();
+                id
+            }
+        }
+    };
+
+    let mut out = String::from("graph TD\n");
+    for tree in trees {
+        id(&tree.label(), &mut out);
+    }
+    for (from, to) in edges(trees) {
+        let from = id(&from, &mut out);
+        let to = id(&to, &mut out);
+        writeln!(out, "    {from} --> {to}").ok();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        site_packages::installed_distributions, test_marker_environment,
+        test_resources_dir_path,
+    };
+    use indexmap::IndexMap;
+
+    fn mock_graph() -> DependencyGraph {
+        let dists = installed_distributions(
+            test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+        let mut groups = IndexMap::new();
+        groups.insert(
+            "required".to_string(),
+            vec![Requirement::from_str("mock-web").unwrap()],
+        );
+        groups.insert(
+            "dev".to_string(),
+            vec![
+                Requirement::from_str("mock-legacy").unwrap(),
+                Requirement::from_str("mock-missing").unwrap(),
+            ],
+        );
+
+        DependencyGraph::new(groups, &dists, &test_marker_environment())
+    }
+
+    fn options() -> TreeOptions {
+        TreeOptions {
+            invert: None,
+            depth: None,
+            duplicates: false,
+            format: TreeFormat::Text,
+        }
+    }
+
+    #[test]
+    fn test_tree_text() {
+        let trees = dependency_trees(&mock_graph(), &options()).unwrap();
+
+        assert_eq!(
+            text(&trees),
+            r#"required
+└── mock-web v2.0
+    └── mock_utils v1.0.0
+dev
+├── Mock.Legacy v0.3
+│   └── mock_utils v1.0.0
+└── mock-missing (not installed)
+"#
+        );
+    }
+
+    #[test]
+    fn test_tree_invert() {
+        let options = TreeOptions {
+            invert: Some("mock-utils".to_string()),
+            ..options()
+        };
+        let trees = dependency_trees(&mock_graph(), &options).unwrap();
+
+        assert_eq!(
+            text(&trees),
+            r#"mock_utils v1.0.0
+├── Mock.Legacy v0.3
+│   └── dev
+└── mock-web v2.0
+    └── required
+"#
+        );
+        assert_eq!(
+            mermaid(&trees),
+            r#"graph TD
+    n0["mock_utils v1.0.0"]
+    n1["Mock.Legacy v0.3"]
+    n0 --> n1
+    n2["dev"]
+    n1 --> n2
+    n3["mock-web v2.0"]
+    n0 --> n3
+    n4["required"]
+    n3 --> n4
+"#
+        );
+    }
+
+    #[test]
+    fn test_tree_depth_and_duplicates() {
+        let options = TreeOptions {
+            depth: Some(1),
+            ..options()
+        };
+        let trees = dependency_trees(&mock_graph(), &options).unwrap();
+
+        assert_eq!(
+            dot(&trees),
+            r#"digraph dependencies {
+    "required" -> "mock-web v2.0";
+    "dev" -> "Mock.Legacy v0.3";
+    "dev" -> "mock-missing";
+}"#
+        );
+
+        let options = TreeOptions {
+            duplicates: true,
+            ..options
+        };
+        let trees = dependency_trees(&mock_graph(), &options).unwrap();
+
+        assert_eq!(
+            trees.iter().map(|it| it.label()).collect::<Vec<_>>(),
+            ["mock_utils v1.0.0"]
+        );
+    }
+}
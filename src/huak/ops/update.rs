@@ -1,7 +1,15 @@
+use super::{
+    install_dependencies,
+    lock::{dependency_groups, update_project_lockfile},
+    report_constraints,
+};
 use crate::{
     dependency::{dependency_iter, Dependency},
-    Config, HuakResult, InstallOptions,
+    metadata::Metadata,
+    package::normalized_package_name,
+    Config, HuakResult, InstallOptions, PythonEnvironment,
 };
+use indexmap::IndexMap;
 use std::str::FromStr;
 
 pub struct UpdateOptions {
@@ -18,6 +26,19 @@ pub fn update_project_dependencies(
     let mut metadata = workspace.current_local_metadata()?;
     let python_env = workspace.resolve_python_environment()?;
 
+    // Direct references (like git or local path dependencies) are updated from their URL and
+    // are never re-pinned to a version.
+    let urls = dependency_groups(metadata.metadata())?
+        .into_values()
+        .flatten()
+        .filter_map(|req| {
+            let dep = Dependency::from(req);
+            dep.url()
+                .is_some()
+                .then(|| (normalized_package_name(dep.name()), dep))
+        })
+        .collect::<IndexMap<_, _>>();
+
     // Collect dependencies to update if they are listed in the metadata file.
     if let Some(it) = dependencies.as_ref() {
         let deps = dependency_iter(it)
@@ -32,13 +53,24 @@ pub fn update_project_dependencies(
                     None
                 }
             })
+            .map(|dep| {
+                urls.get(&normalized_package_name(dep.name()))
+                    .cloned()
+                    .unwrap_or(dep)
+            })
             .collect::<Vec<_>>();
 
         if deps.is_empty() {
             return Ok(());
         }
 
-        python_env.update_packages(&deps, &options.install_options, config)?;
+        update_dependencies(
+            &python_env,
+            &deps,
+            metadata.metadata(),
+            options,
+            config,
+        )?;
     } else {
         let mut deps = metadata
             .metadata()
@@ -53,18 +85,50 @@ pub fn update_project_dependencies(
                 )
             });
         }
+        if let Some(gdeps) = metadata.metadata().dependency_groups() {
+            for g in gdeps.keys() {
+                deps.extend(
+                    metadata
+                        .metadata()
+                        .dependency_group(g)?
+                        .iter()
+                        .map(Dependency::from),
+                );
+            }
+        }
 
         deps.dedup();
-        python_env.update_packages(&deps, &options.install_options, config)?;
+        update_dependencies(
+            &python_env,
+            &deps,
+            metadata.metadata(),
+            options,
+            config,
+        )?;
     }
 
+    report_constraints(
+        &python_env,
+        metadata.metadata(),
+        &options.install_options,
+        config,
+    )?;
+
     // Get all groups from the metadata file to include in the removal process.
     let mut groups = Vec::new();
     if let Some(deps) = metadata.metadata().optional_dependencies() {
         groups.extend(deps.keys().map(|key| key.to_string()));
     }
+    let dependency_groups = metadata
+        .metadata()
+        .dependency_groups()
+        .map(|it| it.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
 
     for pkg in python_env.installed_packages()? {
+        if urls.contains_key(&normalized_package_name(pkg.name())) {
+            continue;
+        }
         let dep = &Dependency::from_str(&pkg.to_string())?;
         if metadata.metadata().contains_dependency(dep)? {
             metadata.metadata_mut().remove_dependency(dep);
@@ -78,11 +142,58 @@ pub fn update_project_dependencies(
                     .add_optional_dependency(dep.clone(), g);
             }
         }
+        for g in dependency_groups.iter() {
+            if metadata.metadata().contains_group_dependency(dep, g)? {
+                metadata.metadata_mut().remove_group_dependency(dep, g);
+                metadata.metadata_mut().add_group_dependency(dep.clone(), g);
+            }
+        }
     }
 
     if package.metadata() != metadata.metadata() {
         metadata.write_file()?;
     }
+
+    update_project_lockfile(
+        &workspace,
+        metadata.metadata(),
+        &python_env,
+        &options.install_options,
+        config,
+    )
+}
+
+/// Update `Dependency`s installed to a `PythonEnvironment`. Editable dependencies are
+/// reinstalled from their local project directory.
+fn update_dependencies(
+    python_env: &PythonEnvironment,
+    dependencies: &[Dependency],
+    metadata: &Metadata,
+    options: &UpdateOptions,
+    config: &Config,
+) -> HuakResult<()> {
+    let table = metadata.huak_table()?;
+    let (editable, dependencies): (Vec<&Dependency>, Vec<&Dependency>) =
+        dependencies.iter().partition(|dep| table.is_editable(dep));
+
+    if !dependencies.is_empty() {
+        python_env.update_packages(
+            &dependencies,
+            &options.install_options,
+            config,
+        )?;
+    }
+
+    if !editable.is_empty() {
+        install_dependencies(
+            python_env,
+            &editable.into_iter().cloned().collect::<Vec<_>>(),
+            metadata,
+            &options.install_options,
+            config,
+        )?;
+    }
+
     Ok(())
 }
 
@@ -110,7 +221,7 @@ mod tests {
         let ws = config.workspace();
         test_venv(&ws);
         let options = UpdateOptions {
-            install_options: InstallOptions { values: None },
+            install_options: InstallOptions::default(),
         };
 
         update_project_dependencies(None, &config, &options).unwrap();
@@ -130,7 +241,7 @@ mod tests {
         let ws = config.workspace();
         test_venv(&ws);
         let options = UpdateOptions {
-            install_options: InstallOptions { values: None },
+            install_options: InstallOptions::default(),
         };
 
         update_project_dependencies(None, &config, &options).unwrap();
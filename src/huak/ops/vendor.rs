@@ -0,0 +1,205 @@
+use super::lock::dependency_groups;
+use crate::{
+    cache::Cache,
+    credentials::CredentialStore,
+    installer::{read_url, verify_hashes},
+    lockfile::{LockedPackage, Lockfile},
+    Config, Error, HuakResult, InstallOptions,
+};
+use std::path::{Path, PathBuf};
+use termcolor::Color;
+use url::Url;
+
+pub struct VendorOptions {
+    /// The directory to download the distributions to.
+    pub path: PathBuf,
+    pub install_options: InstallOptions,
+}
+
+/// Download every distribution the project needs to a directory, so it can be installed
+/// offline with `--find-links`.
+///
+/// If the project is locked the locked distributions are downloaded. Otherwise the
+/// dependencies listed in the metadata file and everything they require are.
+pub fn vendor_dependencies(
+    config: &Config,
+    options: &VendorOptions,
+) -> HuakResult<()> {
+    let workspace = config.workspace();
+    let metadata = workspace.current_local_metadata()?;
+    let lockfile_path = workspace.current_lockfile_path()?;
+    let dir = config.cwd.join(&options.path);
+    std::fs::create_dir_all(&dir)?;
+
+    if lockfile_path.exists() {
+        let lockfile = Lockfile::new(&lockfile_path)?;
+        if !lockfile.is_fresh(&dependency_groups(metadata.metadata())?) {
+            return Err(Error::LockfileOutOfDate(format!(
+                "{} has changed since {} was written (run `huak lock`)",
+                metadata.path().display(),
+                lockfile_path.display()
+            )));
+        }
+
+        let install_options =
+            config.install_options(&options.install_options)?;
+        let cache = match install_options.no_cache {
+            true => None,
+            false => Cache::from_env().ok(),
+        };
+        let credentials = install_options.credential_store()?;
+        for package in lockfile.packages() {
+            vendor_locked_package(
+                package,
+                &dir,
+                cache.as_ref(),
+                &credentials,
+                config,
+            )?;
+        }
+        return Ok(());
+    }
+
+    let packages = dependency_groups(metadata.metadata())?
+        .into_values()
+        .flatten()
+        .map(|it| it.to_string())
+        .collect::<Vec<_>>();
+    if packages.is_empty() {
+        return Ok(());
+    }
+
+    let python_env = workspace.resolve_python_environment()?;
+    python_env.download_packages(
+        &packages,
+        &dir,
+        &options.install_options,
+        config,
+    )
+}
+
+/// Copy a `LockedPackage`'s distribution to a directory from the `Cache` or its source.
+/// Distributions already in the directory are kept if they match their hashes.
+///
+/// Packages locked to something other than a distribution file (like a local directory or
+/// a git repository) are skipped with a warning.
+fn vendor_locked_package(
+    package: &LockedPackage,
+    dir: &Path,
+    cache: Option<&Cache>,
+    credentials: &CredentialStore,
+    config: &Config,
+) -> HuakResult<()> {
+    let mut terminal = config.terminal();
+    let url = package.source.as_ref().and_then(|it| Url::parse(it).ok());
+    let filename = url
+        .as_ref()
+        .and_then(|it| it.path_segments())
+        .and_then(|mut it| it.next_back())
+        .filter(|it| {
+            it.ends_with(".whl")
+                || it.ends_with(".tar.gz")
+                || it.ends_with(".zip")
+        })
+        .map(ToString::to_string);
+    let (Some(url), Some(filename), false) =
+        (url, filename, package.hashes.is_empty())
+    else {
+        return terminal.print_warning(format!(
+            "{} {} isn't locked to a distribution file, so it wasn't vendored",
+            package.name, package.version
+        ));
+    };
+
+    let path = dir.join(&filename);
+    if let Ok(bytes) = std::fs::read(&path) {
+        if verify_hashes(&filename, &bytes, &package.hashes).is_ok() {
+            return Ok(());
+        }
+    }
+
+    let cached = package
+        .hashes
+        .iter()
+        .filter_map(|it| it.strip_prefix("sha256:"))
+        .find_map(|it| cache.and_then(|cache| cache.wheel(it)));
+    let bytes = match cached {
+        Some(it) => std::fs::read(it)?,
+        None => {
+            if url.scheme() != "file" {
+                config.require_network(&format!("downloading {filename}"))?;
+            }
+            read_url(&url, credentials)?
+        }
+    };
+    verify_hashes(&filename, &bytes, &package.hashes)?;
+    std::fs::write(path, bytes)?;
+
+    terminal.print_custom("vendored", filename, Color::Green, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ops::test_config, test_resources_dir_path, Verbosity};
+    use sha2::Digest;
+    use std::str::FromStr;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_vendor_locked_dependencies() {
+        let dir = tempdir().unwrap();
+        let wheel = test_resources_dir_path()
+            .join("mock-wheels")
+            .join("mock_utils-1.0.0-py3-none-any.whl");
+        let bytes = std::fs::read(&wheel).unwrap();
+        let package = LockedPackage {
+            name: "mock-utils".to_string(),
+            version: pep440_rs::Version::from_str("1.0.0").unwrap(),
+            source: Some(Url::from_file_path(&wheel).unwrap().to_string()),
+            groups: vec!["required".to_string()],
+            hashes: vec![format!("sha256:{:x}", sha2::Sha256::digest(&bytes))],
+            dependencies: Vec::new(),
+        };
+        let config = test_config(dir.path(), dir.path(), Verbosity::Quiet);
+        let wheelhouse = dir.path();
+
+        vendor_locked_package(
+            &package,
+            wheelhouse,
+            None,
+            &CredentialStore::default(),
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read(wheelhouse.join("mock_utils-1.0.0-py3-none-any.whl"))
+                .unwrap(),
+            bytes
+        );
+
+        // Remote sources can't be downloaded offline.
+        let package = LockedPackage {
+            source: Some(
+                "https://files.example.com/mock_utils-2.0.0-py3-none-any.whl"
+                    .to_string(),
+            ),
+            ..package
+        };
+        let config = Config {
+            offline: true,
+            ..config
+        };
+        assert!(matches!(
+            vendor_locked_package(
+                &package,
+                wheelhouse,
+                None,
+                &CredentialStore::default(),
+                &config,
+            ),
+            Err(Error::NetworkUnavailable(_))
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+use super::{installed_distributions, lock::dependency_groups};
+use crate::{
+    dependency_graph::{DependencyGraph, Dependent},
+    package::normalized_package_name,
+    Config, Error, HuakResult,
+};
+use std::fmt::Write;
+use termcolor::Color;
+
+/// Display every chain of requirements that pulls a package into the project's environment,
+/// from the dependency group that declares it to the package. Installed packages nothing
+/// declared requires are reported as orphaned.
+pub fn display_dependency_chains(
+    package: &str,
+    config: &Config,
+) -> HuakResult<()> {
+    let workspace = config.workspace();
+    let metadata = workspace.current_local_metadata()?;
+    let python_env = workspace.resolve_python_environment()?;
+    let graph = DependencyGraph::new(
+        dependency_groups(metadata.metadata())?,
+        &installed_distributions(&python_env, config)?,
+        &python_env.marker_environment()?,
+    );
+
+    let chains = dependency_chains(&graph, package);
+    let label = match graph.package(package) {
+        Some(it) => format!("{} {}", it.name, it.version),
+        None if chains.is_empty() => {
+            return Err(Error::HuakConfigurationError(format!(
+                "{package} is not installed or required by the project"
+            )))
+        }
+        None => format!("{package} (not installed)"),
+    };
+
+    let mut terminal = config.terminal();
+    if chains.is_empty() {
+        return terminal.print_custom(
+            "orphaned",
+            format!("{label} isn't required by anything the project declares"),
+            Color::Yellow,
+            false,
+        );
+    }
+
+    terminal.print_stdout(text(&label, &chains).trim_end())
+}
+
+/// Find the chains of `Dependent`s that lead from a dependency group to a package. Each
+/// chain starts with the group and ends with the requirement on the package. Groups that
+/// require the package directly come first.
+fn dependency_chains<'a>(
+    graph: &'a DependencyGraph,
+    name: &str,
+) -> Vec<Vec<Dependent<'a>>> {
+    fn walk<'a>(
+        graph: &'a DependencyGraph,
+        name: &str,
+        path: &mut Vec<Dependent<'a>>,
+        visiting: &mut Vec<String>,
+        chains: &mut Vec<Vec<Dependent<'a>>>,
+    ) {
+        visiting.push(normalized_package_name(name));
+        for dependent in graph.dependents(name) {
+            path.push(dependent);
+            match dependent {
+                Dependent::Group(..) => {
+                    chains.push(path.iter().rev().copied().collect())
+                }
+                // Packages already on the path are skipped to stop at cycles.
+                Dependent::Package(pkg, _)
+                    if !visiting
+                        .contains(&normalized_package_name(&pkg.name)) =>
+                {
+                    walk(graph, &pkg.name, path, visiting, chains)
+                }
+                Dependent::Package(..) => (),
+            }
+            path.pop();
+        }
+        visiting.pop();
+    }
+
+    let mut chains = Vec::new();
+    walk(graph, name, &mut Vec::new(), &mut Vec::new(), &mut chains);
+
+    chains
+}
+
+/// Write each chain on a line as its group followed by the requirement at each hop, with
+/// the installed package that requirement selected.
+fn text(label: &str, chains: &[Vec<Dependent>]) -> String {
+    let mut out = String::new();
+    writeln!(out, "{label}").ok();
+    for chain in chains {
+        let mut line = String::new();
+        for dependent in chain {
+            match dependent {
+                Dependent::Group(group, req) => {
+                    write!(line, "{group}: {req}").ok();
+                }
+                Dependent::Package(pkg, req) => {
+                    write!(line, " ({} {}) -> {req}", pkg.name, pkg.version)
+                        .ok();
+                }
+            }
+        }
+        writeln!(out, "  {line}").ok();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        site_packages::installed_distributions, test_marker_environment,
+        test_resources_dir_path,
+    };
+    use indexmap::IndexMap;
+    use pep508_rs::Requirement;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_dependency_chains() {
+        let dists = installed_distributions(
+            test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+        let mut groups = IndexMap::new();
+        groups.insert(
+            "required".to_string(),
+            vec![Requirement::from_str("mock-web[speedups]").unwrap()],
+        );
+        groups.insert(
+            "dev".to_string(),
+            vec![
+                Requirement::from_str("mock-legacy").unwrap(),
+                Requirement::from_str("mock-utils ; python_version >= '3.8'")
+                    .unwrap(),
+            ],
+        );
+        let graph =
+            DependencyGraph::new(groups, &dists, &test_marker_environment());
+
+        let chains = dependency_chains(&graph, "mock_utils");
+
+        assert_eq!(
+            text("mock_utils 1.0.0", &chains),
+            r#"mock_utils 1.0.0
+  dev: mock-utils ; python_version >= '3.8'
+  dev: mock-legacy (Mock.Legacy 0.3) -> mock-utils >=1.0
+  required: mock-web[speedups] (mock-web 2.0) -> mock-utils >=1.0
+"#
+        );
+        assert!(dependency_chains(&graph, "mock-broken").is_empty());
+    }
+}
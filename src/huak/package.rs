@@ -27,6 +27,19 @@ pub struct Package {
 }
 
 impl Package {
+    /// Initialize a `Package` from its name and PEP 440 `Version`.
+    pub fn new(name: &str, version: Version) -> HuakResult<Package> {
+        let id = PackageId {
+            name: canonical_package_name(name)?,
+            version,
+        };
+
+        let mut metadata = Metadata::default();
+        metadata.set_project_name(name.to_string());
+
+        Ok(Package { id, metadata })
+    }
+
     /// Get a reference to the `Package`'s name.
     pub fn name(&self) -> &str {
         &self.id.name
@@ -49,6 +62,7 @@ impl Display for Package {
     }
 }
 
+#[allow(dead_code)]
 /// A wrapper for implementing iterables on `Package`s.
 struct PackageIter<'a> {
     iter: std::slice::Iter<'a, Package>,
@@ -91,8 +105,12 @@ impl FromStr for Package {
         // A naive approach to parsing the name and `VersionSpecifiers` from the `&str`.
         // Find the first character of the `VersionSpecifiers`. Everything prior is considered
         // the name.
-        let spec_str = parse_version_specifiers_str(s)
-            .expect("package version specifier(s)");
+        let Some(spec_str) = parse_version_specifiers_str(s) else {
+            return Err(Error::InvalidVersionString(format!(
+                "{s} must contain {} specifier",
+                Operator::Equal
+            )));
+        };
         let name = s.strip_suffix(spec_str).unwrap_or(s).to_string();
         let version_specifiers = VersionSpecifiers::from_str(spec_str)?;
 
@@ -113,17 +131,7 @@ impl FromStr for Package {
             )));
         }
 
-        let id = PackageId {
-            name: canonical_package_name(&name)?,
-            version: version_specifer.version().to_owned(),
-        };
-
-        let mut metadata = Metadata::default();
-        metadata.set_project_name(name);
-
-        let package = Package { id, metadata };
-
-        Ok(package)
+        Package::new(&name, version_specifer.version().to_owned())
     }
 }
 
@@ -176,3 +184,39 @@ fn canonical_package_name(name: &str) -> HuakResult<String> {
     let res = re.replace_all(name, "-");
     Ok(res.into_owned())
 }
+
+/// Normalize a name for comparisons (see https://peps.python.org/pep-0503/#normalized-names).
+pub fn normalized_package_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.trim().chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_from_str() {
+        let package = Package::from_str("mock_package==0.0.1").unwrap();
+
+        assert_eq!(package.name(), "mock-package");
+        assert_eq!(package.version(), &Version::from_str("0.0.1").unwrap());
+        assert!(Package::from_str(
+            "-e git+https://github.com/mock/mock-package"
+        )
+        .is_err());
+        assert!(
+            Package::from_str("mock-package @ file:///mock-package").is_err()
+        );
+    }
+}
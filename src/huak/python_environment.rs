@@ -9,8 +9,25 @@ use std::{
     str::FromStr,
 };
 
+use indexmap::IndexMap;
+use pep508_rs::{MarkerEnvironment, Requirement, VersionOrUrl};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
 use crate::{
-    environment::env_path_values, fs, package::Package, sys, version::Version,
+    credentials::{CredentialStore, NetrcFile},
+    distribution::{sdist_version, WheelFilename},
+    environment::env_path_values,
+    fs::{self, TempFile},
+    index::default_index_url,
+    installer::{InstallScheme, Uninstallation},
+    installer_backend::uninstall_natively,
+    lockfile::LockedPackage,
+    package::{normalized_package_name, Package},
+    site_packages::{read_site_packages, InstalledDistribution, SitePackages},
+    sources::NamedIndex,
+    sys,
+    version::Version,
     Config, Error, HuakResult,
 };
 
@@ -18,6 +35,47 @@ const DEFAULT_VENV_NAME: &str = ".venv";
 const VENV_CONFIG_FILE_NAME: &str = "pyvenv.cfg";
 const VIRTUAL_ENV_ENV_VAR: &str = "VIRTUAL_ENV";
 const CONDA_ENV_ENV_VAR: &str = "CONDA_PREFIX";
+/// A Python script printing the interpreter's environment markers as JSON (see https://peps.python.org/pep-0508/#environment-markers).
+const MARKER_ENVIRONMENT_SCRIPT: &str = r#"
+import json, os, platform, sys
+def format_full_version(info):
+    version = "{0.major}.{0.minor}.{0.micro}".format(info)
+    if info.releaselevel != "final":
+        version += info.releaselevel[0] + str(info.serial)
+    return version
+print(json.dumps({
+    "implementation_name": sys.implementation.name,
+    "implementation_version": format_full_version(sys.implementation.version),
+    "os_name": os.name,
+    "platform_machine": platform.machine(),
+    "platform_python_implementation": platform.python_implementation(),
+    "platform_release": platform.release(),
+    "platform_system": platform.system(),
+    "platform_version": platform.version(),
+    "python_full_version": platform.python_version(),
+    "python_version": ".".join(platform.python_version_tuple()[:2]),
+    "sys_platform": sys.platform,
+}))
+"#;
+/// A Python script printing the wheel tags the interpreter supports as JSON, most preferred
+/// first. Only pure Python tags are listed if the packaging library isn't available.
+const SUPPORTED_TAGS_SCRIPT: &str = r#"
+import json, sys
+try:
+    from packaging import tags
+except ImportError:
+    try:
+        from pip._vendor.packaging import tags
+    except ImportError:
+        tags = None
+if tags is None:
+    major, minor = sys.version_info[:2]
+    versions = ["py%d%d" % (major, minor), "py%d" % major]
+    versions += ["py%d%d" % (major, it) for it in range(minor - 1, -1, -1)]
+    print(json.dumps([it + "-none-any" for it in versions]))
+else:
+    print(json.dumps([str(it) for it in tags.sys_tags()]))
+"#;
 
 /// The `PythonEnvironment` is a struct used to intereact with an environment
 /// containing an installed Python `Interpreter` and `Package`s.
@@ -112,7 +170,45 @@ impl PythonEnvironment {
         &self.site_packages_path
     }
 
-    /// Install Python `Package`s to the `PythonEnvironment`.
+    /// Get the `MarkerEnvironment` of the `PythonEnvironment`'s interpreter.
+    pub fn marker_environment(&self) -> HuakResult<MarkerEnvironment> {
+        marker_environment_for_python(self.python_path())
+    }
+
+    /// Get the wheel tags the `PythonEnvironment`'s interpreter supports, most preferred first.
+    pub fn supported_tags(&self) -> HuakResult<Vec<String>> {
+        let mut cmd = Command::new(self.python_path());
+        cmd.args(["-c", SUPPORTED_TAGS_SCRIPT]);
+
+        let output = cmd.output()?;
+        if !output.status.success() {
+            return Err(Error::SubprocessFailure(sys::SubprocessError::new(
+                output.status,
+            )));
+        }
+
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
+
+    /// Get the `InstallScheme` wheels are installed to the `PythonEnvironment` with.
+    pub fn install_scheme(&self) -> InstallScheme {
+        let release = self.interpreter.version.release();
+
+        InstallScheme {
+            site_packages: self.site_packages_path.clone(),
+            scripts: self.executables_dir_path.clone(),
+            headers: self
+                .root
+                .join("include")
+                .join("site")
+                .join(format!("python{}.{}", release[0], release[1])),
+            data: self.root.clone(),
+            python: self.python_path().clone(),
+        }
+    }
+
+    /// Install Python `Package`s to the `PythonEnvironment` with the `InstallerBackend`
+    /// the `Config` selects.
     pub fn install_packages<T>(
         &self,
         packages: &[T],
@@ -122,15 +218,59 @@ impl PythonEnvironment {
     where
         T: Display,
     {
-        let mut cmd = Command::new(self.python_path());
-        cmd.args(["-m", "pip", "install"])
-            .args(packages.iter().map(|item| item.to_string()));
+        let options = config.install_options(options)?;
+        let packages = config.sourced_packages(&to_strings(packages))?;
+        options.check_offline(&packages)?;
 
-        if let Some(v) = options.values.as_ref() {
-            cmd.args(v.iter().map(|item| item.as_str()));
-        }
+        config
+            .installer_backend()?
+            .install_packages(self, &packages, &options, config)
+    }
+
+    /// Install local project directories to the `PythonEnvironment` in editable mode.
+    pub fn install_editable_packages<T>(
+        &self,
+        paths: &[T],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()>
+    where
+        T: AsRef<Path>,
+    {
+        let paths = paths
+            .iter()
+            .map(|it| it.as_ref().to_path_buf())
+            .collect::<Vec<_>>();
+
+        config.installer_backend()?.install_editable_packages(
+            self,
+            &paths,
+            &config.install_options(options)?,
+            config,
+        )
+    }
 
-        config.terminal().run_command(&mut cmd)
+    /// Install `LockedPackage`s to the `PythonEnvironment` exactly as they're pinned.
+    ///
+    /// Hashes are required for every `LockedPackage` that has them and dependencies are
+    /// never resolved.
+    pub fn install_locked_packages(
+        &self,
+        packages: &[&LockedPackage],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()> {
+        let options = config.install_options(options)?;
+        options.check_offline(
+            &packages
+                .iter()
+                .map(|it| it.to_requirement_string())
+                .collect::<Vec<_>>(),
+        )?;
+
+        config
+            .installer_backend()?
+            .install_locked_packages(self, packages, &options, config)
     }
 
     /// Uninstall Python `Package`s from the `PythonEnvironment`.
@@ -143,16 +283,25 @@ impl PythonEnvironment {
     where
         T: Display,
     {
-        let mut cmd = Command::new(self.python_path());
-        cmd.args(["-m", "pip", "uninstall"])
-            .args(packages.iter().map(|item| item.to_string()))
-            .arg("-y");
-
-        if let Some(v) = options.values.as_ref() {
-            cmd.args(v.iter().map(|item| item.as_str()));
-        }
+        config.installer_backend()?.uninstall_packages(
+            self,
+            &to_strings(packages),
+            &config.install_options(options)?,
+            config,
+        )
+    }
 
-        config.terminal().run_command(&mut cmd)
+    /// Get the `Uninstallation`s uninstalling Python `Package`s from the `PythonEnvironment`
+    /// natively would make without removing anything.
+    pub fn uninstall_report<T>(
+        &self,
+        packages: &[T],
+        config: &Config,
+    ) -> HuakResult<Vec<Uninstallation>>
+    where
+        T: Display,
+    {
+        uninstall_natively(self, &to_strings(packages), true, config)
     }
 
     /// Update Python `Package`s installed in the `PythonEnvironment`.
@@ -165,15 +314,129 @@ impl PythonEnvironment {
     where
         T: Display,
     {
+        let options = config.install_options(options)?;
+        let packages = config.sourced_packages(&to_strings(packages))?;
+        options.check_offline(&packages)?;
+
+        config
+            .installer_backend()?
+            .update_packages(self, &packages, &options, config)
+    }
+
+    /// Get pip's `InstallationReport` for Python `Package`s without installing them.
+    ///
+    /// The report is resolved as if nothing was installed in the `PythonEnvironment` and
+    /// without resolving any dependencies of the `Package`s provided.
+    pub fn installation_report<T>(
+        &self,
+        packages: &[T],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<InstallationReport>
+    where
+        T: Display,
+    {
+        let options = config.install_options(options)?;
+        let packages = config.sourced_packages(&to_strings(packages))?;
+        options.check_offline(&packages)?;
+        let (indexed, rest) = options.split_by_package_index(&packages);
+
+        let mut report = None::<InstallationReport>;
+        let batches = indexed
+            .iter()
+            .map(|(url, it)| (options.for_package_index(url), it))
+            .chain(
+                (!rest.is_empty() || indexed.is_empty())
+                    .then(|| (options.clone(), &rest)),
+            );
+        for (options, packages) in batches {
+            let it =
+                self.pip_installation_report(packages, &options, config)?;
+            match report.as_mut() {
+                Some(report) => report.install.extend(it.install),
+                None => report = Some(it),
+            }
+        }
+
+        report.ok_or(Error::InternalError(
+            "no installation report was made".to_string(),
+        ))
+    }
+
+    fn pip_installation_report(
+        &self,
+        packages: &[String],
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<InstallationReport> {
         let mut cmd = Command::new(self.python_path());
-        cmd.args(["-m", "pip", "install", "--upgrade"])
-            .args(packages.iter().map(|item| item.to_string()));
+        cmd.args([
+            "-m",
+            "pip",
+            "install",
+            "--dry-run",
+            "--ignore-installed",
+            "--no-deps",
+            "--quiet",
+            "--report",
+            "-",
+        ])
+        .args(packages)
+        .args(options.args());
+        let _netrc = options.authenticate(&mut cmd)?;
 
-        if let Some(v) = options.values.as_ref() {
-            cmd.args(v.iter().map(|item| item.as_str()));
+        let output = cmd.output()?;
+        if !output.status.success() {
+            let stderr = std::str::from_utf8(&output.stderr)?;
+            config
+                .terminal()
+                .print_error(sys::trim_error_prefix(stderr.trim()))?;
+            return Err(Error::SubprocessFailure(sys::SubprocessError::new(
+                output.status,
+            )));
         }
+        let report = serde_json::from_slice(&output.stdout)?;
 
-        config.terminal().run_command(&mut cmd)
+        Ok(report)
+    }
+
+    /// Download the distributions of Python `Package`s and their dependencies to a
+    /// directory with pip.
+    ///
+    /// Packages with their own package index are downloaded from it first. Their
+    /// dependencies are then downloaded with everything else, using the files downloaded.
+    pub fn download_packages<T>(
+        &self,
+        packages: &[T],
+        dir: &Path,
+        options: &InstallOptions,
+        config: &Config,
+    ) -> HuakResult<()>
+    where
+        T: Display,
+    {
+        let options = config.install_options(options)?;
+        let packages = config.sourced_packages(&to_strings(packages))?;
+        options.check_offline(&packages)?;
+        let (indexed, mut rest) = options.split_by_package_index(&packages);
+
+        let download = |packages: &[String], options: &InstallOptions| {
+            let mut cmd = Command::new(self.python_path());
+            cmd.args(["-m", "pip", "download", "--dest"])
+                .arg(dir)
+                .args(packages)
+                .args(options.args());
+            let _netrc = options.authenticate(&mut cmd)?;
+            config.terminal().run_command(&mut cmd)
+        };
+        for (url, packages) in indexed.iter() {
+            download(packages, &options.for_package_index(url))?;
+            for package in packages {
+                rest.push(downloaded_requirement(package, dir)?);
+            }
+        }
+
+        download(&rest, &options)
     }
 
     /// Check if the `PythonEnvironment` has a module installed in the executables directory.
@@ -196,24 +459,36 @@ impl PythonEnvironment {
     #[allow(dead_code)]
     /// Check if the `PythonEnvironment` has a `Package` already installed.
     pub fn contains_package(&self, package: &Package) -> bool {
-        self.site_packages_dir_path().join(package.name()).exists()
+        let name = normalized_package_name(package.name());
+        self.installed_distributions()
+            .map(|dists| dists.iter().any(|it| it.normalized_name() == name))
+            .unwrap_or_default()
     }
 
     /// Get all of the `Package`s installed in the `PythonEnvironment`.
     pub fn installed_packages(&self) -> HuakResult<Vec<Package>> {
-        let mut cmd = Command::new(self.python_path());
-        cmd.args(["-m", "pip", "freeze"]);
+        self.installed_distributions()?
+            .iter()
+            .map(|it| Package::new(it.name(), it.version().clone()))
+            .collect()
+    }
 
-        let output = cmd.output()?;
-        let output = sys::parse_command_output(output)?;
-        let mut packages = Vec::new();
-        for line in output.split('\n') {
-            if !line.is_empty() {
-                packages.push(Package::from_str(line)?);
-            }
+    /// Get every `InstalledDistribution` in the `PythonEnvironment`'s site-packages directory.
+    /// Distributions that can't be read are skipped.
+    pub fn installed_distributions(
+        &self,
+    ) -> HuakResult<Vec<InstalledDistribution>> {
+        Ok(self.site_packages()?.distributions)
+    }
+
+    /// Read the distributions in the `PythonEnvironment`'s site-packages directory, including
+    /// the ones that can't be read.
+    pub fn site_packages(&self) -> HuakResult<SitePackages> {
+        if !self.site_packages_dir_path().exists() {
+            return Ok(SitePackages::default());
         }
 
-        Ok(packages)
+        read_site_packages(self.site_packages_dir_path())
     }
 
     /// Check if the `PythonEnvironment` is already activated.
@@ -225,6 +500,71 @@ impl PythonEnvironment {
     }
 }
 
+/// Get a requirement as a direct reference to the newest distribution in a directory that
+/// satisfies it. The requirement is kept as it is if there isn't one.
+fn downloaded_requirement(package: &str, dir: &Path) -> HuakResult<String> {
+    let Ok(mut requirement) = Requirement::from_str(package) else {
+        return Ok(package.to_string());
+    };
+    let mut newest: Option<(pep440_rs::Version, PathBuf)> = None;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let filename = path
+            .file_name()
+            .and_then(|it| it.to_str())
+            .unwrap_or_default();
+        let version = match WheelFilename::from_str(filename) {
+            Ok(it)
+                if normalized_package_name(&it.name)
+                    == normalized_package_name(&requirement.name) =>
+            {
+                Some(it.version)
+            }
+            Ok(_) => None,
+            Err(_) => sdist_version(filename, &requirement.name),
+        };
+        let Some(version) = version else {
+            continue;
+        };
+        let satisfies = match requirement.version_or_url.as_ref() {
+            Some(VersionOrUrl::VersionSpecifier(it)) => it.contains(&version),
+            Some(VersionOrUrl::Url(_)) => false,
+            None => true,
+        };
+        if satisfies && newest.as_ref().map_or(true, |(it, _)| &version > it) {
+            newest = Some((version, path));
+        }
+    }
+
+    let Some(url) = newest.and_then(|(_, it)| Url::from_file_path(it).ok())
+    else {
+        return Ok(package.to_string());
+    };
+    requirement.version_or_url = Some(VersionOrUrl::Url(url));
+
+    Ok(requirement.to_string())
+}
+
+/// Check if a package index or find-links location is a remote URL rather than a path.
+pub fn is_remote(location: &str) -> bool {
+    // Windows paths like C:\\wheels parse as URLs with a single letter scheme.
+    Url::parse(location)
+        .map_or(false, |it| it.scheme() != "file" && it.scheme().len() > 1)
+}
+
+/// Check if a pip argument is followed by a package index or find-links location.
+fn is_location_flag(arg: &str) -> bool {
+    matches!(
+        arg,
+        "-i" | "--index-url" | "--extra-index-url" | "-f" | "--find-links"
+    )
+}
+
+/// Convert `Package`s or requirements to strings.
+fn to_strings<T: Display>(items: &[T]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
 /// Helper function for creating a new virtual environment as a `PythonEnvironment`.
 fn new_venv<T: AsRef<Path>>(path: T) -> HuakResult<PythonEnvironment> {
     let root = path.as_ref();
@@ -271,13 +611,468 @@ fn new_venv<T: AsRef<Path>>(path: T) -> HuakResult<PythonEnvironment> {
     Ok(venv)
 }
 
-#[derive(Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
 /// A struct used to configure Python `Package` installations.
+///
+/// The typed options work with every installer and can be set in the `[tool.huak]` table.
+/// `values` are passed to the installer as they are.
 pub struct InstallOptions {
+    /// The URL or path of the package index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_url: Option<String>,
+    /// The URLs or paths of other package indexes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_index_urls: Vec<String>,
+    /// Directories or pages listing distributions to install from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub find_links: Vec<String>,
+    /// Don't install the dependencies of the packages installed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_deps: bool,
+    /// Allow pre-release versions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pre: bool,
+    /// Packages never installed from wheels (`:all:` for every package).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub no_binary: Vec<String>,
+    /// Packages only installed from wheels (`:all:` for every package).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only_binary: Vec<String>,
+    /// Don't use the package cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_cache: bool,
+    /// Hosts trusted even without valid HTTPS.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_hosts: Vec<String>,
+    /// Requirements limiting the versions of packages when they're installed, without
+    /// installing them otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<String>,
+    /// Requirements replacing every requirement on their packages, including the
+    /// requirements of other packages.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<String>,
+    /// The URL of the only index specific packages are installed from, by normalized
+    /// package name (see the `[tool.huak.sources]` table).
+    #[serde(skip)]
+    pub package_index_urls: IndexMap<String, String>,
+    /// The named indexes of the `[tool.huak.indexes]` table, used to find their
+    /// credentials.
+    #[serde(skip)]
+    pub indexes: IndexMap<String, NamedIndex>,
+    /// Install without network access, only from `find_links` directories or the cache.
+    #[serde(skip)]
+    pub offline: bool,
     /// A values vector of install options typically used for passing on arguments.
+    #[serde(skip)]
     pub values: Option<Vec<String>>,
 }
 
+impl InstallOptions {
+    /// Fill in the options not set with `defaults` (like the project's `[tool.huak]`
+    /// options). Lists are combined and `values` are kept as they are.
+    pub fn with_defaults(&self, defaults: &InstallOptions) -> InstallOptions {
+        let combine = |defaults: &[String], values: &[String]| {
+            let mut combined = defaults.to_vec();
+            for it in values {
+                if !combined.contains(it) {
+                    combined.push(it.clone());
+                }
+            }
+            combined
+        };
+
+        InstallOptions {
+            index_url: self.index_url.clone().or(defaults.index_url.clone()),
+            extra_index_urls: combine(
+                &defaults.extra_index_urls,
+                &self.extra_index_urls,
+            ),
+            find_links: combine(&defaults.find_links, &self.find_links),
+            no_deps: self.no_deps || defaults.no_deps,
+            pre: self.pre || defaults.pre,
+            no_binary: combine(&defaults.no_binary, &self.no_binary),
+            only_binary: combine(&defaults.only_binary, &self.only_binary),
+            no_cache: self.no_cache || defaults.no_cache,
+            trusted_hosts: combine(
+                &defaults.trusted_hosts,
+                &self.trusted_hosts,
+            ),
+            constraints: combine(&defaults.constraints, &self.constraints),
+            overrides: combine(&defaults.overrides, &self.overrides),
+            package_index_urls: defaults
+                .package_index_urls
+                .clone()
+                .into_iter()
+                .chain(self.package_index_urls.clone())
+                .collect(),
+            indexes: defaults
+                .indexes
+                .clone()
+                .into_iter()
+                .chain(self.indexes.clone())
+                .collect(),
+            offline: self.offline || defaults.offline,
+            values: self.values.clone(),
+        }
+    }
+
+    /// Split packages into the ones installed from their own package index, by the index's
+    /// URL, and the rest.
+    pub fn split_by_package_index(
+        &self,
+        packages: &[String],
+    ) -> (IndexMap<String, Vec<String>>, Vec<String>) {
+        let mut indexed: IndexMap<String, Vec<String>> = IndexMap::new();
+        let mut rest = Vec::new();
+        for package in packages {
+            let url = Requirement::from_str(package).ok().and_then(|it| {
+                self.package_index_urls
+                    .get(&normalized_package_name(&it.name))
+            });
+            match url {
+                Some(url) => indexed
+                    .entry(url.clone())
+                    .or_default()
+                    .push(package.clone()),
+                None => rest.push(package.clone()),
+            }
+        }
+
+        (indexed, rest)
+    }
+
+    /// Get the URL of the only index a package is installed from. Package indexes aren't
+    /// used offline.
+    pub fn package_index_url(&self, package_name: &str) -> Option<&String> {
+        if self.offline {
+            return None;
+        }
+        self.package_index_urls
+            .get(&normalized_package_name(package_name))
+    }
+
+    /// Check that packages can be installed with the options offline. Remote find-links
+    /// locations and direct references to remote URLs are an `Error::NetworkUnavailable`.
+    pub fn check_offline(&self, packages: &[String]) -> HuakResult<()> {
+        if !self.offline {
+            return Ok(());
+        }
+        if let Some(it) = self.find_links.iter().find(|it| is_remote(it)) {
+            return Err(Error::NetworkUnavailable(format!(
+                "finding distributions at {it}"
+            )));
+        }
+        for package in packages {
+            let Ok(requirement) = Requirement::from_str(package) else {
+                continue;
+            };
+            if let Some(VersionOrUrl::Url(url)) = requirement.version_or_url {
+                if is_remote(url.as_str()) {
+                    return Err(Error::NetworkUnavailable(format!(
+                        "installing {package}"
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the options for installing packages from only the package index at a URL.
+    /// Their dependencies aren't installed, since they could come from other indexes.
+    pub fn for_package_index(&self, url: &str) -> InstallOptions {
+        InstallOptions {
+            index_url: Some(url.to_string()),
+            extra_index_urls: Vec::new(),
+            find_links: Vec::new(),
+            no_deps: true,
+            package_index_urls: IndexMap::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Get the `CredentialStore` for the package indexes of the options.
+    pub fn credential_store(&self) -> HuakResult<CredentialStore> {
+        CredentialStore::new(&self.indexes)
+    }
+
+    /// Give a pip-compatible command the credentials of the options' package indexes
+    /// and find-links locations with a temporary netrc file, so they never appear in its
+    /// arguments. The `NetrcFile` needs to be kept until the command has run.
+    pub fn authenticate(
+        &self,
+        cmd: &mut Command,
+    ) -> HuakResult<Option<NetrcFile>> {
+        if self.offline {
+            return Ok(None);
+        }
+        let index_url =
+            self.index_url.clone().unwrap_or_else(default_index_url);
+        let mut urls = vec![index_url.as_str()];
+        urls.extend(self.extra_index_urls.iter().map(String::as_str));
+        urls.extend(self.find_links.iter().map(String::as_str));
+        urls.extend(self.package_index_urls.values().map(String::as_str));
+        let mut values = self.values.iter().flatten();
+        while let Some(value) = values.next() {
+            match value.split_once('=') {
+                Some((flag, url)) if is_location_flag(flag) => urls.push(url),
+                _ if is_location_flag(value) => {
+                    urls.extend(values.next().map(String::as_str))
+                }
+                _ => (),
+            }
+        }
+
+        self.credential_store()?.authenticate(cmd, urls)
+    }
+
+    /// Pass the options' constraints to a pip-compatible command with a temporary
+    /// constraints file. Overrides are passed with `override_flag`, or are an
+    /// `Error::Unimplemented` if the command doesn't support them. The `TempFile`s need to
+    /// be kept until the command has run.
+    pub fn constrain(
+        &self,
+        cmd: &mut Command,
+        override_flag: Option<&str>,
+    ) -> HuakResult<Vec<TempFile>> {
+        let mut files = Vec::new();
+        if !self.constraints.is_empty() {
+            let file =
+                TempFile::new("constraints.txt", &self.constraints.join("\n"))?;
+            cmd.arg("--constraint").arg(file.path());
+            files.push(file);
+        }
+        if !self.overrides.is_empty() {
+            let Some(flag) = override_flag else {
+                return Err(Error::Unimplemented(
+                    "overriding requirements with pip (use the native or uv installer)"
+                        .to_string(),
+                ));
+            };
+            let file =
+                TempFile::new("overrides.txt", &self.overrides.join("\n"))?;
+            cmd.arg(flag).arg(file.path());
+            files.push(file);
+        }
+
+        Ok(files)
+    }
+
+    /// Get the options as pip's install arguments followed by the `values`. uv's pip
+    /// interface accepts the same arguments.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut push = |flag: &str, value: &str| {
+            args.push(flag.to_string());
+            args.push(value.to_string());
+        };
+        // Package indexes aren't used offline.
+        if !self.offline {
+            if let Some(it) = self.index_url.as_ref() {
+                push("--index-url", it);
+            }
+            for it in self.extra_index_urls.iter() {
+                push("--extra-index-url", it);
+            }
+        }
+        for it in self.find_links.iter() {
+            push("--find-links", it);
+        }
+        for it in self.no_binary.iter() {
+            push("--no-binary", it);
+        }
+        for it in self.only_binary.iter() {
+            push("--only-binary", it);
+        }
+        for it in self.trusted_hosts.iter() {
+            push("--trusted-host", it);
+        }
+        for (flag, enabled) in [
+            ("--no-deps", self.no_deps),
+            ("--pre", self.pre),
+            ("--no-cache-dir", self.no_cache),
+            ("--no-index", self.offline),
+        ] {
+            if enabled {
+                args.push(flag.to_string());
+            }
+        }
+        args.extend(self.values.iter().flatten().cloned());
+
+        args
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+/// A report of what pip would install (see https://pip.pypa.io/en/stable/reference/installation-report/).
+pub struct InstallationReport {
+    /// The distributions that would be installed.
+    #[serde(default)]
+    pub install: Vec<InstallationReportItem>,
+    /// The environment markers pip evaluated requirements against.
+    #[serde(default)]
+    pub environment: IndexMap<String, String>,
+}
+
+impl InstallationReport {
+    /// Get the `MarkerEnvironment` the `InstallationReport` was resolved for.
+    pub fn marker_environment(&self) -> HuakResult<MarkerEnvironment> {
+        marker_environment_from_values(&self.environment)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+/// A distribution listed in an `InstallationReport`.
+pub struct InstallationReportItem {
+    /// The core metadata of the distribution.
+    pub metadata: InstallationReportMetadata,
+    /// Information about where the distribution would be downloaded from.
+    pub download_info: DirectUrl,
+    /// Whether the distribution was explicitly requested.
+    #[serde(default)]
+    pub requested: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+/// The subset of core metadata Huak uses from an `InstallationReportItem`.
+pub struct InstallationReportMetadata {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub requires_dist: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// Data recording where a distribution came from (https://peps.python.org/pep-0610/).
+pub struct DirectUrl {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_info: Option<ArchiveInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vcs_info: Option<VcsInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir_info: Option<DirInfo>,
+}
+
+impl DirectUrl {
+    /// Get the URL of a PEP 508 direct reference to the distribution. Vcs URLs are pinned
+    /// to the commit that was installed.
+    pub fn requirement_url(&self) -> String {
+        match self.vcs_info.as_ref() {
+            Some(it) => format!("{}+{}@{}", it.vcs, self.url, it.commit_id),
+            None => self.url.clone(),
+        }
+    }
+
+    /// Get the hashes of the archive formatted as `<algorithm>:<digest>`.
+    pub fn hashes(&self) -> Vec<String> {
+        let Some(info) = self.archive_info.as_ref() else {
+            return Vec::new();
+        };
+
+        if !info.hashes.is_empty() {
+            return info
+                .hashes
+                .iter()
+                .map(|(algorithm, digest)| format!("{algorithm}:{digest}"))
+                .collect();
+        }
+
+        // The legacy `hash` field is formatted as `<algorithm>=<digest>`.
+        info.hash
+            .as_ref()
+            .map(|it| vec![it.replacen('=', ":", 1)])
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub hashes: IndexMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VcsInfo {
+    pub vcs: String,
+    pub commit_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requested_revision: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DirInfo {
+    #[serde(default)]
+    pub editable: bool,
+}
+
+/// Get the `MarkerEnvironment` of the Python interpreter at a path.
+pub fn marker_environment_for_python<T: AsRef<Path>>(
+    path: T,
+) -> HuakResult<MarkerEnvironment> {
+    let mut cmd = Command::new(path.as_ref());
+    cmd.args(["-c", MARKER_ENVIRONMENT_SCRIPT]);
+
+    let output = cmd.output()?;
+    if !output.status.success() {
+        return Err(Error::SubprocessFailure(sys::SubprocessError::new(
+            output.status,
+        )));
+    }
+    let values = serde_json::from_slice(&output.stdout)?;
+
+    marker_environment_from_values(&values)
+}
+
+/// Describe a `MarkerEnvironment` for display (e.g. "CPython 3.11.4 on linux").
+pub fn marker_environment_description(env: &MarkerEnvironment) -> String {
+    format!(
+        "{} {} on {}",
+        env.platform_python_implementation,
+        env.python_full_version.0,
+        env.sys_platform
+    )
+}
+
+/// Initialize a `MarkerEnvironment` from environment marker names mapped to their values.
+pub fn marker_environment_from_values(
+    values: &IndexMap<String, String>,
+) -> HuakResult<MarkerEnvironment> {
+    let value = |key: &str| -> HuakResult<String> {
+        values.get(key).cloned().ok_or(Error::InternalError(format!(
+            "environment marker {key} could not be found"
+        )))
+    };
+    let version = |key: &str| -> HuakResult<(String, pep440_rs::Version)> {
+        let it = value(key)?;
+        let version = pep440_rs::Version::from_str(&it)
+            .map_err(Error::InvalidVersionString)?;
+        Ok((it, version))
+    };
+
+    let env = MarkerEnvironment {
+        implementation_name: value("implementation_name")?,
+        implementation_version: version("implementation_version")?,
+        os_name: value("os_name")?,
+        platform_machine: value("platform_machine")?,
+        platform_python_implementation: value(
+            "platform_python_implementation",
+        )?,
+        platform_release: value("platform_release")?,
+        platform_system: value("platform_system")?,
+        platform_version: value("platform_version")?,
+        python_full_version: version("python_full_version")?,
+        python_version: version("python_version")?,
+        sys_platform: value("sys_platform")?,
+    };
+
+    Ok(env)
+}
+
 /// Python virtual environment configuration data (pyvenv.cfg).
 ///
 /// See https://docs.python.org/3/library/venv.html.
@@ -294,7 +1089,10 @@ impl VenvConfig {
             panic!("failed to open {}", value.as_ref().display())
         });
         let buff_reader = BufReader::new(file);
-        let lines = buff_reader.lines().flatten().collect::<Vec<String>>();
+        let lines = buff_reader
+            .lines()
+            .map_while(Result::ok)
+            .collect::<Vec<String>>();
 
         // Search for version = "X.X.X"
         let mut version = Version::from_str("0.0.0");
@@ -402,6 +1200,11 @@ impl Interpreter {
     pub fn version(&self) -> &Version {
         &self.version
     }
+
+    /// Get the `MarkerEnvironment` of the `Interpreter`.
+    pub fn marker_environment(&self) -> HuakResult<MarkerEnvironment> {
+        marker_environment_for_python(self.path())
+    }
 }
 
 impl Display for Interpreter {
@@ -471,8 +1274,7 @@ pub fn default_venv_name() -> &'static str {
 /// Get an `Iterator` over available Python `Interpreter` paths parsed from the `PATH`
 /// environment variable (inspired by brettcannon/python-launcher).
 pub fn python_paths() -> impl Iterator<Item = (Option<Version>, PathBuf)> {
-    let paths =
-        fs::flatten_directories(env_path_values().unwrap_or(Vec::new()));
+    let paths = fs::flatten_directories(env_path_values().unwrap_or_default());
 
     python_interpreters_in_paths(paths)
 }
@@ -581,10 +1383,70 @@ pub fn parse_python_version_from_command<T: AsRef<Path>>(
 mod tests {
     use tempfile::tempdir;
 
-    use crate::TerminalOptions;
+    use crate::{environment::Environment, TerminalOptions};
 
     use super::*;
 
+    #[test]
+    fn install_options_with_defaults() {
+        let defaults = InstallOptions {
+            index_url: Some("https://project".to_string()),
+            extra_index_urls: vec!["https://extra".to_string()],
+            no_cache: true,
+            ..Default::default()
+        };
+        let options = InstallOptions {
+            extra_index_urls: vec![
+                "https://extra".to_string(),
+                "https://cli".to_string(),
+            ],
+            pre: true,
+            only_binary: vec![":all:".to_string()],
+            trusted_hosts: vec!["mock.host".to_string()],
+            values: Some(vec!["--no-index".to_string()]),
+            ..Default::default()
+        };
+        let options = options.with_defaults(&defaults);
+
+        assert_eq!(options.index_url.as_deref(), Some("https://project"));
+        assert_eq!(
+            options.args().join(" "),
+            "--index-url https://project --extra-index-url https://extra \
+--extra-index-url https://cli --only-binary :all: --trusted-host mock.host --pre \
+--no-cache-dir --no-index"
+        );
+    }
+
+    #[test]
+    fn install_options_offline() {
+        let mut options = InstallOptions {
+            index_url: Some("https://project".to_string()),
+            find_links: vec!["./wheelhouse".to_string()],
+            offline: true,
+            ..Default::default()
+        };
+        options
+            .package_index_urls
+            .insert("ourlib".to_string(), "https://internal".to_string());
+
+        assert_eq!(
+            options.args().join(" "),
+            "--find-links ./wheelhouse --no-index"
+        );
+        assert!(options.package_index_url("ourlib").is_none());
+        assert!(options.check_offline(&["click==8.1.3".to_string()]).is_ok());
+        assert!(matches!(
+            options.check_offline(&[
+                "ourlib @ git+https://github.com/example/ourlib".to_string()
+            ]),
+            Err(Error::NetworkUnavailable(_))
+        ));
+        options
+            .find_links
+            .push("https://example.com/wheels".to_string());
+        assert!(options.check_offline(&[]).is_err());
+    }
+
     #[test]
     fn python_environment_executables_dir_name() {
         let dir = tempdir().unwrap();
@@ -594,6 +1456,8 @@ mod tests {
             terminal_options: TerminalOptions {
                 verbosity: sys::Verbosity::Quiet,
             },
+            installer: None,
+            offline: false,
         };
         let ws = config.workspace();
         let venv = ws.resolve_python_environment().unwrap();
@@ -612,12 +1476,25 @@ mod tests {
         assert!(path.exists());
     }
 
+    #[test]
+    fn python_marker_environment() {
+        let environment = Environment::new();
+        let interpreter = environment.interpreters().latest().unwrap();
+        let env = interpreter.marker_environment().unwrap();
+
+        assert_eq!(
+            env.python_version.1.release,
+            interpreter.version().release()[..2]
+        );
+        assert!(!env.sys_platform.is_empty());
+    }
+
     #[cfg(unix)]
     #[test]
     fn python_search() {
         let dir = tempdir().unwrap();
         std::fs::write(dir.path().join("python3.11"), "").unwrap();
-        let path_vals = vec![dir.path().to_str().unwrap().to_string()];
+        let path_vals = [dir.path().to_str().unwrap().to_string()];
         std::env::set_var("PATH", path_vals.join(":"));
         let mut interpreter_paths = python_paths();
 
@@ -0,0 +1,878 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    rc::Rc,
+};
+
+use indexmap::IndexMap;
+use pep440_rs::Version;
+use pep508_rs::{MarkerEnvironment, Requirement, VersionOrUrl};
+
+use crate::{
+    distribution::CoreMetadata,
+    index::{DistributionFile, PackageIndex},
+    package::normalized_package_name,
+    Error, HuakResult,
+};
+
+/// The most versions of a package listed when explaining why none of them could be used.
+const MAX_EXPLAINED_VERSIONS: usize = 3;
+
+#[derive(Default)]
+/// A struct used to configure dependency resolution.
+pub struct ResolverOptions {
+    /// Allow pre-release versions to be selected for every package.
+    pub prereleases: bool,
+    /// Versions to try first for packages (like the versions already installed) keyed by
+    /// their normalized names.
+    pub preferences: HashMap<String, Version>,
+    /// Only resolve the requirements given, not the requirements of the packages selected.
+    pub no_deps: bool,
+    /// Requirements limiting the versions of packages when they're needed, without
+    /// requiring them otherwise.
+    pub constraints: Vec<Requirement>,
+    /// Requirements replacing every other requirement on their packages.
+    pub overrides: Vec<Requirement>,
+}
+
+/// A dependency resolver for PEP 508 `Requirement`s.
+///
+/// Packages are decided one at a time, trying the newest compatible version first. Every
+/// requirement remembers which decision introduced it, so when no version of a package
+/// can be used the resolver backjumps straight to a decision that contributed to the
+/// conflict (like PubGrub) and keeps a derivation of the conflict to explain it.
+pub struct Resolver<'a> {
+    index: &'a dyn PackageIndex,
+    env: &'a MarkerEnvironment,
+    options: ResolverOptions,
+    /// The overrides whose markers apply to the environment.
+    overrides: Vec<Requirement>,
+    files: RefCell<HashMap<String, Rc<Vec<DistributionFile>>>>,
+    metadata: RefCell<HashMap<(String, Version), Rc<CoreMetadata>>>,
+}
+
+impl<'a> Resolver<'a> {
+    /// Initialize a `Resolver` for a `PackageIndex` and the `MarkerEnvironment` of the
+    /// interpreter the resolution is for.
+    pub fn new(
+        index: &'a dyn PackageIndex,
+        env: &'a MarkerEnvironment,
+        options: ResolverOptions,
+    ) -> Resolver<'a> {
+        let overrides = options
+            .overrides
+            .iter()
+            .filter(|it| it.evaluate_markers(env, Vec::new()))
+            .cloned()
+            .collect();
+
+        Resolver {
+            index,
+            env,
+            options,
+            overrides,
+            files: RefCell::new(HashMap::new()),
+            metadata: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Get the project's constraints on a package as `Constraint`s.
+    fn project_constraints(&self, name: &str) -> Vec<Constraint> {
+        self.options
+            .constraints
+            .iter()
+            .filter(|it| {
+                normalized_package_name(&it.name) == name
+                    && it.evaluate_markers(self.env, Vec::new())
+            })
+            .map(|it| Constraint {
+                requirement: it.clone(),
+                origin: Origin::Constraints,
+                overridden: None,
+            })
+            .collect()
+    }
+
+    /// Resolve every package needed to satisfy the `Requirement`s.
+    pub fn resolve(
+        &self,
+        requirements: &[Requirement],
+    ) -> HuakResult<Resolution> {
+        let mut state = State::default();
+        for requirement in requirements
+            .iter()
+            .filter(|it| it.evaluate_markers(self.env, Vec::new()))
+        {
+            let constraint = Constraint {
+                requirement: requirement.clone(),
+                origin: Origin::Root,
+                overridden: None,
+            };
+            if let Err(conflict) =
+                self.add_constraint(&mut state, constraint)?
+            {
+                return Err(Error::DependencyResolutionFailure(
+                    conflict.cause.to_string(),
+                ));
+            }
+        }
+
+        let state = match self.solve(state)? {
+            Ok(it) => it,
+            Err(conflict) => {
+                return Err(Error::DependencyResolutionFailure(
+                    conflict.cause.to_string(),
+                ))
+            }
+        };
+
+        let mut packages = Vec::new();
+        for (name, decision) in state.decisions {
+            let metadata = self
+                .metadata(&name, &decision.version)?
+                .map_err(Error::DependencyResolutionFailure)?;
+            let files = self
+                .files(&name)?
+                .iter()
+                .filter(|file| file.version == decision.version)
+                .cloned()
+                .collect();
+            let requires = metadata
+                .requires_dist
+                .iter()
+                .filter(|it| {
+                    it.evaluate_markers(self.env, decision.extras.clone())
+                })
+                .cloned()
+                .collect();
+
+            packages.push(ResolvedPackage {
+                name: metadata.name.clone(),
+                version: decision.version,
+                extras: decision.extras,
+                requires,
+                files,
+            });
+        }
+        packages.sort_by(|a, b| {
+            normalized_package_name(&a.name)
+                .cmp(&normalized_package_name(&b.name))
+        });
+
+        Ok(Resolution { packages })
+    }
+
+    /// Decide the next undecided package, recursing until every package is decided or a
+    /// `Conflict` is found.
+    fn solve(&self, state: State) -> HuakResult<Result<State, Conflict>> {
+        let Some(name) = state
+            .constraints
+            .keys()
+            .find(|it| !state.decisions.contains_key(*it))
+            .cloned()
+        else {
+            return Ok(Ok(state));
+        };
+
+        let constraints = &state.constraints[&name];
+        let candidates = self.candidates(&name, constraints)?;
+        let mut culprits = constraint_culprits(constraints);
+        if candidates.is_empty() {
+            let found = !self.files(&name)?.is_empty();
+            return Ok(Err(Conflict {
+                culprits,
+                cause: Cause::no_versions(&name, constraints, found),
+            }));
+        }
+
+        let extras = constraints
+            .iter()
+            .flat_map(|it| it.requirement.extras.clone().unwrap_or_default())
+            .fold(Vec::new(), |mut acc, it| {
+                if !acc.contains(&it) {
+                    acc.push(it);
+                }
+                acc
+            });
+
+        let mut failures = Vec::new();
+        for version in candidates {
+            let metadata = match self.metadata(&name, &version)? {
+                Ok(it) => it,
+                Err(reason) => {
+                    failures.push((version, Cause::Unavailable(reason)));
+                    continue;
+                }
+            };
+
+            let mut next = state.clone();
+            next.decisions.insert(
+                name.clone(),
+                Decision {
+                    version: version.clone(),
+                    extras: extras.clone(),
+                },
+            );
+            let origin =
+                Origin::Package(metadata.name.clone(), version.clone());
+            let mut result = Ok(());
+            for requirement in metadata.requires_dist.iter().filter(|it| {
+                !self.options.no_deps
+                    && it.evaluate_markers(self.env, extras.clone())
+            }) {
+                result = self.add_constraint(
+                    &mut next,
+                    Constraint {
+                        requirement: requirement.clone(),
+                        origin: origin.clone(),
+                        overridden: None,
+                    },
+                )?;
+                if result.is_err() {
+                    break;
+                }
+            }
+
+            let result = match result {
+                Ok(()) => self.solve(next)?,
+                Err(conflict) => Err(conflict),
+            };
+            match result {
+                Ok(solution) => return Ok(Ok(solution)),
+                // A different version of this package can't fix a conflict it didn't
+                // contribute to, so jump back to a decision that did.
+                Err(conflict) if !conflict.culprits.contains(&name) => {
+                    return Ok(Err(conflict))
+                }
+                Err(conflict) => {
+                    culprits.extend(
+                        conflict.culprits.into_iter().filter(|it| it != &name),
+                    );
+                    failures.push((version, conflict.cause));
+                }
+            }
+        }
+
+        Ok(Err(Conflict {
+            culprits,
+            cause: Cause::AllVersionsFailed {
+                package: name,
+                failures,
+            },
+        }))
+    }
+
+    /// Add a `Constraint` to the `State`, checking it against the package's decision if it
+    /// has already been decided.
+    fn add_constraint(
+        &self,
+        state: &mut State,
+        mut constraint: Constraint,
+    ) -> HuakResult<Result<(), Conflict>> {
+        if let Some(it) =
+            override_requirement(&constraint.requirement, &self.overrides)
+        {
+            constraint.overridden =
+                Some(std::mem::replace(&mut constraint.requirement, it));
+        }
+        let requirement = &constraint.requirement;
+        if let Some(VersionOrUrl::Url(url)) =
+            requirement.version_or_url.as_ref()
+        {
+            return Err(Error::Unimplemented(format!(
+                "resolving direct URL requirements ({} @ {url})",
+                requirement.name
+            )));
+        }
+
+        let name = normalized_package_name(&requirement.name);
+        let new_extras = requirement.extras.clone().unwrap_or_default();
+        state
+            .constraints
+            .entry(name.clone())
+            .or_insert_with(|| self.project_constraints(&name))
+            .push(constraint.clone());
+
+        let Some(decision) = state.decisions.get(&name).cloned() else {
+            return Ok(Ok(()));
+        };
+
+        if !is_satisfied_by(&constraint.requirement, &decision.version) {
+            let constraints = &state.constraints[&name];
+            let mut culprits = constraint_culprits(constraints);
+            culprits.insert(name.clone());
+            return Ok(Err(Conflict {
+                culprits,
+                cause: Cause::no_versions(&name, constraints, true),
+            }));
+        }
+
+        // Extras requested after the package was decided add requirements of their own.
+        let added = new_extras
+            .into_iter()
+            .filter(|it| !decision.extras.contains(it))
+            .collect::<Vec<_>>();
+        if added.is_empty() || self.options.no_deps {
+            return Ok(Ok(()));
+        }
+        if let Some(it) = state.decisions.get_mut(&name) {
+            it.extras.extend(added.iter().cloned());
+        }
+
+        let metadata = match self.metadata(&name, &decision.version)? {
+            Ok(it) => it,
+            Err(reason) => {
+                return Err(Error::DependencyResolutionFailure(reason))
+            }
+        };
+        let origin =
+            Origin::Package(metadata.name.clone(), decision.version.clone());
+        for requirement in metadata.requires_dist.iter().filter(|it| {
+            it.evaluate_markers(self.env, added.clone())
+                && !it.evaluate_markers(self.env, Vec::new())
+        }) {
+            let result = self.add_constraint(
+                state,
+                Constraint {
+                    requirement: requirement.clone(),
+                    origin: origin.clone(),
+                    overridden: None,
+                },
+            )?;
+            if result.is_err() {
+                return Ok(result);
+            }
+        }
+
+        Ok(Ok(()))
+    }
+
+    /// Get the versions of a package that satisfy its `Constraint`s, newest first.
+    fn candidates(
+        &self,
+        name: &str,
+        constraints: &[Constraint],
+    ) -> HuakResult<Vec<Version>> {
+        let files = self.files(name)?;
+        let python_version = &self.env.python_full_version.1;
+
+        let mut versions = files
+            .iter()
+            .filter(|file| {
+                !file.yanked
+                    && file
+                        .requires_python
+                        .as_ref()
+                        .map_or(true, |it| it.contains(python_version))
+            })
+            .map(|file| file.version.clone())
+            .filter(|version| {
+                constraints
+                    .iter()
+                    .all(|it| is_satisfied_by(&it.requirement, version))
+            })
+            .collect::<Vec<_>>();
+        versions.sort();
+        versions.dedup();
+        versions.reverse();
+
+        // Pre-releases are only used if they're allowed, requested explicitly, or are all
+        // that's left (see https://peps.python.org/pep-0440/#handling-of-pre-releases).
+        let requested_prerelease = constraints.iter().any(|it| {
+            match it.requirement.version_or_url.as_ref() {
+                Some(VersionOrUrl::VersionSpecifier(specifiers)) => {
+                    specifiers.iter().any(|it| it.version().any_prerelease())
+                }
+                _ => false,
+            }
+        });
+        if !self.options.prereleases
+            && !requested_prerelease
+            && versions.iter().any(|it| !it.any_prerelease())
+        {
+            versions.retain(|it| !it.any_prerelease());
+        }
+
+        if let Some(preferred) = self.options.preferences.get(name) {
+            if let Some(i) = versions.iter().position(|it| it == preferred) {
+                let it = versions.remove(i);
+                versions.insert(0, it);
+            }
+        }
+
+        Ok(versions)
+    }
+
+    /// Get the `DistributionFile`s of a package from the `PackageIndex`.
+    fn files(&self, name: &str) -> HuakResult<Rc<Vec<DistributionFile>>> {
+        if let Some(files) = self.files.borrow().get(name) {
+            return Ok(files.clone());
+        }
+
+        let files = Rc::new(self.index.files(name)?);
+        self.files
+            .borrow_mut()
+            .insert(name.to_string(), files.clone());
+
+        Ok(files)
+    }
+
+    /// Get the `CoreMetadata` of a version of a package from the `PackageIndex`. If the
+    /// metadata can't be read the reason is returned instead, unless reading it isn't
+    /// supported (like for source distributions), which is an `Error::Unimplemented`.
+    fn metadata(
+        &self,
+        name: &str,
+        version: &Version,
+    ) -> HuakResult<Result<Rc<CoreMetadata>, String>> {
+        let key = (name.to_string(), version.clone());
+        if let Some(metadata) = self.metadata.borrow().get(&key) {
+            return Ok(Ok(metadata.clone()));
+        }
+
+        // Prefer files with metadata that can be read without downloading the distribution.
+        let files = self.files(name)?;
+        let mut files = files
+            .iter()
+            .filter(|file| &file.version == version)
+            .collect::<Vec<_>>();
+        files.sort_by_key(|file| {
+            (file.metadata_url.is_none(), !file.is_wheel())
+        });
+
+        let mut reason = format!("{name} {version} has no distribution files");
+        let mut unsupported = None;
+        for file in files {
+            match self.index.metadata(file) {
+                // The requirements of a version that can't all be parsed aren't resolved.
+                Ok(metadata) if !metadata.invalid_requires_dist.is_empty() => {
+                    reason = format!(
+                        "{}: invalid requirement {}",
+                        file.filename, metadata.invalid_requires_dist[0]
+                    )
+                }
+                Ok(metadata) => {
+                    let metadata = Rc::new(metadata);
+                    self.metadata.borrow_mut().insert(key, metadata.clone());
+                    return Ok(Ok(metadata));
+                }
+                Err(Error::Unimplemented(it)) => {
+                    unsupported.get_or_insert(it);
+                }
+                Err(e) => reason = format!("{}: {e}", file.filename),
+            }
+        }
+        if let Some(it) = unsupported {
+            return Err(Error::Unimplemented(it));
+        }
+
+        Ok(Err(reason))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The packages selected by a `Resolver`.
+pub struct Resolution {
+    packages: Vec<ResolvedPackage>,
+}
+
+impl Resolution {
+    /// Get a reference to the `ResolvedPackage`s sorted by name.
+    pub fn packages(&self) -> &[ResolvedPackage] {
+        &self.packages
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A package selected by a `Resolver`.
+pub struct ResolvedPackage {
+    /// The name of the package.
+    pub name: String,
+    /// The selected PEP 440 `Version`.
+    pub version: Version,
+    /// The extras of the package that were requested.
+    pub extras: Vec<String>,
+    /// The requirements of the package that apply to the environment.
+    pub requires: Vec<Requirement>,
+    /// The `DistributionFile`s of the selected version.
+    pub files: Vec<DistributionFile>,
+}
+
+#[derive(Clone, Default)]
+/// The decisions and constraints made while resolving.
+struct State {
+    decisions: IndexMap<String, Decision>,
+    constraints: IndexMap<String, Vec<Constraint>>,
+}
+
+#[derive(Clone)]
+struct Decision {
+    version: Version,
+    extras: Vec<String>,
+}
+
+#[derive(Clone)]
+/// A `Requirement` and where it came from.
+struct Constraint {
+    requirement: Requirement,
+    origin: Origin,
+    /// The requirement an override replaced.
+    overridden: Option<Requirement>,
+}
+
+#[derive(Clone)]
+enum Origin {
+    /// The requirements being resolved.
+    Root,
+    /// The project's constraints.
+    Constraints,
+    /// The requirements of a decided version of a package.
+    Package(String, Version),
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Root => write!(f, "the project"),
+            Origin::Constraints => write!(f, "the project's constraints"),
+            Origin::Package(name, version) => write!(f, "{name} {version}"),
+        }
+    }
+}
+
+/// A failure to resolve, along with the packages whose decisions contributed to it.
+struct Conflict {
+    culprits: HashSet<String>,
+    cause: Cause,
+}
+
+/// Why a package couldn't be resolved.
+enum Cause {
+    /// No version of the package satisfies every requirement for it.
+    NoVersions {
+        package: String,
+        requirements: Vec<String>,
+        found: bool,
+    },
+    /// Every version of the package that satisfied its requirements led to a conflict.
+    AllVersionsFailed {
+        package: String,
+        failures: Vec<(Version, Cause)>,
+    },
+    /// The metadata of a version couldn't be read.
+    Unavailable(String),
+}
+
+impl Cause {
+    fn no_versions(
+        package: &str,
+        constraints: &[Constraint],
+        found: bool,
+    ) -> Cause {
+        let mut requirements = Vec::new();
+        for constraint in constraints {
+            let mut requirement = constraint.requirement.clone();
+            requirement.marker = None;
+            let overridden = match constraint.overridden {
+                Some(_) => ", overridden by the project",
+                None => "",
+            };
+            let line = format!(
+                "{requirement} (required by {}{overridden})",
+                constraint.origin
+            );
+            if !requirements.contains(&line) {
+                requirements.push(line);
+            }
+        }
+
+        Cause::NoVersions {
+            package: package.to_string(),
+            requirements,
+            found,
+        }
+    }
+
+    fn write_indented(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        indent: usize,
+    ) -> std::fmt::Result {
+        let pad = " ".repeat(indent);
+        match self {
+            Cause::NoVersions {
+                package,
+                requirements,
+                found,
+            } => {
+                if *found {
+                    writeln!(f, "{pad}no version of {package} satisfies:")?;
+                } else {
+                    writeln!(
+                        f,
+                        "{pad}{package} could not be found in the package index but is required by:"
+                    )?;
+                }
+                for line in requirements {
+                    writeln!(f, "{pad}  {line}")?;
+                }
+                Ok(())
+            }
+            Cause::AllVersionsFailed { package, failures } => {
+                writeln!(f, "{pad}no version of {package} can be used:")?;
+                for (version, cause) in
+                    failures.iter().take(MAX_EXPLAINED_VERSIONS)
+                {
+                    writeln!(f, "{pad}  {package} {version} fails because")?;
+                    cause.write_indented(f, indent + 4)?;
+                }
+                if failures.len() > MAX_EXPLAINED_VERSIONS {
+                    writeln!(
+                        f,
+                        "{pad}  ...and {} older versions fail for similar reasons",
+                        failures.len() - MAX_EXPLAINED_VERSIONS
+                    )?;
+                }
+                Ok(())
+            }
+            Cause::Unavailable(reason) => {
+                writeln!(f, "{pad}its metadata could not be read ({reason})")
+            }
+        }
+    }
+}
+
+impl Display for Cause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+/// Get the names of the packages that introduced `Constraint`s.
+fn constraint_culprits(constraints: &[Constraint]) -> HashSet<String> {
+    constraints
+        .iter()
+        .filter_map(|it| match &it.origin {
+            Origin::Root | Origin::Constraints => None,
+            Origin::Package(name, _) => Some(normalized_package_name(name)),
+        })
+        .collect()
+}
+
+/// Get the override replacing a `Requirement`, if one of the `overrides` is for its
+/// package. The requirement's extras are kept.
+pub fn override_requirement(
+    requirement: &Requirement,
+    overrides: &[Requirement],
+) -> Option<Requirement> {
+    let name = normalized_package_name(&requirement.name);
+    let it = overrides
+        .iter()
+        .find(|it| normalized_package_name(&it.name) == name)?;
+
+    Some(Requirement {
+        extras: requirement.extras.clone(),
+        marker: requirement.marker.clone(),
+        ..it.clone()
+    })
+}
+
+/// Check if a version satisfies a `Requirement`'s version specifiers.
+pub fn is_satisfied_by(requirement: &Requirement, version: &Version) -> bool {
+    match requirement.version_or_url.as_ref() {
+        Some(VersionOrUrl::VersionSpecifier(specifiers)) => {
+            specifiers.contains(version)
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        index::SimpleIndex, test_marker_environment, test_resources_dir_path,
+    };
+    use std::str::FromStr;
+    use url::Url;
+
+    fn mock_index() -> SimpleIndex {
+        let path = test_resources_dir_path().join("mock-index");
+        SimpleIndex::new(Url::from_directory_path(path).unwrap().as_str())
+            .unwrap()
+    }
+
+    fn requirements(reqs: &[&str]) -> Vec<Requirement> {
+        reqs.iter()
+            .map(|it| Requirement::from_str(it).unwrap())
+            .collect()
+    }
+
+    fn versions(resolution: &Resolution) -> Vec<String> {
+        resolution
+            .packages()
+            .iter()
+            .map(|it| format!("{}=={}", it.name, it.version))
+            .collect()
+    }
+
+    #[test]
+    fn resolve_with_backtracking() {
+        let index = mock_index();
+        let env = test_marker_environment();
+        let resolver = Resolver::new(&index, &env, ResolverOptions::default());
+
+        let resolution =
+            resolver.resolve(&requirements(&["mock-web"])).unwrap();
+
+        // mock-web 2.0.0 needs mock-utils>=2.0 but every usable mock-db needs mock-utils<2.
+        assert_eq!(
+            versions(&resolution),
+            [
+                "mock-db==1.0.0",
+                "mock-speedups==0.1.0",
+                "mock-utils==1.5.0",
+                "mock-web==1.0.0"
+            ]
+        );
+        let packages = resolution.packages();
+        assert_eq!(packages[0].name, "mock-db");
+        assert_eq!(packages[0].extras, ["fast"]);
+        assert_eq!(packages[3].files.len(), 2);
+    }
+
+    #[test]
+    fn resolve_source_distributions() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("mock-sdist")).unwrap();
+        std::fs::write(
+            dir.path().join("mock-sdist").join("index.html"),
+            r#"<a href="mock_sdist-1.0.0.tar.gz">mock_sdist-1.0.0.tar.gz</a>"#,
+        )
+        .unwrap();
+        let index = SimpleIndex::new(
+            Url::from_directory_path(dir.path()).unwrap().as_str(),
+        )
+        .unwrap();
+        let env = test_marker_environment();
+        let resolver = Resolver::new(&index, &env, ResolverOptions::default());
+
+        // Installers that can build the source distribution can take over.
+        assert!(matches!(
+            resolver.resolve(&requirements(&["mock-sdist"])),
+            Err(Error::Unimplemented(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_prereleases() {
+        let index = mock_index();
+        let env = test_marker_environment();
+        let resolver = Resolver::new(&index, &env, ResolverOptions::default());
+
+        let stable = resolver.resolve(&requirements(&["mock-utils"])).unwrap();
+        let pre = resolver
+            .resolve(&requirements(&["mock-utils>2.0.0"]))
+            .unwrap();
+
+        assert_eq!(versions(&stable), ["mock-utils==2.0.0"]);
+        assert_eq!(versions(&pre), ["mock-utils==2.1.0b1"]);
+    }
+
+    #[test]
+    fn resolve_preferences_and_no_deps() {
+        let index = mock_index();
+        let env = test_marker_environment();
+        let options = ResolverOptions {
+            preferences: [(
+                "mock-utils".to_string(),
+                Version::from_str("1.0.0").unwrap(),
+            )]
+            .into_iter()
+            .collect(),
+            no_deps: true,
+            ..Default::default()
+        };
+        let resolver = Resolver::new(&index, &env, options);
+
+        let resolution =
+            resolver.resolve(&requirements(&["mock-utils"])).unwrap();
+
+        assert_eq!(versions(&resolution), ["mock-utils==1.0.0"]);
+    }
+
+    #[test]
+    fn resolve_constraints_and_overrides() {
+        let index = mock_index();
+        let env = test_marker_environment();
+        let options = ResolverOptions {
+            constraints: requirements(&["mock-utils<1.5", "mock-web<2"]),
+            ..Default::default()
+        };
+        let constrained = Resolver::new(&index, &env, options);
+        let options = ResolverOptions {
+            overrides: requirements(&["mock-utils>=2"]),
+            ..Default::default()
+        };
+        let overridden = Resolver::new(&index, &env, options);
+
+        // Constraints only limit the packages that are needed.
+        assert_eq!(
+            versions(
+                &constrained
+                    .resolve(&requirements(&["mock-db==1.0.0"]))
+                    .unwrap()
+            ),
+            ["mock-db==1.0.0", "mock-utils==1.0.0"]
+        );
+        // The override replaces mock-db's mock-utils<2.
+        assert_eq!(
+            versions(
+                &overridden
+                    .resolve(&requirements(&["mock-db==1.0.0"]))
+                    .unwrap()
+            ),
+            ["mock-db==1.0.0", "mock-utils==2.0.0"]
+        );
+        let err = constrained
+            .resolve(&requirements(&["mock-utils>=2"]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(
+            "mock-utils <1.5 (required by the project's constraints)"
+        ));
+    }
+
+    #[test]
+    fn resolve_conflict_explanation() {
+        let index = mock_index();
+        let env = test_marker_environment();
+        let resolver = Resolver::new(&index, &env, ResolverOptions::default());
+
+        let err = resolver
+            .resolve(&requirements(&["mock-web>=2", "mock-utils>=2"]))
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("no version of mock-web can be used"));
+        assert!(err.contains("no version of mock-db can be used"));
+        assert!(err.contains("mock-utils <2 (required by mock-db 1.0.0)"));
+        assert!(err.contains("mock-utils >=2 (required by the project)"));
+    }
+
+    #[test]
+    fn resolve_missing_package() {
+        let index = mock_index();
+        let env = test_marker_environment();
+        let resolver = Resolver::new(&index, &env, ResolverOptions::default());
+
+        let err = resolver
+            .resolve(&requirements(&["missing-package"]))
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("missing-package could not be found"));
+        assert!(err.contains("(required by the project)"));
+    }
+}
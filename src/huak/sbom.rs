@@ -0,0 +1,438 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{licenses::License, package::normalized_package_name};
+
+const SOURCE_DATE_EPOCH_ENV_VAR: &str = "SOURCE_DATE_EPOCH";
+
+/// A software bill of materials for a project and the packages it's distributed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sbom {
+    /// The project the SBOM describes.
+    pub root: SbomPackage,
+    pub packages: Vec<SbomPackage>,
+    /// When the SBOM was created formatted as RFC 3339.
+    pub timestamp: String,
+}
+
+/// A package in an `Sbom`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbomPackage {
+    pub name: String,
+    pub version: Option<String>,
+    /// Hashes of the package's distribution formatted as `<algorithm>:<digest>`.
+    pub hashes: Vec<String>,
+    pub license: License,
+    /// Where the package's distribution was downloaded from.
+    pub source: Option<String>,
+    /// The normalized names of the packages the package depends on.
+    pub dependencies: Vec<String>,
+}
+
+impl SbomPackage {
+    /// Get the package URL of the package (see https://github.com/package-url/purl-spec).
+    pub fn purl(&self) -> String {
+        let name = normalized_package_name(&self.name);
+        match self.version.as_ref() {
+            Some(it) => format!(
+                "pkg:pypi/{name}@{}",
+                it.replace('!', "%21").replace('+', "%2B")
+            ),
+            None => format!("pkg:pypi/{name}"),
+        }
+    }
+
+    fn spdx_id(&self) -> String {
+        let id = format!(
+            "{}-{}",
+            normalized_package_name(&self.name),
+            self.version.as_deref().unwrap_or_default()
+        );
+        let id = id
+            .chars()
+            .map(|it| match it.is_ascii_alphanumeric() || it == '.' {
+                true => it,
+                false => '-',
+            })
+            .collect::<String>();
+
+        format!("SPDXRef-Package-{}", id.trim_end_matches('-'))
+    }
+
+    /// Get the source of the package if it's a remote URL. Local paths aren't shared.
+    fn download_url(&self) -> Option<&str> {
+        self.source.as_deref().filter(|it| {
+            it.starts_with("https://")
+                || it.starts_with("http://")
+                || it.starts_with("git+")
+        })
+    }
+
+    /// Get the hashes as pairs of algorithm and hex digest.
+    fn hashes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.hashes.iter().filter_map(|it| it.split_once(':'))
+    }
+}
+
+impl Sbom {
+    /// Get the `SbomPackage`s an `SbomPackage` depends on that are in the `Sbom`.
+    fn dependencies<'a>(
+        &'a self,
+        package: &'a SbomPackage,
+    ) -> impl Iterator<Item = &'a SbomPackage> {
+        self.packages.iter().filter(|it| {
+            package
+                .dependencies
+                .contains(&normalized_package_name(&it.name))
+        })
+    }
+
+    /// Get an id for the document that's unique to its contents and timestamp.
+    fn uuid(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.timestamp.as_bytes());
+        for it in std::iter::once(&self.root).chain(self.packages.iter()) {
+            hasher.update(it.purl().as_bytes());
+            for hash in it.hashes.iter() {
+                hasher.update(hash.as_bytes());
+            }
+        }
+        let mut bytes = hasher.finalize()[..16].to_vec();
+        // Format the digest as a custom (version 8) RFC 9562 UUID.
+        bytes[6] = (bytes[6] & 0x0f) | 0x80;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        let hex = bytes
+            .iter()
+            .map(|it| format!("{it:02x}"))
+            .collect::<String>();
+
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+
+    /// Format the `Sbom` as a CycloneDX 1.5 JSON document.
+    pub fn to_cyclonedx_json(&self) -> Value {
+        let components = self
+            .packages
+            .iter()
+            .map(|it| {
+                let mut component = cyclonedx_component(it, "library");
+                let hashes = it
+                    .hashes()
+                    .filter_map(|(algorithm, digest)| {
+                        let (name, _) = hash_algorithm(algorithm)?;
+                        Some(json!({ "alg": name, "content": digest }))
+                    })
+                    .collect::<Vec<_>>();
+                if !hashes.is_empty() {
+                    component["hashes"] = json!(hashes);
+                }
+                let licenses = match (it.license.spdx_expression(), &it.license)
+                {
+                    (Some(expression), _) => {
+                        json!([{ "expression": expression }])
+                    }
+                    (None, License::Unknown) => Value::Null,
+                    (None, license) => {
+                        json!([{ "license": { "name": license.to_string() } }])
+                    }
+                };
+                if !licenses.is_null() {
+                    component["licenses"] = licenses;
+                }
+                if let Some(url) = it.download_url() {
+                    component["externalReferences"] =
+                        json!([{ "type": "distribution", "url": url }]);
+                }
+                component
+            })
+            .collect::<Vec<_>>();
+        let dependencies = std::iter::once(&self.root)
+            .chain(self.packages.iter())
+            .map(|it| {
+                json!({
+                    "ref": it.purl(),
+                    "dependsOn": self
+                        .dependencies(it)
+                        .map(SbomPackage::purl)
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "serialNumber": format!("urn:uuid:{}", self.uuid()),
+            "version": 1,
+            "metadata": {
+                "timestamp": self.timestamp,
+                "tools": {
+                    "components": [{
+                        "type": "application",
+                        "name": "huak",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    }],
+                },
+                "component": cyclonedx_component(&self.root, "application"),
+            },
+            "components": components,
+            "dependencies": dependencies,
+        })
+    }
+
+    /// Format the `Sbom` as an SPDX 2.3 JSON document.
+    pub fn to_spdx_json(&self) -> Value {
+        let packages = std::iter::once(&self.root)
+            .chain(self.packages.iter())
+            .map(|it| {
+                let mut package = json!({
+                    "name": it.name,
+                    "SPDXID": it.spdx_id(),
+                    "downloadLocation": it.download_url().unwrap_or("NOASSERTION"),
+                    "filesAnalyzed": false,
+                    "licenseConcluded": "NOASSERTION",
+                    "licenseDeclared": it
+                        .license
+                        .spdx_expression()
+                        .unwrap_or("NOASSERTION".to_string()),
+                    "externalRefs": [{
+                        "referenceCategory": "PACKAGE-MANAGER",
+                        "referenceType": "purl",
+                        "referenceLocator": it.purl(),
+                    }],
+                });
+                if let Some(version) = it.version.as_ref() {
+                    package["versionInfo"] = json!(version);
+                }
+                let checksums = it
+                    .hashes()
+                    .filter_map(|(algorithm, digest)| {
+                        let (_, name) = hash_algorithm(algorithm)?;
+                        Some(json!({ "algorithm": name, "checksumValue": digest }))
+                    })
+                    .collect::<Vec<_>>();
+                if !checksums.is_empty() {
+                    package["checksums"] = json!(checksums);
+                }
+                if it.license.spdx_expression().is_none()
+                    && it.license != License::Unknown
+                {
+                    package["licenseComments"] =
+                        json!(format!("Declared as {}", it.license));
+                }
+                package
+            })
+            .collect::<Vec<_>>();
+
+        let mut relationships = vec![json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": self.root.spdx_id(),
+        })];
+        for it in std::iter::once(&self.root).chain(self.packages.iter()) {
+            for dependency in self.dependencies(it) {
+                relationships.push(json!({
+                    "spdxElementId": it.spdx_id(),
+                    "relationshipType": "DEPENDS_ON",
+                    "relatedSpdxElement": dependency.spdx_id(),
+                }));
+            }
+        }
+
+        let name = match self.root.version.as_ref() {
+            Some(version) => format!("{}-{version}", self.root.name),
+            None => self.root.name.clone(),
+        };
+        json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": name,
+            "documentNamespace": format!(
+                "https://spdx.org/spdxdocs/{name}-{}",
+                self.uuid()
+            ),
+            "creationInfo": {
+                "created": self.timestamp,
+                "creators": [format!("Tool: huak-{}", env!("CARGO_PKG_VERSION"))],
+            },
+            "packages": packages,
+            "relationships": relationships,
+        })
+    }
+}
+
+fn cyclonedx_component(package: &SbomPackage, kind: &str) -> Value {
+    let mut component = json!({
+        "type": kind,
+        "bom-ref": package.purl(),
+        "name": package.name,
+        "purl": package.purl(),
+    });
+    if let Some(version) = package.version.as_ref() {
+        component["version"] = json!(version);
+    }
+
+    component
+}
+
+/// Get the CycloneDX and SPDX names of a hash algorithm.
+fn hash_algorithm(algorithm: &str) -> Option<(&str, &str)> {
+    match algorithm {
+        "md5" => Some(("MD5", "MD5")),
+        "sha1" => Some(("SHA-1", "SHA1")),
+        "sha256" => Some(("SHA-256", "SHA256")),
+        "sha384" => Some(("SHA-384", "SHA384")),
+        "sha512" => Some(("SHA-512", "SHA512")),
+        _ => None,
+    }
+}
+
+/// Get the time an SBOM is created at formatted as RFC 3339. `SOURCE_DATE_EPOCH` is used if
+/// it's set so that SBOMs can be reproduced.
+pub fn sbom_timestamp() -> String {
+    let seconds = std::env::var(SOURCE_DATE_EPOCH_ENV_VAR)
+        .ok()
+        .and_then(|it| it.trim().parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|it| it.as_secs())
+                .unwrap_or_default()
+        });
+
+    rfc3339(seconds)
+}
+
+/// Format seconds since the Unix epoch as an RFC 3339 UTC timestamp.
+fn rfc3339(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64;
+    let time = seconds % 86400;
+
+    // Convert the days to a civil date (see http://howardhinnant.github.io/date_algorithms.html).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_sbom() -> Sbom {
+        let package = |name: &str, version: &str, deps: &[&str]| SbomPackage {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+            hashes: Vec::new(),
+            license: License::Unknown,
+            source: None,
+            dependencies: deps.iter().map(ToString::to_string).collect(),
+        };
+        let mut web = package("mock-web", "2.0+local", &["mock-utils"]);
+        web.hashes = vec!["sha256:abc123".to_string(), "blake2:ff".to_string()];
+        web.license = License::Classifiers(vec!["GPL-3.0-only".to_string()]);
+        web.source =
+            Some("https://files.mock.index/mock_web-2.0.whl".to_string());
+        let mut utils = package("mock_utils", "1.0.0", &[]);
+        utils.license = License::Text("Mock License".to_string());
+
+        Sbom {
+            root: package("mock-project", "0.0.1", &["mock-web", "missing"]),
+            packages: vec![utils, web],
+            timestamp: rfc3339(1_700_000_000),
+        }
+    }
+
+    #[test]
+    fn sbom_cyclonedx_json() {
+        let bom = mock_sbom().to_cyclonedx_json();
+        let web = &bom["components"][1];
+
+        assert_eq!(bom["metadata"]["timestamp"], "2023-11-14T22:13:20Z");
+        assert_eq!(
+            bom["metadata"]["component"]["purl"],
+            "pkg:pypi/mock-project@0.0.1"
+        );
+        assert_eq!(web["purl"], "pkg:pypi/mock-web@2.0%2Blocal");
+        assert_eq!(
+            web["hashes"],
+            json!([{ "alg": "SHA-256", "content": "abc123" }])
+        );
+        assert_eq!(web["licenses"], json!([{ "expression": "GPL-3.0-only" }]));
+        assert_eq!(
+            bom["components"][0]["licenses"],
+            json!([{ "license": { "name": "Mock License" } }])
+        );
+        assert_eq!(
+            bom["dependencies"][0],
+            json!({
+                "ref": "pkg:pypi/mock-project@0.0.1",
+                "dependsOn": ["pkg:pypi/mock-web@2.0%2Blocal"],
+            })
+        );
+        assert!(bom["serialNumber"]
+            .as_str()
+            .unwrap()
+            .starts_with("urn:uuid:"));
+    }
+
+    #[test]
+    fn sbom_spdx_json() {
+        let doc = mock_sbom().to_spdx_json();
+        let packages = doc["packages"].as_array().unwrap();
+
+        assert_eq!(doc["name"], "mock-project-0.0.1");
+        assert_eq!(packages[0]["SPDXID"], "SPDXRef-Package-mock-project-0.0.1");
+        assert_eq!(packages[1]["licenseDeclared"], "NOASSERTION");
+        assert_eq!(packages[1]["licenseComments"], "Declared as Mock License");
+        assert_eq!(
+            packages[2]["checksums"],
+            json!([{ "algorithm": "SHA256", "checksumValue": "abc123" }])
+        );
+        assert_eq!(
+            packages[2]["downloadLocation"],
+            "https://files.mock.index/mock_web-2.0.whl"
+        );
+        assert_eq!(
+            doc["relationships"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|it| it["relatedSpdxElement"].as_str().unwrap())
+                .collect::<Vec<_>>(),
+            [
+                "SPDXRef-Package-mock-project-0.0.1",
+                "SPDXRef-Package-mock-web-2.0-local",
+                "SPDXRef-Package-mock-utils-1.0.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn sbom_rfc3339() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+    }
+}
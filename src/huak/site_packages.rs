@@ -0,0 +1,504 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+use pep440_rs::Version;
+use pep508_rs::Requirement;
+use url::Url;
+
+use crate::{
+    distribution::CoreMetadata,
+    package::normalized_package_name,
+    python_environment::{DirInfo, DirectUrl},
+    Error, HuakResult,
+};
+
+/// A distribution installed to a site-packages directory.
+///
+/// Installed distributions are read from their `*.dist-info` directory (see
+/// https://packaging.python.org/en/latest/specifications/recording-installed-packages/)
+/// or a legacy `*.egg-info` directory or file. Projects installed in develop mode are read
+/// from the `*.egg-info` their `*.egg-link` points to.
+#[derive(Debug, Clone)]
+pub struct InstalledDistribution {
+    /// The path to the distribution's `*.dist-info` or `*.egg-info`.
+    pub path: PathBuf,
+    /// The core metadata of the distribution.
+    pub metadata: CoreMetadata,
+    /// The name of the tool that installed the distribution.
+    pub installer: Option<String>,
+    /// Where the distribution was installed from if it was a direct reference (see
+    /// https://peps.python.org/pep-0610/).
+    pub direct_url: Option<DirectUrl>,
+    /// Whether the distribution was explicitly requested rather than installed as a
+    /// dependency.
+    pub requested: bool,
+}
+
+impl InstalledDistribution {
+    /// Initialize an `InstalledDistribution` from the path to its `*.dist-info`,
+    /// `*.egg-info`, or `*.egg-link`.
+    pub fn new<T: AsRef<Path>>(path: T) -> HuakResult<InstalledDistribution> {
+        let path = path.as_ref();
+
+        if is_egg_link(path) {
+            return InstalledDistribution::from_egg_link(path);
+        }
+
+        if is_dist_info(path) {
+            let metadata = CoreMetadata::from_str(&fs::read_to_string(
+                path.join("METADATA"),
+            )?)?;
+            let installer = fs::read_to_string(path.join("INSTALLER"))
+                .ok()
+                .map(|it| it.trim().to_string())
+                .filter(|it| !it.is_empty());
+            let direct_url = fs::read(path.join("direct_url.json"))
+                .ok()
+                .and_then(|it| serde_json::from_slice(&it).ok());
+
+            return Ok(InstalledDistribution {
+                path: path.to_path_buf(),
+                metadata,
+                installer,
+                direct_url,
+                requested: path.join("REQUESTED").exists(),
+            });
+        }
+
+        // Legacy distributions keep their metadata in a PKG-INFO file (or the egg-info itself)
+        // and their requirements in requires.txt.
+        let mut metadata = if path.is_dir() {
+            CoreMetadata::from_str(&fs::read_to_string(path.join("PKG-INFO"))?)?
+        } else {
+            CoreMetadata::from_str(&fs::read_to_string(path)?)?
+        };
+        if let Ok(it) = fs::read_to_string(path.join("requires.txt")) {
+            (metadata.requires_dist, metadata.invalid_requires_dist) =
+                egg_info_requirements(&it);
+        }
+
+        Ok(InstalledDistribution {
+            path: path.to_path_buf(),
+            metadata,
+            installer: None,
+            direct_url: None,
+            requested: false,
+        })
+    }
+
+    /// Initialize an editable `InstalledDistribution` from the `*.egg-link` of a project
+    /// installed in develop mode. The link's first line is the directory containing the
+    /// project's `*.egg-info` and its second is the project's directory relative to that.
+    fn from_egg_link(path: &Path) -> HuakResult<InstalledDistribution> {
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines().map(str::trim);
+        let dir = path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(lines.next().unwrap_or_default());
+        let root = normalize_path(&dir.join(lines.next().unwrap_or(".")));
+
+        let name = path
+            .file_stem()
+            .map(|it| normalized_package_name(&it.to_string_lossy()));
+        let egg_info = fs::read_dir(&dir)?
+            .filter_map(|it| Some(it.ok()?.path()))
+            .find(|it| {
+                is_egg_info(it)
+                    && it
+                        .file_stem()
+                        .and_then(|it| it.to_str())
+                        .and_then(|it| it.split('-').next())
+                        .map(normalized_package_name)
+                        == name
+            })
+            .ok_or(Error::MetadataFileNotFound)?;
+
+        let mut dist = InstalledDistribution::new(egg_info)?;
+        dist.direct_url = Url::from_file_path(root).ok().map(|it| DirectUrl {
+            url: it.to_string(),
+            archive_info: None,
+            vcs_info: None,
+            dir_info: Some(DirInfo { editable: true }),
+        });
+
+        Ok(dist)
+    }
+
+    /// Get the name of the `InstalledDistribution`.
+    pub fn name(&self) -> &str {
+        &self.metadata.name
+    }
+
+    /// Get the normalized name of the `InstalledDistribution` (see
+    /// https://peps.python.org/pep-0503/#normalized-names).
+    pub fn normalized_name(&self) -> String {
+        normalized_package_name(&self.metadata.name)
+    }
+
+    /// Get the PEP 440 `Version` of the `InstalledDistribution`.
+    pub fn version(&self) -> &Version {
+        &self.metadata.version
+    }
+
+    /// Check if the `InstalledDistribution` was installed in editable mode.
+    pub fn is_editable(&self) -> bool {
+        self.direct_url
+            .as_ref()
+            .and_then(|it| it.dir_info.as_ref())
+            .map_or(false, |it| it.editable)
+    }
+
+    /// Get the files installed with the `InstalledDistribution` from its RECORD (or a legacy
+    /// installed-files.txt). Paths are relative to the site-packages directory.
+    pub fn record(&self) -> HuakResult<Vec<RecordEntry>> {
+        if is_dist_info(&self.path) {
+            let contents = fs::read_to_string(self.path.join("RECORD"))?;
+            return Ok(contents
+                .lines()
+                .filter_map(RecordEntry::from_line)
+                .collect());
+        }
+
+        // The paths of installed-files.txt are relative to the egg-info directory.
+        let contents =
+            fs::read_to_string(self.path.join("installed-files.txt"))?;
+        let dir = PathBuf::from(self.path.file_name().unwrap_or_default());
+        Ok(contents
+            .lines()
+            .filter(|it| !it.trim().is_empty())
+            .map(|it| RecordEntry {
+                path: normalize_path(&dir.join(it.trim())),
+                hash: None,
+                size: None,
+            })
+            .collect())
+    }
+}
+
+/// A file listed in an `InstalledDistribution`'s RECORD.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordEntry {
+    /// The path of the file relative to the site-packages directory.
+    pub path: PathBuf,
+    /// The hash of the file formatted as `<algorithm>=<digest>`.
+    pub hash: Option<String>,
+    /// The size of the file in bytes.
+    pub size: Option<u64>,
+}
+
+impl RecordEntry {
+    /// Parse a `RecordEntry` from a line of a RECORD file.
+    pub fn from_line(line: &str) -> Option<RecordEntry> {
+        // Hashes and sizes never contain commas, but paths may be quoted CSV fields that do.
+        let mut fields = line.trim_end().rsplitn(3, ',');
+        let size = fields.next()?;
+        let hash = fields.next()?;
+        let path = fields.next()?;
+        let path =
+            match path.strip_prefix('"').and_then(|it| it.strip_suffix('"')) {
+                Some(it) => it.replace("\"\"", "\""),
+                None => path.to_string(),
+            };
+
+        Some(RecordEntry {
+            path: PathBuf::from(path),
+            hash: (!hash.is_empty()).then(|| hash.to_string()),
+            size: size.parse().ok(),
+        })
+    }
+
+    /// Format the `RecordEntry` as a line of a RECORD file.
+    pub fn to_line(&self) -> String {
+        let path = self
+            .path
+            .components()
+            .map(|it| it.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let path = if path.contains(',') || path.contains('"') {
+            format!("\"{}\"", path.replace('"', "\"\""))
+        } else {
+            path
+        };
+
+        format!(
+            "{path},{},{}",
+            self.hash.as_deref().unwrap_or_default(),
+            self.size.map(|it| it.to_string()).unwrap_or_default()
+        )
+    }
+}
+
+/// The distributions read from a site-packages directory.
+#[derive(Debug, Default)]
+pub struct SitePackages {
+    /// The distributions that could be read sorted by name.
+    pub distributions: Vec<InstalledDistribution>,
+    /// The paths of the distributions with missing or invalid metadata and why they
+    /// couldn't be read.
+    pub unreadable: Vec<(PathBuf, Error)>,
+}
+
+impl SitePackages {
+    /// Get a warning for each distribution that couldn't be read and each requirement of a
+    /// distribution that couldn't be parsed.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = self
+            .unreadable
+            .iter()
+            .map(|(path, e)| format!("skipped {} ({e})", path.display()))
+            .collect::<Vec<_>>();
+        for dist in self.distributions.iter() {
+            warnings.extend(dist.metadata.invalid_requires_dist.iter().map(
+                |it| {
+                    format!(
+                        "skipped the invalid requirement {it} of {}",
+                        dist.name()
+                    )
+                },
+            ));
+        }
+
+        warnings
+    }
+}
+
+/// Read every distribution installed to a site-packages directory.
+pub fn read_site_packages<T: AsRef<Path>>(path: T) -> HuakResult<SitePackages> {
+    let mut site_packages = SitePackages::default();
+
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if !is_dist_info(&path) && !is_egg_info(&path) && !is_egg_link(&path) {
+            continue;
+        }
+        match InstalledDistribution::new(&path) {
+            Ok(it) => site_packages.distributions.push(it),
+            Err(e) => site_packages.unreadable.push((path, e)),
+        }
+    }
+    site_packages
+        .distributions
+        .sort_by_key(|it| it.normalized_name());
+
+    Ok(site_packages)
+}
+
+/// Get every distribution installed to a site-packages directory sorted by name.
+///
+/// Distributions with missing or invalid metadata are skipped. Use `read_site_packages` to
+/// report them.
+pub fn installed_distributions<T: AsRef<Path>>(
+    path: T,
+) -> HuakResult<Vec<InstalledDistribution>> {
+    Ok(read_site_packages(path)?.distributions)
+}
+
+fn is_dist_info(path: &Path) -> bool {
+    path.is_dir() && path.extension().map_or(false, |it| it == "dist-info")
+}
+
+fn is_egg_info(path: &Path) -> bool {
+    path.extension().map_or(false, |it| it == "egg-info")
+}
+
+fn is_egg_link(path: &Path) -> bool {
+    path.is_file() && path.extension().map_or(false, |it| it == "egg-link")
+}
+
+/// Parse the requirements of a legacy requires.txt file. Lines that can't be parsed are
+/// returned separately.
+///
+/// Requirements listed under a `[extra]`, `[extra:marker]` or `[:marker]` section apply only
+/// to that extra and marker.
+fn egg_info_requirements(contents: &str) -> (Vec<Requirement>, Vec<String>) {
+    let mut requirements = Vec::new();
+    let mut invalid = Vec::new();
+    let mut marker = None;
+
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(section) =
+            line.strip_prefix('[').and_then(|it| it.strip_suffix(']'))
+        {
+            let (extra, env) = section.split_once(':').unwrap_or((section, ""));
+            marker = match (extra.is_empty(), env.is_empty()) {
+                (true, true) => None,
+                (false, true) => Some(format!("extra == '{extra}'")),
+                (true, false) => Some(env.to_string()),
+                (false, false) => {
+                    Some(format!("({env}) and extra == '{extra}'"))
+                }
+            };
+            continue;
+        }
+        let requirement = match marker.as_ref() {
+            Some(it) => format!("{line} ; {it}"),
+            None => line.to_string(),
+        };
+        match Requirement::from_str(&requirement) {
+            Ok(it) => requirements.push(it),
+            Err(_) => invalid.push(line.to_string()),
+        }
+    }
+
+    (requirements, invalid)
+}
+
+/// Lexically resolve `..` and `.` components of a path.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            Component::CurDir => (),
+            it => normalized.push(it),
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn site_packages_installed_distributions() {
+        let dists = installed_distributions(
+            crate::test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dists.iter().map(|it| it.name()).collect::<Vec<_>>(),
+            ["Mock.Legacy", "mock_utils", "mock-web"]
+        );
+
+        let utils = &dists[1];
+        assert_eq!(utils.normalized_name(), "mock-utils");
+        assert_eq!(utils.version(), &Version::from_str("1.0.0").unwrap());
+        assert_eq!(utils.installer.as_deref(), Some("pip"));
+        assert!(!utils.requested);
+        assert!(!utils.is_editable());
+
+        let web = &dists[2];
+        assert!(web.requested);
+        assert!(web.is_editable());
+        assert_eq!(web.metadata.requires_dist[0].name, "mock-utils");
+    }
+
+    #[test]
+    fn site_packages_unreadable_distributions() {
+        let site_packages = read_site_packages(
+            crate::test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+
+        assert_eq!(site_packages.distributions.len(), 3);
+        assert_eq!(site_packages.unreadable.len(), 1);
+        assert!(site_packages.unreadable[0]
+            .0
+            .ends_with("mock_broken-0.1.dist-info"));
+        let warnings = site_packages.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("skipped "));
+    }
+
+    #[test]
+    fn site_packages_egg_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let site_packages = dir.path().join("site-packages");
+        let project = dir.path().join("mock-dev");
+        fs::create_dir_all(project.join("src/mock_dev.egg-info")).unwrap();
+        fs::create_dir(&site_packages).unwrap();
+        fs::write(
+            project.join("src/mock_dev.egg-info/PKG-INFO"),
+            "Metadata-Version: 2.1\nName: mock-dev\nVersion: 0.1.0\n",
+        )
+        .unwrap();
+        fs::write(
+            site_packages.join("mock-dev.egg-link"),
+            format!("{}\n../\n", project.join("src").display()),
+        )
+        .unwrap();
+        fs::write(
+            site_packages.join("mock-gone.egg-link"),
+            format!("{}\n.\n", dir.path().join("mock-gone").display()),
+        )
+        .unwrap();
+
+        let site_packages = read_site_packages(site_packages).unwrap();
+
+        assert_eq!(site_packages.distributions.len(), 1);
+        let dist = &site_packages.distributions[0];
+        assert_eq!(dist.name(), "mock-dev");
+        assert!(dist.is_editable());
+        assert_eq!(
+            dist.direct_url.as_ref().unwrap().url,
+            Url::from_file_path(&project).unwrap().as_str()
+        );
+        assert_eq!(site_packages.unreadable.len(), 1);
+        assert!(site_packages.unreadable[0]
+            .0
+            .ends_with("mock-gone.egg-link"));
+    }
+
+    #[test]
+    fn site_packages_record() {
+        let dists = installed_distributions(
+            crate::test_resources_dir_path().join("mock-site-packages"),
+        )
+        .unwrap();
+
+        let record = dists[1].record().unwrap();
+        assert_eq!(record[0].path, PathBuf::from("mock_utils/__init__.py"));
+        assert_eq!(record[0].size, Some(22));
+        assert!(record[0].hash.as_ref().unwrap().starts_with("sha256="));
+        assert_eq!(record[1].path, PathBuf::from("mock_utils/a,b.py"));
+        assert_eq!(record.last().unwrap().hash, None);
+
+        let line = record[1].to_line();
+        assert!(line.starts_with("\"mock_utils/a,b.py\",sha256="));
+        assert_eq!(RecordEntry::from_line(&line).unwrap(), record[1]);
+
+        let record = dists[0].record().unwrap();
+        assert_eq!(record[0].path, PathBuf::from("mock_legacy/__init__.py"));
+    }
+
+    #[test]
+    fn egg_info_requires() {
+        let (requirements, invalid) = egg_info_requirements(
+            r#"mock-utils>=1.0
+
+[speedups]
+mock-speedups
+mock-broken >=
+
+[:python_version < "3.11"]
+tomli
+"#,
+        );
+
+        assert_eq!(requirements.len(), 3);
+        assert_eq!(invalid, ["mock-broken >="]);
+        assert_eq!(
+            requirements[1].to_string(),
+            "mock-speedups ; extra == 'speedups'"
+        );
+        assert_eq!(
+            requirements[2].to_string(),
+            "tomli ; python_version < '3.11'"
+        );
+    }
+}
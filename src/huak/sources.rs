@@ -0,0 +1,231 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use indexmap::IndexMap;
+use pep508_rs::{Requirement, VersionOrUrl};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{package::normalized_package_name, Error, HuakResult};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// A package index named in the `[tool.huak.indexes]` table.
+///
+/// ```toml
+/// [tool.huak.indexes]
+/// mirror = { url = "https://mirror.example.com/simple", default = true }
+/// internal = { url = "https://pypi.example.com/simple" }
+/// ```
+pub struct NamedIndex {
+    /// The URL or path of the index.
+    pub url: String,
+    /// Use the index instead of PyPI for every package without a source. Other named
+    /// indexes are only used for the packages whose source names them.
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+/// Where a package is installed from, set in the `[tool.huak.sources]` table.
+///
+/// ```toml
+/// [tool.huak.sources]
+/// ourlib = { index = "internal" }
+/// ourtools = { path = "../ourtools" }
+/// ourapi = { git = "https://github.com/example/ourapi", rev = "v1.2.0" }
+/// ```
+pub enum PackageSource {
+    /// A package index from the `[tool.huak.indexes]` table.
+    Index { index: String },
+    /// A local wheel or project directory, relative to the project's root.
+    Path { path: PathBuf },
+    /// A git repository, checked out at `rev` if it's given.
+    Git { git: String, rev: Option<String> },
+}
+
+impl PackageSource {
+    /// Get the URL of a direct reference to the `PackageSource`. Paths are resolved from
+    /// `root`. Packages from an index aren't direct references.
+    pub fn url(&self, root: &Path) -> HuakResult<Option<Url>> {
+        match self {
+            PackageSource::Index { .. } => Ok(None),
+            PackageSource::Path { path } => {
+                let path = std::fs::canonicalize(root.join(path))
+                    .map_err(|_| Error::InvalidDependencyPath(path.clone()))?;
+                Url::from_file_path(&path)
+                    .map(Some)
+                    .map_err(|_| Error::InvalidDependencyPath(path))
+            }
+            PackageSource::Git { git, rev } => {
+                let mut url = match git.strip_prefix("git+") {
+                    Some(_) => git.clone(),
+                    None => format!("git+{git}"),
+                };
+                if let Some(rev) = rev {
+                    url.push('@');
+                    url.push_str(rev);
+                }
+                Ok(Some(Url::parse(&url)?))
+            }
+        }
+    }
+}
+
+/// Get the URL of the index each package with an index source is installed from, by
+/// normalized package name.
+pub fn package_index_urls(
+    indexes: &IndexMap<String, NamedIndex>,
+    sources: &IndexMap<String, PackageSource>,
+) -> HuakResult<IndexMap<String, String>> {
+    let mut urls = IndexMap::new();
+    for (name, source) in sources {
+        let PackageSource::Index { index } = source else {
+            continue;
+        };
+        let Some(it) = indexes.get(index) else {
+            return Err(Error::HuakConfigurationError(format!(
+                "the source of {name} is an index named {index}, but no index is named {index}"
+            )));
+        };
+        urls.insert(normalized_package_name(name), it.url.clone());
+    }
+
+    Ok(urls)
+}
+
+/// Get the URL of the named index to use instead of PyPI, if one is the default.
+pub fn default_index_url(
+    indexes: &IndexMap<String, NamedIndex>,
+) -> HuakResult<Option<String>> {
+    let mut defaults = indexes.iter().filter(|(_, it)| it.default);
+    let default = defaults.next();
+    if let Some((name, _)) = defaults.next() {
+        return Err(Error::HuakConfigurationError(format!(
+            "only one index can be the default, but {} and {name} are",
+            default.map(|(it, _)| it.as_str()).unwrap_or_default()
+        )));
+    }
+
+    Ok(default.map(|(_, it)| it.url.clone()))
+}
+
+/// Replace packages that have a path or git `PackageSource` with direct references to
+/// the source. Paths are resolved from `root`.
+///
+/// Their extras and markers are kept. Packages that already are direct references or
+/// aren't valid requirements are left as they are.
+pub fn apply_sources(
+    packages: &[String],
+    sources: &IndexMap<String, PackageSource>,
+    root: &Path,
+) -> HuakResult<Vec<String>> {
+    if sources.is_empty() {
+        return Ok(packages.to_vec());
+    }
+    let sources = sources
+        .iter()
+        .map(|(name, it)| (normalized_package_name(name), it))
+        .collect::<IndexMap<_, _>>();
+
+    let mut sourced = Vec::with_capacity(packages.len());
+    for package in packages {
+        let Ok(mut requirement) = Requirement::from_str(package) else {
+            sourced.push(package.clone());
+            continue;
+        };
+        let url = match sources.get(&normalized_package_name(&requirement.name))
+        {
+            Some(source)
+                if !matches!(
+                    requirement.version_or_url,
+                    Some(VersionOrUrl::Url(_))
+                ) =>
+            {
+                source.url(root)?
+            }
+            _ => None,
+        };
+        match url {
+            Some(url) => {
+                requirement.version_or_url = Some(VersionOrUrl::Url(url));
+                sourced.push(requirement.to_string());
+            }
+            None => sourced.push(package.clone()),
+        }
+    }
+
+    Ok(sourced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_resources_dir_path;
+
+    #[test]
+    fn sources() {
+        let indexes: IndexMap<String, NamedIndex> = toml::from_str(
+            r#"
+mirror = { url = "https://mirror.example.com/simple", default = true }
+internal = { url = "https://pypi.example.com/simple" }
+"#,
+        )
+        .unwrap();
+        let sources: IndexMap<String, PackageSource> = toml::from_str(
+            r#"
+Our_Lib = { index = "internal" }
+mock-project = { path = "mock-project" }
+ourapi = { git = "https://github.com/example/ourapi", rev = "v1.2.0" }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            default_index_url(&indexes).unwrap().as_deref(),
+            Some("https://mirror.example.com/simple")
+        );
+        assert_eq!(
+            package_index_urls(&indexes, &sources).unwrap()["our-lib"],
+            "https://pypi.example.com/simple"
+        );
+        let packages = [
+            "our-lib>=1",
+            "mock_project[dev]>=0.1; python_version >= '3.8'",
+            "ourapi",
+            "click==8.1.3",
+        ]
+        .map(ToString::to_string);
+        let sourced =
+            apply_sources(&packages, &sources, &test_resources_dir_path())
+                .unwrap();
+        let project = std::fs::canonicalize(
+            test_resources_dir_path().join("mock-project"),
+        )
+        .unwrap();
+
+        assert_eq!(sourced[0], "our-lib>=1");
+        assert_eq!(
+            sourced[1],
+            format!(
+                "mock_project[dev] @ {} ; python_version >= '3.8'",
+                Url::from_file_path(project).unwrap()
+            )
+        );
+        assert_eq!(
+            sourced[2],
+            "ourapi @ git+https://github.com/example/ourapi@v1.2.0"
+        );
+        assert_eq!(sourced[3], "click==8.1.3");
+    }
+
+    #[test]
+    fn sources_with_unknown_index() {
+        let sources: IndexMap<String, PackageSource> =
+            toml::from_str(r#"ourlib = { index = "internal" }"#).unwrap();
+
+        assert!(package_index_urls(&IndexMap::new(), &sources).is_err());
+    }
+}
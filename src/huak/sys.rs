@@ -41,6 +41,7 @@ pub enum Verbosity {
     Quiet,
 }
 
+#[allow(dead_code)]
 pub trait ToTerminal {
     /// Get a `Terminal`.
     fn to_terminal(&self) -> Terminal;
@@ -116,6 +117,12 @@ impl Terminal {
         }
     }
 
+    /// Prints a message to stdout without a status. Use this for output meant to be read by
+    /// other programs (like JSON). It's printed regardless of verbosity.
+    pub fn print_stdout<T: Display>(&mut self, message: T) -> HuakResult<()> {
+        self.output.message_stdout(&message)
+    }
+
     /// Set the verbosity level.
     pub fn set_verbosity(&mut self, verbosity: Verbosity) {
         self.verbosity = verbosity;
@@ -191,7 +198,7 @@ impl Default for Terminal {
     }
 }
 
-fn trim_error_prefix(msg: &str) -> &str {
+pub fn trim_error_prefix(msg: &str) -> &str {
     msg.trim_start_matches("error:")
         .trim_start_matches("ERROR:")
         .trim_start()
@@ -201,7 +208,6 @@ fn trim_error_prefix(msg: &str) -> &str {
 enum TerminalOut {
     /// Color-enabled stdio with information on whether color should be used
     Stream {
-        #[allow(dead_code)]
         stdout: StandardStream,
         stderr: StandardStream,
     },
@@ -240,6 +246,16 @@ impl TerminalOut {
         }
         Ok(())
     }
+
+    /// Prints out a message to stdout.
+    fn message_stdout(&mut self, message: &dyn Display) -> HuakResult<()> {
+        match *self {
+            TerminalOut::Stream { ref mut stdout, .. } => {
+                writeln!(stdout, "{message}")?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Gets the name of the current shell.
@@ -4,6 +4,7 @@ use regex::{Captures, Regex};
 
 use crate::{Error, HuakResult};
 
+#[allow(dead_code)]
 /// A trait used to convert a struct to `SemVer`.
 trait ToSemVer {
     /// Convert to `SemVer` (MAJOR.MINOR.PATCH).
@@ -34,6 +35,7 @@ impl Display for Version {
     }
 }
 
+#[allow(dead_code)]
 struct SemVer {
     major: usize,
     minor: usize,
@@ -88,7 +90,7 @@ impl FromStr for Version {
 }
 
 /// Use regex to capture potential `Version` numbers from a `&str`.
-fn captures_version_str(s: &str) -> HuakResult<Captures> {
+fn captures_version_str(s: &str) -> HuakResult<Captures<'_>> {
     let re = Regex::new(r"^(\d+)(?:\.(\d+))?(?:\.(\d+))?$")?;
     let captures = match re.captures(s) {
         Some(captures) => captures,
@@ -2,6 +2,7 @@ use crate::package::Package;
 use crate::{
     environment::Environment,
     fs,
+    lockfile::default_lockfile_name,
     metadata::LocalMetadata,
     python_environment::{default_venv_name, venv_config_file_name},
     Config, Error, HuakResult, PythonEnvironment,
@@ -71,6 +72,14 @@ impl Workspace {
         Ok(metadata)
     }
 
+    /// Get the path to the current `Lockfile`. The `Lockfile` lives next to the current
+    /// `LocalMetadata` file.
+    pub fn current_lockfile_path(&self) -> HuakResult<PathBuf> {
+        let package_root = find_package_root(&self.config.cwd, &self.root)?;
+
+        Ok(package_root.join(default_lockfile_name()))
+    }
+
     /// Resolve a `PythonEnvironment` pulling the current or creating one if none is found.
     pub fn resolve_python_environment(&self) -> HuakResult<PythonEnvironment> {
         // NOTE: Currently only virtual environments are supported. We search for them, stopping